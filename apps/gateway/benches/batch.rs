@@ -0,0 +1,84 @@
+//! Allocation benchmark: per-event vs batched serialization
+//!
+//! Run with `cargo bench --bench batch`. Counts heap allocations through a
+//! wrapping global allocator and reports wall time for encoding a burst of
+//! member events one-by-one versus into a single JSON array.
+
+use arrakis_gateway::events::{encode_batch, EventType, GatewayEvent, ShardId};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const BURST: usize = 1_000;
+const ROUNDS: u32 = 100;
+
+fn burst() -> Vec<GatewayEvent> {
+    (0..BURST as u64)
+        .map(|i| GatewayEvent {
+            event_id: format!("00000000-0000-4000-8000-{i:012}"),
+            event_type: EventType::MemberUpdate,
+            shard_id: ShardId::ZERO,
+            timestamp: 1700000000000 + i,
+            guild_id: Some("123456789012345678".to_string()),
+            channel_id: None,
+            user_id: Some((987654321098765432 + i).to_string()),
+            data: serde_json::json!({
+                "roles": ["111111111111111111", "222222222222222222"],
+                "nick": "testnick",
+            }),
+            sequence: Some(i + 1),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Run `f` once to count allocations, then `ROUNDS` times for timing
+fn measure<T>(label: &str, mut f: impl FnMut() -> T) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    black_box(f());
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        black_box(f());
+    }
+    let per_round = start.elapsed() / ROUNDS;
+
+    println!("{label:<10} {allocations:>6} allocations  {per_round:>10.2?} per {BURST}-event burst");
+}
+
+fn main() {
+    let events = burst();
+
+    measure("per-event", || {
+        events
+            .iter()
+            .map(|event| serde_json::to_vec(event).expect("serializable"))
+            .collect::<Vec<_>>()
+    });
+    measure("batched", || encode_batch(&events));
+}
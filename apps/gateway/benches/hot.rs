@@ -0,0 +1,105 @@
+//! Throughput benchmark: fixed-schema vs dynamic serialization
+//!
+//! Run with `cargo bench --bench hot`. Serializes a burst of typing and
+//! presence events to JSON bytes with `fixed_schema_hot_events` off (a
+//! `serde_json::Value` tree per event) and on (concrete structs), and
+//! reports heap allocations and wall time for each.
+
+use arrakis_gateway::events::{serialize_event_bytes, SerializeConfig, ShardId};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+use twilight_model::gateway::event::Event;
+use twilight_model::gateway::payload::incoming::{PresenceUpdate, TypingStart};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const BURST: usize = 1_000;
+const ROUNDS: u32 = 100;
+
+/// Alternating typing and Spotify presence events
+fn burst() -> Vec<Event> {
+    (0..BURST as u64)
+        .map(|i| {
+            let user_id = (987654321098765432 + i).to_string();
+            if i % 2 == 0 {
+                let typing: TypingStart = serde_json::from_value(serde_json::json!({
+                    "channel_id": "333333333333333333",
+                    "guild_id": "123456789012345678",
+                    "user_id": user_id,
+                    "timestamp": 1700000000 + i,
+                }))
+                .expect("valid TYPING_START");
+                Event::TypingStart(Box::new(typing))
+            } else {
+                let presence: PresenceUpdate = serde_json::from_value(serde_json::json!({
+                    "guild_id": "123456789012345678",
+                    "user": { "id": user_id },
+                    "status": "online",
+                    "activities": [{
+                        "name": "Spotify",
+                        "type": 2,
+                        "details": "Leaving Caladan",
+                        "state": "Hans Zimmer",
+                        "created_at": 1700000000000u64,
+                    }],
+                    "client_status": { "desktop": "online" },
+                }))
+                .expect("valid PRESENCE_UPDATE");
+                Event::PresenceUpdate(Box::new(presence))
+            }
+        })
+        .collect()
+}
+
+/// Run `f` once to count allocations, then `ROUNDS` times for timing
+fn measure<T>(label: &str, mut f: impl FnMut() -> T) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    black_box(f());
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        black_box(f());
+    }
+    let per_round = start.elapsed() / ROUNDS;
+
+    println!("{label:<10} {allocations:>6} allocations  {per_round:>10.2?} per {BURST}-event burst");
+}
+
+fn main() {
+    let events = burst();
+    let dynamic = SerializeConfig::default();
+    let fixed = SerializeConfig { fixed_schema_hot_events: true, ..Default::default() };
+
+    for (label, config) in [("dynamic", &dynamic), ("fixed", &fixed)] {
+        measure(label, || {
+            events
+                .iter()
+                .map(|event| serialize_event_bytes(event, ShardId::ZERO, config).expect("forwarded"))
+                .collect::<Vec<_>>()
+        });
+    }
+}
@@ -0,0 +1,198 @@
+//! Payload compression codecs
+//!
+//! Large payloads (full-guild `guild.join`, batches) compress well, but not
+//! every consumer can decompress every format. A [`Codec`] pairs
+//! `compress`/`decompress` with a one-byte id; the publisher stamps the id
+//! in the [`CODEC_HEADER`] message header and consumers pick the matching
+//! decompressor with [`from_id`]. Identity is the default and publishes
+//! without the header, so existing consumers see no change.
+
+use crate::error::GatewayError;
+use flate2::read::{GzDecoder, GzEncoder};
+use std::fmt::Debug;
+use std::io::Read;
+
+/// NATS header carrying the codec id of a compressed payload
+pub const CODEC_HEADER: &str = "Arrakis-Codec";
+
+/// Compression format for published payloads
+pub trait Codec: Debug + Send + Sync {
+    /// Id stamped in [`CODEC_HEADER`]; stable across releases
+    fn id(&self) -> u8;
+
+    /// Name accepted by `PAYLOAD_CODEC`
+    fn name(&self) -> &'static str;
+
+    fn compress(&self, payload: &[u8]) -> Result<Vec<u8>, GatewayError>;
+
+    fn decompress(&self, payload: &[u8]) -> Result<Vec<u8>, GatewayError>;
+}
+
+/// No compression
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Identity;
+
+impl Codec for Identity {
+    fn id(&self) -> u8 {
+        0
+    }
+
+    fn name(&self) -> &'static str {
+        "identity"
+    }
+
+    fn compress(&self, payload: &[u8]) -> Result<Vec<u8>, GatewayError> {
+        Ok(payload.to_vec())
+    }
+
+    fn decompress(&self, payload: &[u8]) -> Result<Vec<u8>, GatewayError> {
+        Ok(payload.to_vec())
+    }
+}
+
+/// gzip (RFC 1952), for consumers limited to standard library codecs
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Gzip;
+
+impl Codec for Gzip {
+    fn id(&self) -> u8 {
+        1
+    }
+
+    fn name(&self) -> &'static str {
+        "gzip"
+    }
+
+    fn compress(&self, payload: &[u8]) -> Result<Vec<u8>, GatewayError> {
+        let mut compressed = Vec::new();
+        GzEncoder::new(payload, flate2::Compression::default())
+            .read_to_end(&mut compressed)
+            .map_err(|source| codec_error(self, source))?;
+        Ok(compressed)
+    }
+
+    fn decompress(&self, payload: &[u8]) -> Result<Vec<u8>, GatewayError> {
+        let mut decompressed = Vec::new();
+        GzDecoder::new(payload)
+            .read_to_end(&mut decompressed)
+            .map_err(|source| codec_error(self, source))?;
+        Ok(decompressed)
+    }
+}
+
+/// zstd, the better ratio and speed where consumers support it
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Zstd;
+
+/// zstd's default level; higher levels cost publish latency
+const ZSTD_LEVEL: i32 = 3;
+
+impl Codec for Zstd {
+    fn id(&self) -> u8 {
+        2
+    }
+
+    fn name(&self) -> &'static str {
+        "zstd"
+    }
+
+    fn compress(&self, payload: &[u8]) -> Result<Vec<u8>, GatewayError> {
+        zstd::encode_all(payload, ZSTD_LEVEL).map_err(|source| codec_error(self, source))
+    }
+
+    fn decompress(&self, payload: &[u8]) -> Result<Vec<u8>, GatewayError> {
+        zstd::decode_all(payload).map_err(|source| codec_error(self, source))
+    }
+}
+
+const CODECS: &[&dyn Codec] = &[&Identity, &Gzip, &Zstd];
+
+/// Look up a codec by the id stamped in [`CODEC_HEADER`]
+pub fn from_id(id: u8) -> Result<&'static dyn Codec, GatewayError> {
+    CODECS
+        .iter()
+        .copied()
+        .find(|codec| codec.id() == id)
+        .ok_or_else(|| GatewayError::UnknownCodec(id.to_string()))
+}
+
+/// Look up a codec by name (`identity`, `gzip`, `zstd`)
+pub fn from_name(name: &str) -> Result<&'static dyn Codec, GatewayError> {
+    CODECS
+        .iter()
+        .copied()
+        .find(|codec| codec.name() == name)
+        .ok_or_else(|| GatewayError::UnknownCodec(name.to_string()))
+}
+
+/// Decompress a received payload given its [`CODEC_HEADER`] value
+///
+/// A missing header means the payload was published uncompressed.
+pub fn decode(header: Option<&str>, payload: &[u8]) -> Result<Vec<u8>, GatewayError> {
+    let codec = match header {
+        None => &Identity,
+        Some(value) => {
+            let id = value
+                .trim()
+                .parse()
+                .map_err(|_| GatewayError::UnknownCodec(value.to_string()))?;
+            from_id(id)?
+        }
+    };
+    codec.decompress(payload)
+}
+
+fn codec_error(codec: &dyn Codec, source: std::io::Error) -> GatewayError {
+    GatewayError::Codec {
+        codec: codec.name(),
+        source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload() -> Vec<u8> {
+        let event = serde_json::json!({
+            "event_type": "guild.join",
+            "data": { "channels": vec!["333333333333333333"; 200] },
+        });
+        serde_json::to_vec(&event).unwrap()
+    }
+
+    #[test]
+    fn every_codec_round_trips() {
+        let payload = payload();
+        for codec in CODECS {
+            let compressed = codec.compress(&payload).unwrap();
+            assert_eq!(codec.decompress(&compressed).unwrap(), payload, "{} round trip", codec.name());
+        }
+        assert!(Zstd.compress(&payload).unwrap().len() < payload.len() / 4);
+        assert!(Gzip.compress(&payload).unwrap().len() < payload.len() / 4);
+    }
+
+    #[test]
+    fn codec_id_selects_the_decompressor() {
+        let payload = payload();
+        for codec in CODECS {
+            let compressed = codec.compress(&payload).unwrap();
+            let header = codec.id().to_string();
+            assert_eq!(decode(Some(&header), &compressed).unwrap(), payload);
+        }
+
+        // No header: published uncompressed
+        assert_eq!(decode(None, &payload).unwrap(), payload);
+        // Right bytes, wrong codec id
+        let zstd = Zstd.compress(&payload).unwrap();
+        assert!(matches!(decode(Some("1"), &zstd), Err(GatewayError::Codec { codec: "gzip", .. })));
+    }
+
+    #[test]
+    fn unknown_codecs_are_rejected() {
+        assert!(matches!(from_id(9), Err(GatewayError::UnknownCodec(_))));
+        assert!(matches!(from_name("brotli"), Err(GatewayError::UnknownCodec(_))));
+        assert!(matches!(decode(Some("zstd"), b""), Err(GatewayError::UnknownCodec(_))));
+        assert_eq!(from_name("zstd").unwrap().id(), 2);
+    }
+}
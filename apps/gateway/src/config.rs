@@ -3,8 +3,19 @@
 //! Sprint S-4: Enhanced configuration for shard pools and NATS
 //! Handles loading configuration from environment variables.
 
+use crate::codec::{self, Codec};
 use crate::error::GatewayError;
+use crate::events::anomaly::DEFAULT_WINDOW;
+use crate::events::channel_filter::parse_channel_ids;
+use crate::events::guild_filter::parse_guild_ids;
+use crate::events::{AnomalyConfig, EngagementWeights, GuildIdHasher, LogicalClock, RenameMap, SerializeConfig, SerializeFilter, SharedLogicalClock, TimestampUnit};
+use crate::nats::backpressure::{DEFAULT_POLL_INTERVAL, DEFAULT_THRESHOLD};
+use crate::nats::{streams, BackpressureConfig, DEFAULT_BUFFER_CAPACITY};
+use crate::shard::DEFAULT_SNAPSHOT_INTERVAL;
 use std::env;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 use twilight_gateway::Intents;
 
 /// Gateway configuration
@@ -28,6 +39,53 @@ pub struct GatewayConfig {
 
     /// Log level (trace, debug, info, warn, error)
     pub log_level: String,
+
+    /// File backing the failed-publish buffer (None = buffering disabled)
+    pub publish_buffer_path: Option<PathBuf>,
+
+    /// Maximum events held in the failed-publish buffer
+    pub publish_buffer_capacity: usize,
+
+    /// Directory of per-shard serializer state snapshots, restored on
+    /// restart (None = every start begins fresh)
+    pub state_dir: Option<PathBuf>,
+
+    /// Interval between periodic serializer state snapshots
+    pub state_snapshot_interval: Duration,
+
+    /// Longest wait for a publish's JetStream ack before the event is
+    /// dead-lettered (None = wait indefinitely)
+    pub publish_timeout: Option<Duration>,
+
+    /// Events whose `data` exceeds this many bytes have it published on
+    /// its own subject (None = always inline)
+    pub split_data_threshold: Option<usize>,
+
+    /// Publish on priority-encoded subjects
+    /// (`discord.{priority}.{category}.{event_type}`) instead of the flat ones
+    pub priority_subjects: bool,
+
+    /// Event payload options passed to the serializer
+    pub serialize: SerializeConfig,
+
+    /// Compression applied to published payloads (identity = none)
+    pub payload_codec: &'static dyn Codec,
+
+    /// Shed low-priority events while this consumer lags (None = never shed)
+    pub backpressure: Option<BackpressureConfig>,
+
+    /// Also write every event to stdout as NDJSON (logs move to stderr)
+    pub stdout_events: bool,
+
+    /// Subscribe to guild message and reaction events (adds the
+    /// GUILD_MESSAGES and GUILD_MESSAGE_REACTIONS intents)
+    pub message_events: bool,
+
+    /// Subscribe to presence updates (adds the privileged GUILD_PRESENCES intent)
+    pub presence_events: bool,
+
+    /// Subscribe to typing indicators (adds the GUILD_MESSAGE_TYPING intent)
+    pub typing_events: bool,
 }
 
 impl GatewayConfig {
@@ -63,6 +121,137 @@ impl GatewayConfig {
 
         let log_level = env::var("LOG_LEVEL").unwrap_or_else(|_| "info".to_string());
 
+        let publish_buffer_path = env::var("PUBLISH_BUFFER_PATH").ok().map(PathBuf::from);
+
+        let publish_buffer_capacity = env::var("PUBLISH_BUFFER_CAPACITY")
+            .map(|v| v.parse())
+            .unwrap_or(Ok(DEFAULT_BUFFER_CAPACITY))
+            .map_err(|e| GatewayError::Config(format!("PUBLISH_BUFFER_CAPACITY must be a valid number: {e}")))?;
+
+        let state_dir = env::var("STATE_DIR").ok().map(PathBuf::from);
+
+        let state_snapshot_interval = match env::var("STATE_SNAPSHOT_INTERVAL_SECS") {
+            Ok(secs) => match secs.parse() {
+                Ok(0) => return Err(GatewayError::Config("STATE_SNAPSHOT_INTERVAL_SECS must be positive".to_string())),
+                Ok(secs) => Duration::from_secs(secs),
+                Err(e) => {
+                    return Err(GatewayError::Config(format!(
+                        "STATE_SNAPSHOT_INTERVAL_SECS must be a valid number: {e}"
+                    )))
+                }
+            },
+            Err(_) => DEFAULT_SNAPSHOT_INTERVAL,
+        };
+
+        let publish_timeout = env::var("PUBLISH_TIMEOUT_MS")
+            .ok()
+            .map(|ms| ms.parse().map(Duration::from_millis))
+            .transpose()
+            .map_err(|e| GatewayError::Config(format!("PUBLISH_TIMEOUT_MS must be a valid number: {e}")))?;
+
+        let split_data_threshold = env::var("PUBLISH_SPLIT_DATA_BYTES")
+            .ok()
+            .map(|bytes| bytes.parse())
+            .transpose()
+            .map_err(|e| GatewayError::Config(format!("PUBLISH_SPLIT_DATA_BYTES must be a valid number: {e}")))?;
+
+        let priority_subjects = env_flag("PUBLISH_PRIORITY_SUBJECTS")?;
+
+        let anomaly = match env::var("ANOMALY_THRESHOLD") {
+            Ok(threshold) => {
+                let threshold = threshold.parse()
+                    .map_err(|e| GatewayError::Config(format!("ANOMALY_THRESHOLD must be a valid number: {e}")))?;
+                let window = env::var("ANOMALY_WINDOW_SECS")
+                    .map(|v| v.parse().map(Duration::from_secs))
+                    .unwrap_or(Ok(DEFAULT_WINDOW))
+                    .map_err(|e| GatewayError::Config(format!("ANOMALY_WINDOW_SECS must be a valid number: {e}")))?;
+                Some(AnomalyConfig { threshold, window })
+            }
+            Err(_) => None,
+        };
+
+        let dedup_window = env::var("SERIALIZE_DEDUP_WINDOW_MS")
+            .ok()
+            .map(|ms| ms.parse().map(Duration::from_millis))
+            .transpose()
+            .map_err(|e| GatewayError::Config(format!("SERIALIZE_DEDUP_WINDOW_MS must be a valid number: {e}")))?;
+
+        let serialize = SerializeConfig {
+            include_resolved: env_flag("SERIALIZE_INCLUDE_RESOLVED")?,
+            omit_unchanged_roles: env_flag("SERIALIZE_OMIT_UNCHANGED_ROLES")?,
+            role_diffs: env_flag("SERIALIZE_ROLE_DIFFS")?,
+            flat_data: env_flag("SERIALIZE_FLAT_DATA")?,
+            anomaly,
+            dedup_window,
+            include_shard_info: env_flag("SERIALIZE_INCLUDE_SHARD_INFO")?,
+            capture_message_content: env_flag("SERIALIZE_CAPTURE_MESSAGE_CONTENT")?,
+            include_components: env_flag("SERIALIZE_INCLUDE_COMPONENTS")?,
+            detect_language: env_flag("SERIALIZE_DETECT_LANGUAGE")?,
+            join_after_screening: env_flag("SERIALIZE_JOIN_AFTER_SCREENING")?,
+            timeout_expiry: env_flag("SERIALIZE_TIMEOUT_EXPIRY")?,
+            leave_on_ban: env_flag("SERIALIZE_LEAVE_ON_BAN")?,
+            compact_presence: env_flag("SERIALIZE_COMPACT_PRESENCE")?,
+            guild_update_deltas: env_flag("SERIALIZE_GUILD_UPDATE_DELTAS")?,
+            forward_reconnects: env_flag("SERIALIZE_FORWARD_RECONNECTS")?,
+            include_cache_epoch: env_flag("SERIALIZE_INCLUDE_CACHE_EPOCH")?,
+            include_timestamp_ns: env_flag("SERIALIZE_INCLUDE_TIMESTAMP_NS")?,
+            timestamp_unit: TimestampUnit::parse(&env::var("SERIALIZE_TIMESTAMP_UNIT").unwrap_or_else(|_| "millis".to_string()))?,
+            include_producer_version: env_flag("SERIALIZE_INCLUDE_PRODUCER_VERSION")?,
+            include_schema_fingerprint: env_flag("SERIALIZE_INCLUDE_SCHEMA_FINGERPRINT")?,
+            include_intent: env_flag("SERIALIZE_INCLUDE_INTENT")?,
+            deterministic_ids: env_flag("SERIALIZE_DETERMINISTIC_IDS")?,
+            protocol_debug: env_flag("SERIALIZE_PROTOCOL_DEBUG")?,
+            renames: RenameMap::parse(&env::var("EVENT_TYPE_RENAMES").unwrap_or_default())?,
+            engagement_weights: EngagementWeights::parse(&env::var("ENGAGEMENT_WEIGHTS").unwrap_or_default())?,
+            // One clock for the whole process; every shard's config clone shares it
+            logical_clock: env_flag("SERIALIZE_LOGICAL_CLOCK")?
+                .then(|| Arc::new(SharedLogicalClock::new()) as Arc<dyn LogicalClock>),
+            // Library hooks; not configurable from the environment
+            id_mapper: None,
+            compress_threshold: None,
+            fixed_schema_hot_events: false,
+            metrics: None,
+            guild_allowlist: env::var("GUILD_ALLOWLIST").ok().map(|ids| parse_guild_ids(&ids)).transpose()?,
+            guild_denylist: parse_guild_ids(&env::var("GUILD_DENYLIST").unwrap_or_default())?,
+            channel_denylist: parse_channel_ids(&env::var("CHANNEL_DENYLIST").unwrap_or_default())?,
+            guild_id_hashing: env::var("GUILD_ID_HMAC_KEY")
+                .ok()
+                .filter(|key| !key.is_empty())
+                .map(|key| Arc::new(GuildIdHasher::new(key.as_bytes()))),
+            event_filter: env::var("FORWARD_EVENT_TYPES")
+                .ok()
+                .map(|types| SerializeFilter::parse(&types))
+                .transpose()?
+                .unwrap_or_default(),
+        };
+
+        let payload_codec = codec::from_name(&env::var("PAYLOAD_CODEC").unwrap_or_else(|_| "identity".to_string()))?;
+
+        let backpressure = match env::var("BACKPRESSURE_CONSUMER") {
+            Ok(consumer) => {
+                let threshold = env::var("BACKPRESSURE_THRESHOLD")
+                    .map(|v| v.parse())
+                    .unwrap_or(Ok(DEFAULT_THRESHOLD))
+                    .map_err(|e| GatewayError::Config(format!("BACKPRESSURE_THRESHOLD must be a valid number: {e}")))?;
+                let poll_interval = env::var("BACKPRESSURE_POLL_SECS")
+                    .map(|v| v.parse().map(Duration::from_secs))
+                    .unwrap_or(Ok(DEFAULT_POLL_INTERVAL))
+                    .map_err(|e| GatewayError::Config(format!("BACKPRESSURE_POLL_SECS must be a valid number: {e}")))?;
+                Some(BackpressureConfig {
+                    stream: streams::EVENTS.to_string(),
+                    consumer,
+                    threshold,
+                    poll_interval,
+                })
+            }
+            Err(_) => None,
+        };
+
+        let stdout_events = env_flag("STDOUT_EVENTS")?;
+        let message_events = env_flag("MESSAGE_EVENTS")?;
+        let presence_events = env_flag("PRESENCE_EVENTS")?;
+        let typing_events = env_flag("TYPING_EVENTS")?;
+
         Ok(Self {
             discord_token,
             pool_id,
@@ -70,6 +259,20 @@ impl GatewayConfig {
             nats_url,
             http_port,
             log_level,
+            publish_buffer_path,
+            publish_buffer_capacity,
+            state_dir,
+            state_snapshot_interval,
+            publish_timeout,
+            split_data_threshold,
+            priority_subjects,
+            serialize,
+            payload_codec,
+            backpressure,
+            stdout_events,
+            message_events,
+            presence_events,
+            typing_events,
         })
     }
 
@@ -78,9 +281,55 @@ impl GatewayConfig {
     /// Per SDD §5.1.2, we use minimal intents:
     /// - GUILDS: Required for guild lifecycle events
     /// - GUILD_MEMBERS: Required for member events (privileged)
+    /// - GUILD_VOICE_STATES: Required for voice state events
+    /// - AUTO_MODERATION_CONFIGURATION: Required for AutoMod rule lifecycle events
+    /// - GUILD_SCHEDULED_EVENTS: Required for scheduled event RSVP events
+    /// - GUILD_MODERATION: Required for ban events
     /// - GUILD_MESSAGES: Optional, for message-based features
     pub fn intents() -> Intents {
-        Intents::GUILDS | Intents::GUILD_MEMBERS
+        Intents::GUILDS
+            | Intents::GUILD_MEMBERS
+            | Intents::GUILD_VOICE_STATES
+            | Intents::AUTO_MODERATION_CONFIGURATION
+            | Intents::GUILD_SCHEDULED_EVENTS
+            | Intents::GUILD_MODERATION
+    }
+
+    /// Intents for this deployment: the minimal set, plus GUILD_MESSAGES
+    /// and GUILD_MESSAGE_REACTIONS when message events are enabled, plus the privileged MESSAGE_CONTENT
+    /// when a serialize option reads message content (components included),
+    /// plus GUILD_PRESENCES when presence events are enabled, plus
+    /// GUILD_MESSAGE_TYPING when typing events are enabled
+    pub fn enabled_intents(&self) -> Intents {
+        let mut intents = Self::intents();
+        if self.message_events {
+            intents |= Intents::GUILD_MESSAGES | Intents::GUILD_MESSAGE_REACTIONS;
+            let reads_content = self.serialize.detect_language
+                || self.serialize.capture_message_content
+                || self.serialize.include_components;
+            if reads_content {
+                intents |= Intents::MESSAGE_CONTENT;
+            }
+        }
+        if self.presence_events {
+            intents |= Intents::GUILD_PRESENCES;
+        }
+        if self.typing_events {
+            intents |= Intents::GUILD_MESSAGE_TYPING;
+        }
+        intents
+    }
+}
+
+/// Read an opt-in boolean flag (`true`/`1` or `false`/`0`, default false)
+fn env_flag(name: &str) -> Result<bool, GatewayError> {
+    match env::var(name) {
+        Ok(value) => match value.trim().to_ascii_lowercase().as_str() {
+            "1" | "true" => Ok(true),
+            "0" | "false" | "" => Ok(false),
+            other => Err(GatewayError::Config(format!("{name} must be true or false, got '{other}'"))),
+        },
+        Err(_) => Ok(false),
     }
 }
 
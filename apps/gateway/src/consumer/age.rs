@@ -0,0 +1,56 @@
+//! Event age at processing time
+//!
+//! `timestamp` is when the gateway serialized the event, so a consumer's
+//! clock minus it is end-to-end latency: NATS, JetStream redelivery and the
+//! consumer's own queueing. [`record_event_age`] records it under one metric
+//! name and label set, so dashboards can compare consumers directly.
+
+use crate::events::serialize::GatewayEvent;
+use crate::events::timestamp_unit::timestamp_millis;
+use metrics::histogram;
+
+/// Recommended histogram for [`event_age_ms`], labelled by `event_type`
+pub const EVENT_AGE_METRIC: &str = "gateway_consumer_event_age_ms";
+
+/// Milliseconds between the event's `timestamp` and `now_ms` (Unix millis)
+///
+/// Micros events (`timestamp_unit`) are read in milliseconds too.
+///
+/// Clamped to zero when the consumer's clock is behind the gateway's, so
+/// clock skew never reads as negative latency.
+pub fn event_age_ms(event: &GatewayEvent, now_ms: u64) -> i64 {
+    i64::try_from(now_ms.saturating_sub(timestamp_millis(event))).unwrap_or(i64::MAX)
+}
+
+/// Record [`event_age_ms`] to [`EVENT_AGE_METRIC`]
+pub fn record_event_age(event: &GatewayEvent, now_ms: u64) {
+    histogram!(EVENT_AGE_METRIC, "event_type" => event.event_type.to_string())
+        .record(event_age_ms(event, now_ms) as f64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::test_support::gateway_event;
+
+    fn event(timestamp: u64) -> GatewayEvent {
+        gateway_event("member.join").timestamp(timestamp).build()
+    }
+
+    #[test]
+    fn age_is_now_minus_timestamp_clamped_at_zero() {
+        assert_eq!(event_age_ms(&event(1700000000000), 1700000000250), 250);
+        assert_eq!(event_age_ms(&event(1700000000250), 1700000000000), 0, "consumer clock behind");
+        assert_eq!(event_age_ms(&event(0), u64::MAX), i64::MAX);
+    }
+
+    #[test]
+    fn micros_timestamps_are_aged_in_millis() {
+        let micros = GatewayEvent {
+            timestamp: 1700000000000 * 1000,
+            timestamp_unit: Some("micros".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(event_age_ms(&micros, 1700000000250), 250);
+    }
+}
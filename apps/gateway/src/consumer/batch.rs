@@ -0,0 +1,39 @@
+//! Batch expansion
+//!
+//! Producers may publish several events as one [`GatewayEventBatch`].
+//! [`unbatch`] flattens a stream of batches back into single events, in
+//! order, so consumers written for single events take batched input
+//! unchanged.
+
+use crate::events::batch::GatewayEventBatch;
+use crate::events::serialize::GatewayEvent;
+use futures_util::stream::{self, Stream, StreamExt};
+
+/// Expand a stream of batches into a stream of their events
+///
+/// Events keep their batch order, and batches their stream order. Empty
+/// batches contribute nothing.
+pub fn unbatch<S>(batches: S) -> impl Stream<Item = GatewayEvent>
+where
+    S: Stream<Item = GatewayEventBatch>,
+{
+    batches.flat_map(|batch| stream::iter(batch.into_events()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::test_support::event;
+
+    #[test]
+    fn batches_flatten_in_order() {
+        let batches = stream::iter(vec![
+            GatewayEventBatch { events: vec![event("member.join"), event("member.update")] },
+            GatewayEventBatch::default(),
+        ]);
+
+        let events: Vec<_> = tokio_test::block_on(unbatch(batches).collect());
+        let types: Vec<_> = events.iter().map(|e| e.event_type.as_str()).collect();
+        assert_eq!(types, ["member.join", "member.update"]);
+    }
+}
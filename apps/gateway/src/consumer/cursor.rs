@@ -0,0 +1,301 @@
+//! Replay deduplication against a persisted cursor
+//!
+//! JetStream redelivers unacknowledged messages after a consumer restart,
+//! so a consumer can see events it already processed. A [`PersistentCursor`]
+//! durably records the last processed `(producer_epoch, sequence)` per shard
+//! and a [`ReplayFilter`] uses it to skip anything at or below that point.
+//!
+//! The filter relies on one invariant: the gateway never stamps the same
+//! `(producer_epoch, sequence)` on two different events of a shard. A shard
+//! resumes its epoch and sequence only from the state it saved on stopping;
+//! starting fresh or from a periodic snapshot (after a crash) it numbers
+//! from 1 again under a later epoch. That sequence going backwards is a
+//! reset, not a replay: the new epoch's events pass and move the cursor,
+//! while redelivered events from an earlier epoch are still skipped.
+//!
+//! Epochs are Unix milliseconds, so the invariant assumes the producer's
+//! clock doesn't step back past a previous run's epoch between restarts
+//! that lose state.
+//!
+//! The gateway provides the trait and filter; consumers plug in storage.
+//! [`SqliteCursor`] is the default store, [`MemoryCursor`] is for tests and
+//! ephemeral consumers.
+
+use crate::error::GatewayError;
+use crate::events::serialize::GatewayEvent;
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Position of the last processed event of a shard
+///
+/// Ordered by epoch, then sequence. Events from producers that predate
+/// `producer_epoch` have no epoch, which orders before any epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CursorPosition {
+    pub producer_epoch: Option<u64>,
+    pub sequence: u64,
+}
+
+impl CursorPosition {
+    /// The event's position, if it carries a `sequence`
+    pub fn of(event: &GatewayEvent) -> Option<Self> {
+        Some(Self {
+            producer_epoch: event.producer_epoch,
+            sequence: event.sequence?,
+        })
+    }
+}
+
+/// Durable record of the last processed position per shard
+pub trait PersistentCursor {
+    /// Last processed position for a shard, or None if nothing processed yet
+    fn get(&self, shard_id: u64) -> Result<Option<CursorPosition>, GatewayError>;
+
+    /// Record `position` as the last processed event for a shard
+    fn set(&mut self, shard_id: u64, position: CursorPosition) -> Result<(), GatewayError>;
+}
+
+/// In-memory cursor (not durable across process restarts)
+#[derive(Debug, Clone, Default)]
+pub struct MemoryCursor {
+    positions: HashMap<u64, CursorPosition>,
+}
+
+impl PersistentCursor for MemoryCursor {
+    fn get(&self, shard_id: u64) -> Result<Option<CursorPosition>, GatewayError> {
+        Ok(self.positions.get(&shard_id).copied())
+    }
+
+    fn set(&mut self, shard_id: u64, position: CursorPosition) -> Result<(), GatewayError> {
+        self.positions.insert(shard_id, position);
+        Ok(())
+    }
+}
+
+/// SQLite-backed cursor, one row per shard
+pub struct SqliteCursor {
+    conn: Connection,
+}
+
+impl SqliteCursor {
+    /// Open (or create) a cursor database at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, GatewayError> {
+        let conn = Connection::open(path).map_err(|e| cursor_error(None, e))?;
+        Self::with_connection(conn)
+    }
+
+    /// Open a private in-memory database (useful for tests)
+    pub fn open_in_memory() -> Result<Self, GatewayError> {
+        let conn = Connection::open_in_memory().map_err(|e| cursor_error(None, e))?;
+        Self::with_connection(conn)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self, GatewayError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS replay_cursor (
+                shard_id INTEGER PRIMARY KEY,
+                sequence INTEGER NOT NULL,
+                producer_epoch INTEGER
+            )",
+            [],
+        )
+        .map_err(|e| cursor_error(None, e))?;
+
+        // Databases created before producer epochs lack the column; their
+        // rows read back without an epoch
+        let has_epoch = conn
+            .prepare("SELECT 1 FROM pragma_table_info('replay_cursor') WHERE name = 'producer_epoch'")
+            .and_then(|mut stmt| stmt.exists([]))
+            .map_err(|e| cursor_error(None, e))?;
+        if !has_epoch {
+            conn.execute("ALTER TABLE replay_cursor ADD COLUMN producer_epoch INTEGER", [])
+                .map_err(|e| cursor_error(None, e))?;
+        }
+        Ok(Self { conn })
+    }
+}
+
+impl PersistentCursor for SqliteCursor {
+    fn get(&self, shard_id: u64) -> Result<Option<CursorPosition>, GatewayError> {
+        self.conn
+            .query_row(
+                "SELECT producer_epoch, sequence FROM replay_cursor WHERE shard_id = ?1",
+                [shard_id as i64],
+                |row| {
+                    Ok(CursorPosition {
+                        producer_epoch: row.get::<_, Option<i64>>(0)?.map(|e| e as u64),
+                        sequence: row.get::<_, i64>(1)? as u64,
+                    })
+                },
+            )
+            .optional()
+            .map_err(|e| cursor_error(Some(shard_id), e))
+    }
+
+    fn set(&mut self, shard_id: u64, position: CursorPosition) -> Result<(), GatewayError> {
+        self.conn
+            .execute(
+                "INSERT INTO replay_cursor (shard_id, sequence, producer_epoch) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(shard_id) DO UPDATE SET
+                     sequence = excluded.sequence,
+                     producer_epoch = excluded.producer_epoch",
+                rusqlite::params![
+                    shard_id as i64,
+                    position.sequence as i64,
+                    position.producer_epoch.map(|e| e as i64)
+                ],
+            )
+            .map(|_| ())
+            .map_err(|e| cursor_error(Some(shard_id), e))
+    }
+}
+
+/// Skips events already processed according to a [`PersistentCursor`]
+///
+/// Usage: call [`should_process`](Self::should_process) before handling an
+/// event and [`commit`](Self::commit) once it's handled. Events without a
+/// `sequence` (older producers) always pass.
+pub struct ReplayFilter<C: PersistentCursor> {
+    cursor: C,
+}
+
+impl<C: PersistentCursor> ReplayFilter<C> {
+    /// Create a filter over a cursor store
+    pub fn new(cursor: C) -> Self {
+        Self { cursor }
+    }
+
+    /// Returns false if the event is at or below the shard's cursor
+    ///
+    /// An event under a later `producer_epoch` than the cursor's is past
+    /// it whatever its sequence, as positions are never reused (see the
+    /// [module docs](self)).
+    pub fn should_process(&self, event: &GatewayEvent) -> Result<bool, GatewayError> {
+        let Some(position) = CursorPosition::of(event) else {
+            return Ok(true);
+        };
+        Ok(self
+            .cursor
+            .get(event.shard_id.get())?
+            .is_none_or(|last| position > last))
+    }
+
+    /// Record the event as processed
+    pub fn commit(&mut self, event: &GatewayEvent) -> Result<(), GatewayError> {
+        match CursorPosition::of(event) {
+            Some(position) => self.cursor.set(event.shard_id.get(), position),
+            None => Ok(()),
+        }
+    }
+
+    /// Recover the underlying cursor store
+    pub fn into_inner(self) -> C {
+        self.cursor
+    }
+}
+
+fn cursor_error(shard_id: Option<u64>, source: rusqlite::Error) -> GatewayError {
+    GatewayError::CursorStore {
+        shard_id,
+        source: Box::new(source),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::test_support::gateway_event;
+
+    fn event(shard_id: u64, sequence: u64) -> GatewayEvent {
+        gateway_event("member.join").random_id().shard(shard_id, 2).sequence(sequence).build()
+    }
+
+    /// Process a redelivered stream, returning the sequences handled
+    fn consume<C: PersistentCursor>(filter: &mut ReplayFilter<C>, events: &[GatewayEvent]) -> Vec<u64> {
+        let mut handled = Vec::new();
+        for event in events {
+            if filter.should_process(event).unwrap() {
+                handled.push(event.sequence.unwrap());
+                filter.commit(event).unwrap();
+            }
+        }
+        handled
+    }
+
+    #[test]
+    fn resume_skips_already_seen_events() {
+        let stream: Vec<_> = (1..=5).map(|seq| event(0, seq)).collect();
+
+        let mut filter = ReplayFilter::new(MemoryCursor::default());
+        assert_eq!(consume(&mut filter, &stream[..3]), vec![1, 2, 3]);
+
+        // Simulated restart: same durable cursor, JetStream redelivers everything
+        let mut filter = ReplayFilter::new(filter.into_inner());
+        assert_eq!(consume(&mut filter, &stream), vec![4, 5]);
+    }
+
+    #[test]
+    fn cursors_are_per_shard() {
+        let mut filter = ReplayFilter::new(MemoryCursor::default());
+        filter.commit(&event(0, 10)).unwrap();
+
+        assert!(!filter.should_process(&event(0, 10)).unwrap());
+        assert!(filter.should_process(&event(1, 1)).unwrap());
+    }
+
+    #[test]
+    fn events_without_sequence_always_pass() {
+        let mut filter = ReplayFilter::new(MemoryCursor::default());
+        filter.commit(&event(0, 10)).unwrap();
+
+        let legacy = GatewayEvent { sequence: None, ..event(0, 0) };
+        assert!(filter.should_process(&legacy).unwrap());
+    }
+
+    #[test]
+    fn sequence_restarting_under_a_new_epoch_is_a_reset() {
+        let run = |epoch: u64, sequences: std::ops::RangeInclusive<u64>| -> Vec<GatewayEvent> {
+            sequences
+                .map(|seq| GatewayEvent { producer_epoch: Some(epoch), ..event(0, seq) })
+                .collect()
+        };
+        let first = run(1_700_000_000_000, 1..=5);
+        let second = run(1_700_000_600_000, 1..=3);
+
+        let mut filter = ReplayFilter::new(SqliteCursor::open_in_memory().unwrap());
+        assert_eq!(consume(&mut filter, &first), vec![1, 2, 3, 4, 5]);
+        // The producer restarted without its state: numbering begins again
+        assert_eq!(consume(&mut filter, &second[..2]), vec![1, 2]);
+
+        // Redelivery spanning both runs only yields the unseen event
+        let redelivered: Vec<_> = first.iter().chain(&second).cloned().collect();
+        assert_eq!(consume(&mut filter, &redelivered), vec![3]);
+    }
+
+    #[test]
+    fn sqlite_cursor_persists_positions() {
+        let position = |producer_epoch, sequence| CursorPosition { producer_epoch, sequence };
+        let mut cursor = SqliteCursor::open_in_memory().unwrap();
+        assert_eq!(cursor.get(3).unwrap(), None);
+
+        cursor.set(3, position(None, 41)).unwrap();
+        assert_eq!(cursor.get(3).unwrap(), Some(position(None, 41)));
+        cursor.set(3, position(Some(1_700_000_000_000), 1)).unwrap();
+        assert_eq!(cursor.get(3).unwrap(), Some(position(Some(1_700_000_000_000), 1)));
+        assert_eq!(cursor.get(4).unwrap(), None);
+    }
+
+    #[test]
+    fn sqlite_cursor_adds_the_epoch_column_to_older_databases() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE replay_cursor (shard_id INTEGER PRIMARY KEY, sequence INTEGER NOT NULL);
+             INSERT INTO replay_cursor VALUES (0, 7);",
+        )
+        .unwrap();
+
+        let cursor = SqliteCursor::with_connection(conn).unwrap();
+        assert_eq!(cursor.get(0).unwrap(), Some(CursorPosition { producer_epoch: None, sequence: 7 }));
+    }
+}
@@ -0,0 +1,197 @@
+//! Ordered merge across shard streams
+//!
+//! Each shard's events arrive in order, but a consumer reading every shard
+//! sees them interleaved by arrival, and one shard can run behind another.
+//! [`ShardMerger`] buffers events and releases them ordered by
+//! `(timestamp, ingest_order)`, where ingest_order is the order the merger
+//! received them, so events sharing a millisecond come out as they came in.
+//!
+//! Timestamps are compared in milliseconds, so micros events
+//! (`timestamp_unit`) merge with the same window, even alongside millis
+//! ones. The buffer is bounded: an event is held until an event more than
+//! `window_ms` newer has arrived, or until the buffer is over capacity. A
+//! shard that lags by more than the window has its events released late,
+//! out of order, rather than stalling every other shard behind it.
+
+use crate::events::serialize::GatewayEvent;
+use crate::events::timestamp_unit::timestamp_millis;
+use futures_util::stream::{self, Stream, StreamExt};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+/// Reorders events from several shards by `(timestamp, ingest_order)`
+#[derive(Debug)]
+pub struct ShardMerger {
+    window_ms: u64,
+    capacity: usize,
+    pending: BinaryHeap<Reverse<Pending>>,
+    /// Events pushed so far; the next event's ingest_order
+    ingested: u64,
+    /// Newest timestamp pushed so far
+    newest_ms: u64,
+}
+
+#[derive(Debug)]
+struct Pending {
+    /// The event's timestamp in milliseconds
+    timestamp: u64,
+    ingest_order: u64,
+    event: GatewayEvent,
+}
+
+impl Pending {
+    fn key(&self) -> (u64, u64) {
+        (self.timestamp, self.ingest_order)
+    }
+}
+
+impl PartialEq for Pending {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Pending {}
+
+impl PartialOrd for Pending {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Pending {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl ShardMerger {
+    /// Hold events for up to `window_ms` of inter-shard skew, and never
+    /// more than `capacity` of them
+    pub fn new(window_ms: u64, capacity: usize) -> Self {
+        Self {
+            window_ms,
+            capacity,
+            pending: BinaryHeap::new(),
+            ingested: 0,
+            newest_ms: 0,
+        }
+    }
+
+    /// Buffer one event
+    pub fn push(&mut self, event: GatewayEvent) {
+        let timestamp = timestamp_millis(&event);
+        self.newest_ms = self.newest_ms.max(timestamp);
+        self.pending.push(Reverse(Pending {
+            timestamp,
+            ingest_order: self.ingested,
+            event,
+        }));
+        self.ingested += 1;
+    }
+
+    /// The next event in order, if the window or capacity has released it
+    pub fn pop_ready(&mut self) -> Option<GatewayEvent> {
+        let Reverse(oldest) = self.pending.peek()?;
+        let released = self.pending.len() > self.capacity
+            || oldest.timestamp.saturating_add(self.window_ms) < self.newest_ms;
+        released.then(|| self.pop())?
+    }
+
+    /// The next event in order regardless of the window, for flushing once
+    /// the inputs have ended
+    pub fn pop(&mut self) -> Option<GatewayEvent> {
+        self.pending.pop().map(|Reverse(pending)| pending.event)
+    }
+
+    /// Events currently buffered
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Returns true if no events are buffered
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Merge `shards` into one ordered stream
+    ///
+    /// Ends once every shard stream has ended and the buffer is flushed.
+    pub fn merge<S>(self, shards: impl IntoIterator<Item = S>) -> impl Stream<Item = GatewayEvent>
+    where
+        S: Stream<Item = GatewayEvent> + Unpin,
+    {
+        let inputs = stream::select_all(shards);
+        stream::unfold((inputs, self, false), |(mut inputs, mut merger, mut ended)| async move {
+            loop {
+                let next = if ended { merger.pop() } else { merger.pop_ready() };
+                if let Some(event) = next {
+                    return Some((event, (inputs, merger, ended)));
+                }
+                if ended {
+                    return None;
+                }
+                match inputs.next().await {
+                    Some(event) => merger.push(event),
+                    None => ended = true,
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::test_support::gateway_event;
+
+    fn event(shard_id: u64, timestamp: u64) -> GatewayEvent {
+        gateway_event("member.join").shard(shard_id, 2).timestamp(timestamp).build()
+    }
+
+    #[test]
+    fn interleaved_shards_merge_in_timestamp_order() {
+        let shard_0 = stream::iter(vec![event(0, 100), event(0, 400), event(0, 700)]);
+        let shard_1 = stream::iter(vec![event(1, 200), event(1, 300), event(1, 800)]);
+
+        let merged: Vec<_> = tokio_test::block_on(ShardMerger::new(1000, 64).merge([shard_0, shard_1]).collect());
+        let order: Vec<_> = merged.iter().map(|e| (e.shard_id.get(), e.timestamp)).collect();
+        assert_eq!(order, [(0, 100), (1, 200), (1, 300), (0, 400), (0, 700), (1, 800)]);
+    }
+
+    #[test]
+    fn window_and_capacity_bound_the_buffer() {
+        let mut merger = ShardMerger::new(50, 2);
+        merger.push(event(0, 120));
+        merger.push(event(1, 100));
+        assert!(merger.pop_ready().is_none(), "within the skew window");
+
+        merger.push(event(1, 120));
+        let first = merger.pop_ready().expect("over capacity");
+        assert_eq!(first.timestamp, 100);
+        let second = merger.pop().unwrap();
+        assert_eq!((second.shard_id.get(), second.timestamp), (0, 120), "ties keep ingest order");
+
+        merger.push(event(0, 200));
+        assert_eq!(merger.pop_ready().map(|e| e.timestamp), Some(120), "released by an event past the window");
+        assert!(merger.pop_ready().is_none());
+        assert_eq!(merger.len(), 1);
+    }
+
+    #[test]
+    fn micros_events_use_the_same_window() {
+        let micros = |shard_id, timestamp_ms: u64| GatewayEvent {
+            timestamp: timestamp_ms * 1000,
+            timestamp_unit: Some("micros".to_string()),
+            ..event(shard_id, 0)
+        };
+        let mut merger = ShardMerger::new(50, 64);
+        merger.push(micros(0, 120));
+        merger.push(micros(1, 100));
+        merger.push(micros(1, 140));
+        assert!(merger.pop_ready().is_none(), "40ms of skew is within a 50ms window");
+
+        merger.push(event(0, 160));
+        assert_eq!(merger.pop_ready().map(|e| e.timestamp), Some(100_000), "ordered with millis events");
+    }
+}
@@ -0,0 +1,22 @@
+//! Consumer-side helpers
+//!
+//! Utilities for services reading gateway events off NATS. They depend only
+//! on the wire types in `events`, so every consumer handles replays the same
+//! way instead of re-implementing it, can route by `event_type` without
+//! a hand-written match, measure latency under one metric, merge shard
+//! streams back into timestamp order, and spot interaction tokens that
+//! expired in a backlog.
+
+pub mod age;
+pub mod batch;
+pub mod cursor;
+pub mod merge;
+pub mod router;
+pub mod token;
+
+pub use age::{event_age_ms, record_event_age, EVENT_AGE_METRIC};
+pub use batch::unbatch;
+pub use cursor::{CursorPosition, MemoryCursor, PersistentCursor, ReplayFilter, SqliteCursor};
+pub use merge::ShardMerger;
+pub use router::Router;
+pub use token::{redact_expired_tokens, redact_tokens_older_than, EXPIRED_TOKEN, INTERACTION_TOKEN_TTL_MS};
@@ -0,0 +1,97 @@
+//! event_type dispatch for consumers
+//!
+//! Instead of every consumer writing its own `match event.event_type.as_str()`,
+//! register a handler per event type on a [`Router`] and hand it each
+//! decoded [`GatewayEvent`]. Unmatched types go to an optional fallback.
+//!
+//! Handlers return `R`, so a consumer that needs fallible handlers uses
+//! `Router<Result<(), E>>` and propagates the result from
+//! [`dispatch`](Router::dispatch).
+
+use crate::events::serialize::GatewayEvent;
+use std::collections::HashMap;
+
+type Handler<'h, R> = Box<dyn FnMut(&GatewayEvent) -> R + Send + 'h>;
+
+/// Maps `event_type` to handlers
+pub struct Router<'h, R = ()> {
+    handlers: HashMap<String, Handler<'h, R>>,
+    fallback: Option<Handler<'h, R>>,
+}
+
+impl<'h, R> Default for Router<'h, R> {
+    fn default() -> Self {
+        Self {
+            handlers: HashMap::new(),
+            fallback: None,
+        }
+    }
+}
+
+impl<'h, R> Router<'h, R> {
+    /// Create a router with no handlers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handle events of `event_type`, replacing any earlier handler for it
+    pub fn on(mut self, event_type: impl Into<String>, handler: impl FnMut(&GatewayEvent) -> R + Send + 'h) -> Self {
+        self.handlers.insert(event_type.into(), Box::new(handler));
+        self
+    }
+
+    /// Handle events no other handler matched
+    pub fn fallback(mut self, handler: impl FnMut(&GatewayEvent) -> R + Send + 'h) -> Self {
+        self.fallback = Some(Box::new(handler));
+        self
+    }
+
+    /// Run the handler for the event's type, else the fallback
+    ///
+    /// Returns None if neither exists.
+    pub fn dispatch(&mut self, event: &GatewayEvent) -> Option<R> {
+        let handler = self
+            .handlers
+            .get_mut(event.event_type.as_str())
+            .or(self.fallback.as_mut())?;
+        Some(handler(event))
+    }
+
+    /// Returns true if a handler (not the fallback) is registered for `event_type`
+    pub fn handles(&self, event_type: &str) -> bool {
+        self.handlers.contains_key(event_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::test_support::event;
+
+    #[test]
+    fn each_event_runs_its_own_handler() {
+        let (mut joins, mut leaves) = (0, 0);
+        let mut router = Router::new()
+            .on("member.join", |_| joins += 1)
+            .on("member.leave", |_| leaves += 1);
+
+        assert!(router.dispatch(&event("member.join")).is_some());
+        assert!(router.dispatch(&event("member.join")).is_some());
+        assert!(router.dispatch(&event("member.leave")).is_some());
+        assert!(router.dispatch(&event("guild.join")).is_none(), "no fallback registered");
+        drop(router);
+
+        assert_eq!((joins, leaves), (2, 1));
+    }
+
+    #[test]
+    fn unmatched_types_go_to_fallback() {
+        let mut router = Router::new()
+            .on("member.join", |_| "join".to_string())
+            .fallback(|e| format!("unhandled {}", e.event_type));
+
+        assert_eq!(router.dispatch(&event("member.join")).as_deref(), Some("join"));
+        assert_eq!(router.dispatch(&event("guild.anomaly")).as_deref(), Some("unhandled guild.anomaly"));
+        assert!(!router.handles("guild.anomaly"));
+    }
+}
@@ -0,0 +1,88 @@
+//! Expired interaction token redaction
+//!
+//! Discord accepts responses on an `interaction_token` for 15 minutes after
+//! the interaction. An event that waited longer in a JetStream backlog or
+//! the failed-publish buffer still carries the token, and a worker that
+//! responds with it gets an opaque API rejection. [`redact_expired_tokens`]
+//! swaps such a token for [`EXPIRED_TOKEN`] and flags the event with
+//! `interaction_token_expired`, so workers can skip the response upfront.
+
+use crate::events::serialize::{snowflake_timestamp_ms, GatewayEvent};
+
+/// How long Discord accepts responses on an interaction token
+pub const INTERACTION_TOKEN_TTL_MS: u64 = 15 * 60 * 1000;
+
+/// Stands in for an `interaction_token` past its TTL
+pub const EXPIRED_TOKEN: &str = "<expired>";
+
+/// Redact `event`'s `interaction_token` if the interaction is older than
+/// [`INTERACTION_TOKEN_TTL_MS`] at `now_ms` (Unix millis)
+pub fn redact_expired_tokens(event: &mut GatewayEvent, now_ms: u64) {
+    redact_tokens_older_than(event, now_ms, INTERACTION_TOKEN_TTL_MS);
+}
+
+/// [`redact_expired_tokens`] with a custom TTL, for consumers that want a
+/// margin to respond before Discord's deadline
+///
+/// The interaction's age comes from its `interaction_id` snowflake, not the
+/// envelope `timestamp`. Events without an interaction token are untouched.
+pub fn redact_tokens_older_than(event: &mut GatewayEvent, now_ms: u64, ttl_ms: u64) {
+    let Some(data) = event.data.as_object_mut() else {
+        return;
+    };
+    let Some(created_ms) = data
+        .get("interaction_id")
+        .and_then(|id| id.as_str())
+        .and_then(|id| id.parse().ok())
+        .map(snowflake_timestamp_ms)
+    else {
+        return;
+    };
+    if !data.get("interaction_token").is_some_and(|token| token.is_string()) {
+        return;
+    }
+    if now_ms.saturating_sub(created_ms) > ttl_ms {
+        data.insert("interaction_token".to_string(), EXPIRED_TOKEN.into());
+        data.insert("interaction_token_expired".to_string(), true.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::test_support::gateway_event;
+
+    /// Interaction snowflake 123456789012345678 was created at this Unix ms
+    const CREATED_MS: u64 = 1449504792216;
+
+    fn interaction() -> GatewayEvent {
+        gateway_event("interaction.create")
+            .data(serde_json::json!({
+                "interaction_id": "123456789012345678",
+                "interaction_type": "application_command",
+                "interaction_token": "aW50ZXJhY3Rpb24tdG9rZW4",
+            }))
+            .build()
+    }
+
+    #[test]
+    fn fresh_token_is_kept() {
+        assert_eq!(snowflake_timestamp_ms(123456789012345678), CREATED_MS);
+        let mut event = interaction();
+        redact_expired_tokens(&mut event, CREATED_MS + INTERACTION_TOKEN_TTL_MS);
+        assert_eq!(event.data, interaction().data);
+    }
+
+    #[test]
+    fn expired_token_is_redacted() {
+        let mut event = interaction();
+        redact_expired_tokens(&mut event, CREATED_MS + INTERACTION_TOKEN_TTL_MS + 1);
+        assert_eq!(event.data["interaction_token"], EXPIRED_TOKEN);
+        assert_eq!(event.data["interaction_token_expired"], true);
+        assert_eq!(event.data["interaction_id"], "123456789012345678");
+
+        let mut early = interaction();
+        redact_tokens_older_than(&mut early, CREATED_MS + 60_000, 30_000);
+        assert_eq!(early.data["interaction_token"], EXPIRED_TOKEN, "custom TTL");
+    }
+}
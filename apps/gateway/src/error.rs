@@ -47,6 +47,10 @@ pub enum GatewayError {
         source: Box<dyn std::error::Error + Send + Sync>,
     },
 
+    /// NATS publish got no ack within the configured publish timeout
+    #[error("NATS publish for subject '{subject}' timed out after {timeout_ms}ms")]
+    PublishTimeout { subject: String, timeout_ms: u64 },
+
     /// NATS connection failed
     #[error("NATS connection failed")]
     NatsConnectionFailed(#[source] Box<dyn std::error::Error + Send + Sync>),
@@ -67,6 +71,71 @@ pub enum GatewayError {
     /// Shard ID overflow: u64 value exceeds u32::MAX (Twilight API boundary)
     #[error("shard ID overflow: {value} exceeds u32::MAX")]
     ShardIdOverflow { value: u64 },
+
+    /// Shard id outside the configured shard count
+    #[error("shard id {shard_id} is out of range for {shard_count} shards")]
+    InvalidShardId { shard_id: u64, shard_count: u64 },
+
+    /// Consumer replay cursor store could not be read or written
+    #[error("replay cursor store failed (shard {shard_id:?})")]
+    CursorStore {
+        shard_id: Option<u64>,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    /// Failed-publish buffer file could not be read or written
+    #[error("publish buffer I/O failed for '{path}'")]
+    PublishBuffer {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// Serialized event is missing a guild_id its event type requires
+    /// (e.g. threads, which only exist in guilds). Rejected, not published.
+    #[error("{event_type} event on shard {shard_id} has no guild_id")]
+    MissingGuildId {
+        event_type: String,
+        shard_id: u64,
+    },
+
+    /// Payload compression or decompression failed
+    #[error("{codec} codec failed")]
+    Codec {
+        codec: &'static str,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// Codec id or name not recognized (PAYLOAD_CODEC, or a received header)
+    #[error("unknown payload codec '{0}'")]
+    UnknownCodec(String),
+
+    /// Serializer state snapshot could not be encoded or decoded
+    #[error("serializer state snapshot failed")]
+    StateSnapshot(#[source] serde_json::Error),
+
+    /// Shard state snapshot file could not be read or written
+    #[error("state snapshot I/O failed for '{path}'")]
+    StateFile {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// Debugging event sink could not be written (e.g. stdout pipe closed)
+    #[error("event sink write failed")]
+    SinkWrite(#[source] std::io::Error),
+
+    /// Shutdown flush stopped with buffered events still unpublished; they
+    /// stay in the buffer file for the next start
+    #[error("flush left {remaining} buffered events unpublished")]
+    FlushIncomplete {
+        remaining: usize,
+        #[source]
+        source: Box<GatewayError>,
+    },
 }
 
 impl GatewayError {
@@ -79,10 +148,21 @@ impl GatewayError {
             Self::ShardCircuitBroken { .. } => "circuit_broken",
             Self::ShardReconnectFailed { .. } => "reconnect_failed",
             Self::NatsPublishFailed { .. } => "nats_publish",
+            Self::PublishTimeout { .. } => "publish_timeout",
             Self::NatsConnectionFailed(_) => "nats_connection",
             Self::SerializationFailed { .. } => "serialization",
             Self::Config(_) => "config",
             Self::ShardIdOverflow { .. } => "shard_overflow",
+            Self::InvalidShardId { .. } => "invalid_shard_id",
+            Self::CursorStore { .. } => "cursor_store",
+            Self::PublishBuffer { .. } => "publish_buffer",
+            Self::MissingGuildId { .. } => "missing_guild_id",
+            Self::Codec { .. } => "codec",
+            Self::UnknownCodec(_) => "unknown_codec",
+            Self::StateSnapshot(_) => "state_snapshot",
+            Self::StateFile { .. } => "state_file",
+            Self::SinkWrite(_) => "sink_write",
+            Self::FlushIncomplete { .. } => "flush_incomplete",
         }
     }
 }
@@ -92,7 +172,7 @@ mod tests {
     use super::*;
 
     fn test_error() -> Box<dyn std::error::Error + Send + Sync> {
-        Box::new(std::io::Error::new(std::io::ErrorKind::Other, "test"))
+        Box::new(std::io::Error::other("test"))
     }
 
     #[test]
@@ -110,6 +190,11 @@ mod tests {
                 source: test_error(),
             }
             .error_type_label(),
+            GatewayError::PublishTimeout {
+                subject: "test".to_string(),
+                timeout_ms: 5000,
+            }
+            .error_type_label(),
             GatewayError::NatsConnectionFailed(test_error()).error_type_label(),
             GatewayError::SerializationFailed {
                 event_type: "test".to_string(),
@@ -119,6 +204,40 @@ mod tests {
             .error_type_label(),
             GatewayError::Config("test".to_string()).error_type_label(),
             GatewayError::ShardIdOverflow { value: u64::MAX }.error_type_label(),
+            GatewayError::InvalidShardId { shard_id: 1, shard_count: 1 }.error_type_label(),
+            GatewayError::CursorStore {
+                shard_id: Some(0),
+                source: test_error(),
+            }
+            .error_type_label(),
+            GatewayError::PublishBuffer {
+                path: "/tmp/buffer.ndjson".to_string(),
+                source: std::io::Error::other("test"),
+            }
+            .error_type_label(),
+            GatewayError::MissingGuildId {
+                event_type: "thread.create".to_string(),
+                shard_id: 0,
+            }
+            .error_type_label(),
+            GatewayError::Codec {
+                codec: "zstd",
+                source: std::io::Error::other("test"),
+            }
+            .error_type_label(),
+            GatewayError::UnknownCodec("brotli".to_string()).error_type_label(),
+            GatewayError::StateSnapshot(serde_json::from_str::<()>("invalid").unwrap_err()).error_type_label(),
+            GatewayError::StateFile {
+                path: "/var/lib/arrakis/shard-0-of-1.json".to_string(),
+                source: std::io::Error::other("test"),
+            }
+            .error_type_label(),
+            GatewayError::SinkWrite(std::io::Error::other("test")).error_type_label(),
+            GatewayError::FlushIncomplete {
+                remaining: 1,
+                source: Box::new(GatewayError::Config("test".to_string())),
+            }
+            .error_type_label(),
         ];
 
         // All labels are unique
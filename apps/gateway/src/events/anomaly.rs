@@ -0,0 +1,168 @@
+//! Rate-based anomaly detection
+//!
+//! Raids show up as a spike of joins or messages in one guild. The detector
+//! counts watched event types per guild in fixed windows and, the first time
+//! a window exceeds the configured baseline, emits a `guild.anomaly` control
+//! event carrying the event type and observed rate. Consumers get an early
+//! raid signal without each computing rates themselves.
+
+use crate::events::event_type::EventType;
+use crate::events::serialize::{GatewayEvent, CURRENT_SCHEMA_VERSION};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Event types whose per-guild rate is watched
+pub const WATCHED_EVENT_TYPES: &[&str] = &["member.join", "message.create"];
+
+/// Default counting window
+pub const DEFAULT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Tracked windows before stale ones are pruned
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Baseline a guild's rate is compared against
+#[derive(Debug, Clone)]
+pub struct AnomalyConfig {
+    /// Events of one type per guild per window above which an alert fires
+    pub threshold: u32,
+    /// Counting window
+    pub window: Duration,
+}
+
+/// Per-guild, per-event-type counting window
+struct Window {
+    started: Instant,
+    count: u32,
+    alerted: bool,
+}
+
+/// Shard-local anomaly detector
+pub struct AnomalyDetector {
+    config: AnomalyConfig,
+    windows: HashMap<(String, &'static str), Window>,
+}
+
+impl AnomalyDetector {
+    pub fn new(config: AnomalyConfig) -> Self {
+        Self {
+            config,
+            windows: HashMap::new(),
+        }
+    }
+
+    /// Count `event` and return a `guild.anomaly` event if its guild's rate
+    /// for that event type just crossed the threshold
+    ///
+    /// Fires at most once per window, so a sustained raid yields one alert
+    /// per window rather than one per event.
+    pub fn observe(&mut self, event: &GatewayEvent, now: Instant) -> Option<GatewayEvent> {
+        let watched = *WATCHED_EVENT_TYPES.iter().find(|t| event.event_type == **t)?;
+        let guild_id = event.guild_id.as_ref()?;
+
+        if self.windows.len() > PRUNE_THRESHOLD {
+            let window = self.config.window;
+            self.windows.retain(|_, w| now.duration_since(w.started) < window);
+        }
+
+        let entry = self
+            .windows
+            .entry((guild_id.clone(), watched))
+            .or_insert(Window { started: now, count: 0, alerted: false });
+        if now.duration_since(entry.started) >= self.config.window {
+            *entry = Window { started: now, count: 0, alerted: false };
+        }
+        entry.count += 1;
+
+        if entry.count <= self.config.threshold || entry.alerted {
+            return None;
+        }
+        entry.alerted = true;
+
+        let window_secs = self.config.window.as_secs_f64();
+        Some(GatewayEvent {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            event_id: Uuid::new_v4().to_string(),
+            event_type: EventType::GuildAnomaly,
+            shard_id: event.shard_id,
+            timestamp: event.timestamp,
+            guild_id: Some(guild_id.clone()),
+            channel_id: None,
+            user_id: None,
+            data: serde_json::json!({
+                "event_type": watched,
+                "count": entry.count,
+                "threshold": self.config.threshold,
+                "window_secs": window_secs,
+                "rate_per_sec": f64::from(entry.count) / window_secs,
+            }),
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::test_support::gateway_event;
+
+    fn join(guild_id: &str) -> GatewayEvent {
+        gateway_event("member.join").guild(guild_id).build()
+    }
+
+    fn detector(threshold: u32) -> AnomalyDetector {
+        AnomalyDetector::new(AnomalyConfig { threshold, window: Duration::from_secs(10) })
+    }
+
+    #[test]
+    fn join_burst_above_threshold_fires_once() {
+        let mut detector = detector(5);
+        let now = Instant::now();
+
+        let alerts: Vec<GatewayEvent> = (0..20)
+            .filter_map(|i| detector.observe(&join("123456789012345678"), now + Duration::from_millis(i * 100)))
+            .collect();
+
+        assert_eq!(alerts.len(), 1, "one alert per window");
+        let alert = &alerts[0];
+        assert_eq!(alert.event_type, "guild.anomaly");
+        assert_eq!(alert.guild_id.as_deref(), Some("123456789012345678"));
+        assert_eq!(alert.data["event_type"], "member.join");
+        assert_eq!(alert.data["count"], 6);
+        assert_eq!(alert.data["rate_per_sec"], 0.6);
+    }
+
+    #[test]
+    fn rate_at_baseline_does_not_fire() {
+        let mut detector = detector(5);
+        let now = Instant::now();
+        for _ in 0..5 {
+            assert!(detector.observe(&join("123456789012345678"), now).is_none());
+        }
+        // Other guilds have their own windows
+        assert!(detector.observe(&join("223456789012345678"), now).is_none());
+    }
+
+    #[test]
+    fn new_window_can_fire_again() {
+        let mut detector = detector(1);
+        let now = Instant::now();
+        detector.observe(&join("123456789012345678"), now);
+        assert!(detector.observe(&join("123456789012345678"), now).is_some());
+
+        let later = now + Duration::from_secs(10);
+        assert!(detector.observe(&join("123456789012345678"), later).is_none());
+        assert!(detector.observe(&join("123456789012345678"), later).is_some());
+    }
+
+    #[test]
+    fn unwatched_event_types_are_ignored() {
+        let mut detector = detector(0);
+        let update = GatewayEvent {
+            event_type: EventType::MemberUpdate,
+            guild_id: Some("123456789012345678".to_string()),
+            ..Default::default()
+        };
+        assert!(detector.observe(&update, Instant::now()).is_none());
+    }
+}
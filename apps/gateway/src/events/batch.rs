@@ -0,0 +1,276 @@
+//! Batch serialization for burst publishing
+//!
+//! Serializing a burst event-by-event allocates one buffer per event (plus
+//! regrowth). The batch encoder instead writes every payload into a single
+//! exactly pre-sized buffer holding one JSON array, so a burst of N events
+//! costs one allocation. Consumers parse the array back into events.
+//!
+//! An unbounded batch can exceed the NATS max payload, so
+//! [`serialize_batch`] splits its input into [`GatewayEventBatch`]es that
+//! each respect [`BatchLimits`]. Per-guild consumers can take
+//! [`serialize_batch_by_guild`] instead, which partitions by guild_id.
+
+use crate::events::serialize::{serialize_event, GatewayEvent, SerializeConfig};
+use crate::events::shard_id::ShardId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use twilight_model::gateway::event::Event;
+
+/// NATS server default `max_payload` (1 MiB)
+pub const DEFAULT_MAX_BATCH_BYTES: usize = 1024 * 1024;
+
+/// Size caps for a single batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchLimits {
+    /// Most events in one batch
+    pub max_events: usize,
+    /// Most encoded bytes in one batch
+    pub max_bytes: usize,
+}
+
+impl BatchLimits {
+    /// Cap the event count, with the default byte cap
+    pub fn max_events(max_events: usize) -> Self {
+        Self { max_events, max_bytes: DEFAULT_MAX_BATCH_BYTES }
+    }
+}
+
+/// Events published together, encoded as one JSON array
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct GatewayEventBatch {
+    pub events: Vec<GatewayEvent>,
+}
+
+impl GatewayEventBatch {
+    /// Number of events in the batch
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns true if the batch has no events
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Take the batch's events, in order
+    pub fn into_events(self) -> impl Iterator<Item = GatewayEvent> {
+        self.events.into_iter()
+    }
+
+    /// Encode as a JSON array, allocating once (see [`encode_batch`])
+    pub fn encode(&self) -> Vec<u8> {
+        encode_batch(&self.events)
+    }
+}
+
+/// Serialize forwardable events into batches within `limits`
+///
+/// Events are split in order; every batch but the last is full by count or
+/// by bytes. A single event larger than `max_bytes` can't be split and gets
+/// a batch of its own. Empty input yields no batches.
+pub fn serialize_batch(
+    events: &[Event],
+    shard_id: ShardId,
+    config: &SerializeConfig,
+    limits: BatchLimits,
+) -> Vec<GatewayEventBatch> {
+    let max_events = limits.max_events.max(1);
+    let mut batches = Vec::new();
+    let mut current = GatewayEventBatch::default();
+    // Encoded size of `current`: brackets plus a comma between events
+    let mut current_bytes = 2;
+
+    for payload in events.iter().filter_map(|event| serialize_event(event, shard_id, config)) {
+        let size = encoded_len(&payload);
+        let added = size + usize::from(!current.is_empty());
+        let full = current.len() >= max_events || current_bytes + added > limits.max_bytes;
+        if full && !current.is_empty() {
+            batches.push(std::mem::take(&mut current));
+            current_bytes = 2;
+        }
+        current_bytes += size + usize::from(!current.is_empty());
+        current.events.push(payload);
+    }
+    if !current.is_empty() {
+        batches.push(current);
+    }
+    batches
+}
+
+/// Serialize forwardable events into one batch per guild_id
+///
+/// Guildless events (shard control, DMs) share the `None` batch. Order is
+/// preserved within each batch.
+pub fn serialize_batch_by_guild(
+    events: &[Event],
+    shard_id: ShardId,
+    config: &SerializeConfig,
+) -> HashMap<Option<String>, GatewayEventBatch> {
+    let mut batches: HashMap<Option<String>, GatewayEventBatch> = HashMap::new();
+    for payload in events.iter().filter_map(|event| serialize_event(event, shard_id, config)) {
+        batches.entry(payload.guild_id.clone()).or_default().events.push(payload);
+    }
+    batches
+}
+
+/// Serialize forwardable events into one JSON array
+///
+/// Events `serialize_event` doesn't forward are skipped; order is preserved.
+/// An input with nothing forwardable encodes as `[]`.
+pub fn serialize_batch_bytes(events: &[Event], shard_id: ShardId, config: &SerializeConfig) -> Vec<u8> {
+    let payloads: Vec<GatewayEvent> = events
+        .iter()
+        .filter_map(|event| serialize_event(event, shard_id, config))
+        .collect();
+    encode_batch(&payloads)
+}
+
+/// Encode already-serialized events into one JSON array
+///
+/// Sizes the output with a counting pass first, so the returned buffer is
+/// allocated exactly once.
+pub fn encode_batch(payloads: &[GatewayEvent]) -> Vec<u8> {
+    let mut counter = ByteCounter(0);
+    write_array(&mut counter, payloads);
+
+    let mut buf = Vec::with_capacity(counter.0);
+    write_array(&mut buf, payloads);
+    debug_assert_eq!(buf.len(), counter.0);
+    buf
+}
+
+fn encoded_len(payload: &GatewayEvent) -> usize {
+    let mut counter = ByteCounter(0);
+    serde_json::to_writer(&mut counter, payload).expect("GatewayEvent serialization is infallible");
+    counter.0
+}
+
+fn write_array<W: Write>(writer: &mut W, payloads: &[GatewayEvent]) {
+    // GatewayEvent has only string-keyed maps, and neither writer can fail
+    payloads
+        .serialize(&mut serde_json::Serializer::new(writer))
+        .expect("GatewayEvent serialization is infallible");
+}
+
+/// Writer that only counts bytes
+struct ByteCounter(usize);
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::test_support::{member_add, member_update};
+
+    const GUILD: u64 = 123456789012345678;
+
+    #[test]
+    fn batch_round_trips_forwardable_events_in_order() {
+        let events = [
+            member_add(GUILD, 1, &[]),
+            Event::GatewayHeartbeatAck,
+            member_update(GUILD, 2, &[111]),
+        ];
+        let bytes = serialize_batch_bytes(&events, ShardId::new(3, 4).unwrap(), &SerializeConfig::default());
+
+        let decoded: Vec<GatewayEvent> = serde_json::from_slice(&bytes).unwrap();
+        let types: Vec<&str> = decoded.iter().map(|e| e.event_type.as_str()).collect();
+        assert_eq!(types, vec!["member.join", "member.update"]);
+        assert_eq!(decoded[1].user_id.as_deref(), Some("2"));
+        assert!(decoded.iter().all(|e| e.shard_id == 3));
+    }
+
+    #[test]
+    fn buffer_is_exactly_presized() {
+        let events = [member_add(GUILD, 1, &[]), member_update(GUILD, 2, &[111])];
+        let bytes = serialize_batch_bytes(&events, ShardId::ZERO, &SerializeConfig::default());
+        assert_eq!(bytes.capacity(), bytes.len());
+    }
+
+    #[test]
+    fn large_input_is_split_by_event_count() {
+        let events: Vec<Event> = (1..=250).map(|user| member_add(GUILD, user, &[])).collect();
+        let batches = serialize_batch(&events, ShardId::ZERO, &SerializeConfig::default(), BatchLimits::max_events(100));
+
+        let sizes: Vec<usize> = batches.iter().map(GatewayEventBatch::len).collect();
+        assert_eq!(sizes, vec![100, 100, 50]);
+        assert_eq!(batches[2].events.last().unwrap().user_id.as_deref(), Some("250"), "order is kept");
+    }
+
+    #[test]
+    fn split_batches_keep_only_forwardable_events_in_order() {
+        let events = [
+            Event::GatewayHeartbeatAck,
+            member_add(GUILD, 1, &[]),
+            Event::GatewayReconnect,
+            member_update(GUILD, 1, &[111]),
+            member_add(GUILD, 2, &[]),
+            Event::GatewayHeartbeatAck,
+        ];
+        let batches = serialize_batch(&events, ShardId::ZERO, &SerializeConfig::default(), BatchLimits::max_events(2));
+
+        let forwarded: Vec<(&str, Option<&str>)> = batches
+            .iter()
+            .flat_map(|batch| &batch.events)
+            .map(|e| (e.event_type.as_str(), e.user_id.as_deref()))
+            .collect();
+        assert_eq!(forwarded, vec![("member.join", Some("1")), ("member.update", Some("1")), ("member.join", Some("2"))]);
+        assert!(serialize_batch(&[Event::GatewayHeartbeatAck], ShardId::ZERO, &SerializeConfig::default(), BatchLimits::max_events(2)).is_empty());
+    }
+
+    #[test]
+    fn batches_stay_under_byte_cap() {
+        let events: Vec<Event> = (1..=20).map(|user| member_add(GUILD, user, &[])).collect();
+        let one = encode_batch(&[serialize_event(&events[0], ShardId::ZERO, &SerializeConfig::default()).unwrap()]);
+        // Room for about three events per batch
+        let limits = BatchLimits { max_events: 100, max_bytes: one.len() * 3 + 2 };
+
+        let batches = serialize_batch(&events, ShardId::ZERO, &SerializeConfig::default(), limits);
+        assert!(batches.len() > 1);
+        for batch in &batches {
+            assert!(batch.encode().len() <= limits.max_bytes, "batch of {} over byte cap", batch.len());
+        }
+        assert_eq!(batches.iter().map(GatewayEventBatch::len).sum::<usize>(), 20);
+
+        let decoded: GatewayEventBatch = serde_json::from_slice(&batches[0].encode()).unwrap();
+        assert_eq!(decoded.len(), batches[0].len());
+    }
+
+    #[test]
+    fn events_are_grouped_by_guild() {
+        let other_guild = 234567890123456789;
+        let events = [
+            member_add(GUILD, 1, &[]),
+            member_add(other_guild, 2, &[]),
+            Event::GatewayReconnect,
+            member_update(GUILD, 1, &[111]),
+            Event::GatewayHeartbeatAck,
+        ];
+        let config = SerializeConfig { forward_reconnects: true, ..Default::default() };
+        let batches = serialize_batch_by_guild(&events, ShardId::ZERO, &config);
+
+        assert_eq!(batches.len(), 3);
+        let guild = &batches[&Some(GUILD.to_string())];
+        let types: Vec<&str> = guild.events.iter().map(|e| e.event_type.as_str()).collect();
+        assert_eq!(types, vec!["member.join", "member.update"]);
+        assert_eq!(batches[&Some(other_guild.to_string())].len(), 1);
+        assert_eq!(batches[&None].events[0].event_type, "shard.reconnect_requested");
+    }
+
+    #[test]
+    fn empty_batch_is_empty_array() {
+        let bytes = serialize_batch_bytes(&[Event::GatewayHeartbeatAck], ShardId::ZERO, &SerializeConfig::default());
+        assert_eq!(bytes, b"[]");
+    }
+}
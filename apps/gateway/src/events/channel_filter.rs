@@ -0,0 +1,59 @@
+//! Channel denylist
+//!
+//! Bot-spam and other high-noise channels can drown out the traffic
+//! workers care about. Channel-scoped events (messages, reactions, typing)
+//! from a denied channel are dropped; everything else, including guild-level
+//! events and events without a channel, is forwarded.
+
+use crate::error::GatewayError;
+use std::collections::HashSet;
+
+/// Channels whose channel-scoped events are dropped
+pub type ChannelDenylist = HashSet<u64>;
+
+/// event_type prefixes of events that happen in a channel
+const CHANNEL_SCOPED_PREFIXES: &[&str] = &["message.", "reaction.", "typing."];
+
+/// Parse a comma-separated list of channel ids
+/// (e.g. `123456789012345678,234567890123456789`)
+pub fn parse_channel_ids(spec: &str) -> Result<ChannelDenylist, GatewayError> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| id.parse().map_err(|e| GatewayError::Config(format!("channel id '{id}' must be a valid number: {e}"))))
+        .collect()
+}
+
+/// Returns true if an event of `event_type` in `channel_id` passes the
+/// denylist
+pub fn channel_forwarded(event_type: &str, channel_id: Option<&str>, denylist: &ChannelDenylist) -> bool {
+    if denylist.is_empty() || !CHANNEL_SCOPED_PREFIXES.iter().any(|prefix| event_type.starts_with(prefix)) {
+        return true;
+    }
+    let Some(channel_id) = channel_id.and_then(|id| id.parse::<u64>().ok()) else {
+        return true;
+    };
+    !denylist.contains(&channel_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_channel_scoped_events_are_dropped() {
+        let deny = ChannelDenylist::from([4]);
+
+        assert!(!channel_forwarded("message.create", Some("4"), &deny));
+        assert!(!channel_forwarded("reaction.add", Some("4"), &deny));
+        assert!(channel_forwarded("message.create", Some("5"), &deny));
+        assert!(channel_forwarded("voice.state.update", Some("4"), &deny), "not channel-scoped");
+        assert!(channel_forwarded("message.create", None, &deny));
+    }
+
+    #[test]
+    fn parses_comma_separated_ids() {
+        assert_eq!(parse_channel_ids("4, 5").unwrap(), HashSet::from([4, 5]));
+        assert!(matches!(parse_channel_ids("bot-spam"), Err(GatewayError::Config(_))));
+    }
+}
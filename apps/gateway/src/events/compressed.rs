@@ -0,0 +1,110 @@
+//! Size-gated compression of serialized events
+//!
+//! `PAYLOAD_CODEC` compresses every publish, but most events are a few
+//! hundred bytes, where zstd saves little and costs a decompress on every
+//! consumer. The payloads worth compressing are the rare large ones: a
+//! full-guild `guild.join` runs to hundreds of kilobytes.
+//! [`serialize_event_compressed`] compresses an event's JSON with zstd only
+//! above `compress_threshold` and labels the result with its `encoding`, so
+//! a consumer decompresses only when it has to.
+
+use crate::codec::{self, Codec, Identity, Zstd};
+use crate::error::GatewayError;
+use crate::events::event_type::EventType;
+use crate::events::serialize::{serialize_event, SerializeConfig};
+use crate::events::shard_id::ShardId;
+use tracing::warn;
+use twilight_model::gateway::event::Event;
+
+/// A serialized [`GatewayEvent`](super::serialize::GatewayEvent) with its
+/// JSON possibly compressed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressedEvent {
+    pub event_id: String,
+    pub event_type: EventType,
+    /// Codec name `payload` is encoded with: `zstd` or `identity`
+    pub encoding: &'static str,
+    pub payload: Vec<u8>,
+}
+
+impl CompressedEvent {
+    /// The event's JSON, decompressed per `encoding`
+    pub fn decompress(&self) -> Result<Vec<u8>, GatewayError> {
+        codec::from_name(self.encoding)?.decompress(&self.payload)
+    }
+}
+
+/// Serialize `event`, zstd-compressing its JSON when larger than
+/// `config.compress_threshold` bytes
+///
+/// Returns None for events [`serialize_event`] doesn't forward. Without a
+/// threshold every payload stays identity. A payload zstd fails on is sent
+/// uncompressed rather than dropped.
+pub fn serialize_event_compressed(event: &Event, shard_id: ShardId, config: &SerializeConfig) -> Option<CompressedEvent> {
+    let payload = serialize_event(event, shard_id, config)?;
+    let json = serde_json::to_vec(&payload).ok()?;
+
+    let (encoding, bytes) = match config.compress_threshold {
+        Some(threshold) if json.len() > threshold => match Zstd.compress(&json) {
+            Ok(compressed) => (Zstd.name(), compressed),
+            Err(e) => {
+                warn!(event_id = %payload.event_id, error = %e, "Compression failed, sending uncompressed");
+                (Identity.name(), json)
+            }
+        },
+        _ => (Identity.name(), json),
+    };
+
+    Some(CompressedEvent {
+        event_id: payload.event_id,
+        event_type: payload.event_type,
+        encoding,
+        payload: bytes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::serialize::GatewayEvent;
+    use crate::events::test_support::{dispatch, guild};
+
+    fn config() -> SerializeConfig {
+        SerializeConfig { compress_threshold: Some(4096), ..Default::default() }
+    }
+
+    /// GUILD_CREATE whose channel list pushes the payload far past the threshold
+    fn large_guild() -> Event {
+        let channels: Vec<_> = (0..500u64)
+            .map(|i| serde_json::json!({ "id": (333333333333333333 + i).to_string(), "type": 0, "name": "general" }))
+            .collect();
+        let mut large = guild(123456789012345678, None);
+        large["channels"] = channels.into();
+        dispatch("GUILD_CREATE", large)
+    }
+
+    #[test]
+    fn large_payload_is_compressed_and_round_trips() {
+        let compressed = serialize_event_compressed(&large_guild(), ShardId::ZERO, &config()).unwrap();
+        assert_eq!(compressed.encoding, "zstd");
+        assert_eq!(compressed.event_type, EventType::GuildJoin);
+
+        let json = compressed.decompress().unwrap();
+        assert!(compressed.payload.len() < json.len());
+        let event: GatewayEvent = serde_json::from_slice(&json).unwrap();
+        assert_eq!(event.event_id, compressed.event_id);
+        assert_eq!(event.data["channels"].as_array().map(Vec::len), Some(500));
+    }
+
+    #[test]
+    fn small_payload_stays_identity() {
+        let small = dispatch("GUILD_CREATE", guild(123456789012345678, None));
+        let compressed = serialize_event_compressed(&small, ShardId::ZERO, &config()).unwrap();
+        assert_eq!(compressed.encoding, "identity");
+        let event: GatewayEvent = serde_json::from_slice(&compressed.payload).unwrap();
+        assert_eq!(event.event_type, EventType::GuildJoin);
+
+        let unset = serialize_event_compressed(&large_guild(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+        assert_eq!(unset.encoding, "identity", "no threshold, no compression");
+    }
+}
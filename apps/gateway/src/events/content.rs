@@ -0,0 +1,117 @@
+//! User-content field registry
+//!
+//! Downstream PII and moderation layers need to know which `data` fields
+//! hold text a user wrote or chose (message content, nicknames, option
+//! values) as opposed to structural values (ids, timestamps, flags).
+//! [`content_fields`] declares that per event_type, so scanners don't guess
+//! from field names.
+//!
+//! Paths are dotted like [`FlatData`](super::flat::FlatData) keys, with `*`
+//! standing for any array index or map key (`embeds.*.title`). A field is
+//! listed whether or not the current serialize options emit it. Envelope
+//! fields (`guild_id`, `user_id`, ...) are always structural.
+
+/// event_type → user-content paths in `data`
+const CONTENT_FIELDS: &[(&str, &[&str])] = &[
+    (
+        "guild.join",
+        &[
+            "name",
+            "description",
+            "channels.*.name",
+            "channels.*.topic",
+            "roles.*.name",
+            "emojis.*.name",
+            "members.*.nick",
+            "members.*.user.username",
+            "members.*.user.global_name",
+        ],
+    ),
+    ("guild.update", &["name", "description"]),
+    ("member.join", &["username", "global_name"]),
+    ("guild.ban.add", &["username"]),
+    ("guild.ban.remove", &["username"]),
+    ("member.update", &["nick", "global_name"]),
+    (
+        "message.create",
+        &[
+            "content",
+            "attachments.*",
+            "embeds.*.title",
+            "embeds.*.description",
+            "embeds.*.author",
+            "components.*.label",
+        ],
+    ),
+    ("message.update", &["content"]),
+    ("message.embed.update", &["embeds.*.title"]),
+    (
+        "interaction.create",
+        &[
+            "resolved.users.*.username",
+            "resolved.users.*.global_name",
+            "resolved.members.*.nick",
+            "resolved.channels.*.name",
+            "resolved.roles.*.name",
+        ],
+    ),
+    ("interaction.autocomplete", &["value"]),
+    ("interaction.modal", &["fields.*"]),
+    ("thread.list.sync", &["threads.*.name"]),
+    ("thread.create", &["name"]),
+    ("thread.update", &["name"]),
+    ("channel.update", &["name", "topic"]),
+    ("user.update", &["username", "global_name"]),
+    ("member.chunk", &["members.*.username", "members.*.global_name"]),
+    ("presence.update", &["activity.details", "activity.state"]),
+    ("automod.rule.create", &["name"]),
+    ("automod.rule.update", &["name"]),
+    ("automod.rule.delete", &["name"]),
+];
+
+/// Paths in `data` that carry user-generated content for `event_type`
+///
+/// Empty for event types with only structural data (and unknown types).
+pub fn content_fields(event_type: &str) -> &'static [&'static str] {
+    CONTENT_FIELDS
+        .iter()
+        .find(|(registered, _)| *registered == event_type)
+        .map_or(&[], |(_, fields)| fields)
+}
+
+/// Returns true if the dotted `path` (`embeds.0.title`) in an event's
+/// `data` is user content
+pub fn is_content_field(event_type: &str, path: &str) -> bool {
+    content_fields(event_type).iter().any(|pattern| {
+        let mut pattern = pattern.split('.');
+        let mut path = path.split('.');
+        loop {
+            match (pattern.next(), path.next()) {
+                (None, None) => return true,
+                (Some(p), Some(segment)) if p == "*" || p == segment => {}
+                _ => return false,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_content_is_user_content_but_ids_are_not() {
+        assert!(content_fields("message.create").contains(&"content"));
+        assert!(!content_fields("message.create").contains(&"channel_id"));
+        assert!(!is_content_field("message.create", "message_id"));
+        assert!(is_content_field("message.create", "embeds.0.title"));
+        assert!(!is_content_field("message.create", "embeds.0.url"));
+    }
+
+    #[test]
+    fn structural_event_types_have_no_content_fields() {
+        assert!(content_fields("member.leave").is_empty());
+        assert!(content_fields("not.an.event").is_empty());
+        assert!(!is_content_field("member.update", "nick.extra"));
+    }
+}
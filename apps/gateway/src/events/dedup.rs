@@ -0,0 +1,77 @@
+//! Consecutive duplicate suppression
+//!
+//! A reconnect loop or a replayed dispatch can make a shard emit the same
+//! event twice in a row. With a dedup window configured, the EventPipeline
+//! drops an event whose content matches the event it forwarded just before,
+//! if that one went out within the window. Content is the event type,
+//! envelope ids and `data`; `event_id` and `timestamp` differ on every
+//! serialization and don't count.
+
+use crate::events::serialize::GatewayEvent;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+/// Shard-local consecutive duplicate detector
+pub struct Deduplicator {
+    window: Duration,
+    /// Content hash of the last forwarded event and when it was forwarded
+    last: Option<(u64, Instant)>,
+}
+
+impl Deduplicator {
+    pub fn new(window: Duration) -> Self {
+        Self { window, last: None }
+    }
+
+    /// Returns true if `event` repeats the previously forwarded event within
+    /// the window; otherwise remembers it as the previous event
+    pub fn is_duplicate(&mut self, event: &GatewayEvent, now: Instant) -> bool {
+        let hash = content_hash(event);
+        if let Some((last, at)) = self.last {
+            if last == hash && now.duration_since(at) <= self.window {
+                return true;
+            }
+        }
+        self.last = Some((hash, now));
+        false
+    }
+}
+
+fn content_hash(event: &GatewayEvent) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    event.event_type.hash(&mut hasher);
+    event.guild_id.hash(&mut hasher);
+    event.channel_id.hash(&mut hasher);
+    event.user_id.hash(&mut hasher);
+    // serde_json::Value isn't Hash; its serialization is deterministic
+    event.data.to_string().hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::test_support::gateway_event;
+
+    fn event(user_id: &str) -> GatewayEvent {
+        gateway_event("member.update")
+            .random_id()
+            .user(user_id)
+            .data(serde_json::json!({ "nick": "raider" }))
+            .build()
+    }
+
+    #[test]
+    fn repeat_is_only_suppressed_within_the_window() {
+        let mut dedup = Deduplicator::new(Duration::from_secs(1));
+        let start = Instant::now();
+
+        assert!(!dedup.is_duplicate(&event("1"), start));
+        assert!(dedup.is_duplicate(&event("1"), start + Duration::from_millis(500)), "event_id doesn't count");
+        assert!(!dedup.is_duplicate(&event("1"), start + Duration::from_secs(5)), "window passed");
+        // Only the immediately preceding event is compared
+        assert!(!dedup.is_duplicate(&event("2"), start + Duration::from_secs(5)));
+        assert!(!dedup.is_duplicate(&event("1"), start + Duration::from_secs(5)));
+    }
+}
@@ -0,0 +1,74 @@
+//! Engagement weights for ranking consumers
+//!
+//! Leaderboard and referral consumers score members by what they do, and
+//! a message is worth more than a reaction. Rather than each consumer
+//! hardcoding its own table, the gateway stamps `engagement_weight` from
+//! one configured [`EngagementWeights`] table, so every consumer ranks by
+//! the same values. Event types missing from the table carry no weight.
+
+use crate::error::GatewayError;
+use std::collections::HashMap;
+
+/// event_type → engagement weight
+#[derive(Debug, Clone, Default)]
+pub struct EngagementWeights {
+    weights: HashMap<String, f32>,
+}
+
+impl EngagementWeights {
+    /// Empty table (no weights)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse `event_type=weight` pairs separated by commas
+    /// (e.g. `message.create=1.0,reaction.add=0.25`)
+    pub fn parse(spec: &str) -> Result<Self, GatewayError> {
+        let mut table = Self::new();
+        for pair in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (event_type, weight) = pair
+                .split_once('=')
+                .map(|(t, w)| (t.trim(), w.trim().parse::<f32>()))
+                .and_then(|(t, w)| Some((t, w.ok()?)))
+                .filter(|(t, w)| !t.is_empty() && w.is_finite() && *w >= 0.0)
+                .ok_or_else(|| GatewayError::Config(format!(
+                    "engagement weight '{pair}' must be event_type=weight with a non-negative number"
+                )))?;
+            table.insert(event_type, weight);
+        }
+        Ok(table)
+    }
+
+    /// Weight `event_type` events with `weight`
+    pub fn insert(&mut self, event_type: impl Into<String>, weight: f32) {
+        self.weights.insert(event_type.into(), weight);
+    }
+
+    /// Returns true if no weights are configured
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    /// The weight configured for `event_type`, if any
+    pub fn weight(&self, event_type: &str) -> Option<f32> {
+        self.weights.get(event_type).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_weights_and_rejects_malformed_pairs() {
+        let table = EngagementWeights::parse("message.create=1.0, reaction.add = 0.25").unwrap();
+        assert_eq!(table.weight("message.create"), Some(1.0));
+        assert_eq!(table.weight("reaction.add"), Some(0.25));
+        assert_eq!(table.weight("member.join"), None);
+        assert!(EngagementWeights::parse("").unwrap().is_empty());
+
+        for bad in ["message.create", "message.create=lots", "=1.0", "reaction.add=-1", "reaction.add=NaN"] {
+            assert!(matches!(EngagementWeights::parse(bad), Err(GatewayError::Config(_))), "{bad}");
+        }
+    }
+}
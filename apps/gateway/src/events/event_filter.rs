@@ -0,0 +1,109 @@
+//! event_type allowlist
+//!
+//! Some deployments only care about a few event types (say member joins and
+//! leaves) and would rather not pay NATS volume for interaction or message
+//! traffic. A [`SerializeFilter`] decides which event types are forwarded;
+//! it is applied to the finished event, so events the pipeline derives from
+//! another (deferred joins, pin updates) are matched by their own type.
+
+use crate::error::GatewayError;
+use std::collections::HashSet;
+
+/// Which event types are forwarded
+///
+/// The default forwards everything, same as [`SerializeFilter::all`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SerializeFilter {
+    /// None forwards every type not disabled
+    enabled: Option<HashSet<String>>,
+    disabled: HashSet<String>,
+}
+
+impl SerializeFilter {
+    /// Forward every event type
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Forward no event type; add some with [`enable`](Self::enable)
+    pub fn none() -> Self {
+        Self {
+            enabled: Some(HashSet::new()),
+            disabled: HashSet::new(),
+        }
+    }
+
+    /// Forward only `event_types`
+    pub fn only<I, S>(event_types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        event_types.into_iter().fold(Self::none(), Self::enable)
+    }
+
+    /// Parse a comma-separated list of event types to forward
+    /// (e.g. `member.join,member.leave`)
+    pub fn parse(spec: &str) -> Result<Self, GatewayError> {
+        let filter = Self::only(spec.split(',').map(str::trim).filter(|t| !t.is_empty()));
+        if filter.enabled.as_ref().is_some_and(HashSet::is_empty) {
+            return Err(GatewayError::Config(format!(
+                "event type list '{spec}' must name at least one event type"
+            )));
+        }
+        Ok(filter)
+    }
+
+    /// Also forward `event_type`
+    pub fn enable(mut self, event_type: impl Into<String>) -> Self {
+        let event_type = event_type.into();
+        self.disabled.remove(&event_type);
+        if let Some(ref mut enabled) = self.enabled {
+            enabled.insert(event_type);
+        }
+        self
+    }
+
+    /// Stop forwarding `event_type`
+    pub fn disable(mut self, event_type: impl Into<String>) -> Self {
+        let event_type = event_type.into();
+        if let Some(ref mut enabled) = self.enabled {
+            enabled.remove(&event_type);
+        }
+        self.disabled.insert(event_type);
+        self
+    }
+
+    /// Returns true if events of `event_type` are forwarded
+    pub fn is_enabled(&self, event_type: &str) -> bool {
+        self.enabled.as_ref().is_none_or(|enabled| enabled.contains(event_type)) && !self.disabled.contains(event_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_enables_and_disables_types() {
+        assert!(SerializeFilter::all().is_enabled("interaction.create"));
+
+        let filter = SerializeFilter::all().disable("interaction.create");
+        assert!(!filter.is_enabled("interaction.create"));
+        assert!(filter.is_enabled("member.join"));
+
+        let filter = SerializeFilter::none().enable("member.join").enable("member.leave");
+        assert!(filter.is_enabled("member.leave"));
+        assert!(!filter.is_enabled("message.create"));
+        assert!(!filter.disable("member.leave").is_enabled("member.leave"));
+    }
+
+    #[test]
+    fn parses_comma_separated_types() {
+        assert_eq!(
+            SerializeFilter::parse(" member.join, member.leave ,").unwrap(),
+            SerializeFilter::only(["member.join", "member.leave"])
+        );
+        assert!(matches!(SerializeFilter::parse(" , "), Err(GatewayError::Config(_))));
+    }
+}
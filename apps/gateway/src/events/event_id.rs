@@ -0,0 +1,59 @@
+//! Content-derived event ids
+//!
+//! `event_id` is a random v4 UUID by default, so when a reconnect makes
+//! Discord redeliver a dispatch the copy gets a fresh id and consumers
+//! can't tell it from a new event. With `deterministic_ids` on, the id is
+//! a v5 UUID of the event's identity instead: event type, envelope ids and
+//! `data`. A redelivered event gets the id it had the first time, so
+//! consumers can use `event_id` as an idempotency key.
+//!
+//! Identity is content, so two events that really are identical (a member
+//! adding, removing and re-adding the same reaction) also share an id.
+//! `timestamp` is when the gateway serialized the event and doesn't count.
+
+use crate::events::serialize::GatewayEvent;
+use uuid::Uuid;
+
+/// Namespace for event id UUIDs; changing it changes every id
+const EVENT_ID_NAMESPACE: Uuid = Uuid::from_u128(0x6f1c_2a4e_9b3d_4c7a_8e15_d0a9_47b2_c3e8);
+
+/// The v5 UUID identifying `event`'s content
+///
+/// Computed on the event as emitted, so with guild id hashing it covers
+/// the hashed id and doesn't let anyone confirm a raw guild id.
+pub fn deterministic_event_id(event: &GatewayEvent) -> String {
+    // Newline-separated; none of the parts can contain one unescaped
+    let name = format!(
+        "{}\n{}\n{}\n{}\n{}",
+        event.event_type,
+        event.guild_id.as_deref().unwrap_or_default(),
+        event.channel_id.as_deref().unwrap_or_default(),
+        event.user_id.as_deref().unwrap_or_default(),
+        event.data,
+    );
+    Uuid::new_v5(&EVENT_ID_NAMESPACE, name.as_bytes()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::test_support::gateway_event;
+
+    fn event(user_id: &str, timestamp: u64) -> GatewayEvent {
+        gateway_event("member.update")
+            .random_id()
+            .timestamp(timestamp)
+            .guild("123456789012345678")
+            .user(user_id)
+            .data(serde_json::json!({ "nick": "raider" }))
+            .build()
+    }
+
+    #[test]
+    fn id_depends_only_on_content() {
+        let id = deterministic_event_id(&event("1", 1700000000000));
+        assert_eq!(deterministic_event_id(&event("1", 1700000005000)), id, "timestamp doesn't count");
+        assert_ne!(deterministic_event_id(&event("2", 1700000000000)), id);
+        assert_eq!(Uuid::parse_str(&id).unwrap().get_version_num(), 5);
+    }
+}
@@ -0,0 +1,169 @@
+//! Typed event_type
+//!
+//! Every producer site used to spell its event_type as a string literal,
+//! which is how a name drifts between the gateway and its consumers.
+//! [`EventType`] names each type once, with its wire string, so producers
+//! can't typo one and consumers can match exhaustively. The wire form is
+//! unchanged: each variant serializes as its string, and a string this
+//! build doesn't know (from a newer producer, or a rename target)
+//! deserializes as [`EventType::Unknown`] instead of failing.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Deref;
+
+macro_rules! event_types {
+    ($($(#[$doc:meta])* $variant:ident => $wire:literal,)*) => {
+        /// Kind of a [`GatewayEvent`](super::serialize::GatewayEvent), as
+        /// its wire string
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        pub enum EventType {
+            $(
+                $(#[$doc])*
+                #[serde(rename = $wire)]
+                $variant,
+            )*
+            /// An event_type this build doesn't know
+            #[serde(untagged)]
+            Unknown(String),
+        }
+
+        impl EventType {
+            /// Every known event type
+            pub const ALL: &'static [EventType] = &[$(EventType::$variant),*];
+
+            /// The wire string
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(EventType::$variant => $wire,)*
+                    EventType::Unknown(event_type) => event_type,
+                }
+            }
+        }
+
+        impl From<&str> for EventType {
+            fn from(event_type: &str) -> Self {
+                match event_type {
+                    $($wire => EventType::$variant,)*
+                    other => EventType::Unknown(other.to_string()),
+                }
+            }
+        }
+    };
+}
+
+event_types! {
+    GuildJoin => "guild.join",
+    GuildLeave => "guild.leave",
+    GuildUpdate => "guild.update",
+    /// Control event from the anomaly detector
+    GuildAnomaly => "guild.anomaly",
+    GuildBanAdd => "guild.ban.add",
+    GuildBanRemove => "guild.ban.remove",
+    MemberJoin => "member.join",
+    MemberLeave => "member.leave",
+    MemberUpdate => "member.update",
+    MemberOnboardingComplete => "member.onboarding.complete",
+    MemberTimeoutExpired => "member.timeout.expired",
+    MemberChunk => "member.chunk",
+    InteractionCreate => "interaction.create",
+    InteractionAutocomplete => "interaction.autocomplete",
+    InteractionModal => "interaction.modal",
+    MessageCreate => "message.create",
+    MessageUpdate => "message.update",
+    MessageDelete => "message.delete",
+    MessageDeleteBulk => "message.delete.bulk",
+    MessageEmbedUpdate => "message.embed.update",
+    MessagePinUpdate => "message.pin.update",
+    ReactionAdd => "reaction.add",
+    ReactionRemove => "reaction.remove",
+    TypingStart => "typing.start",
+    VoiceStateUpdate => "voice.state.update",
+    AutomodRuleCreate => "automod.rule.create",
+    AutomodRuleUpdate => "automod.rule.update",
+    AutomodRuleDelete => "automod.rule.delete",
+    ScheduledEventUserRemove => "scheduled_event.user_remove",
+    PresenceUpdate => "presence.update",
+    /// Control event: Discord asked the shard to reconnect
+    ShardReconnectRequested => "shard.reconnect_requested",
+    ThreadListSync => "thread.list.sync",
+    ThreadCreate => "thread.create",
+    ThreadUpdate => "thread.update",
+    ThreadDelete => "thread.delete",
+    ChannelUpdate => "channel.update",
+    UserUpdate => "user.update",
+}
+
+impl EventType {
+    /// Returns true for event types this build knows
+    pub fn is_known(&self) -> bool {
+        !matches!(self, EventType::Unknown(_))
+    }
+}
+
+/// Empty `Unknown`, serializing as `""` like the old `String` default
+impl Default for EventType {
+    fn default() -> Self {
+        EventType::Unknown(String::new())
+    }
+}
+
+impl From<String> for EventType {
+    fn from(event_type: String) -> Self {
+        match EventType::from(event_type.as_str()) {
+            EventType::Unknown(_) => EventType::Unknown(event_type),
+            known => known,
+        }
+    }
+}
+
+impl Deref for EventType {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for EventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq<str> for EventType {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for EventType {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wire_strings_round_trip() {
+        for event_type in EventType::ALL {
+            let json = serde_json::to_string(event_type).unwrap();
+            assert_eq!(json, format!("\"{}\"", event_type.as_str()));
+            assert_eq!(&serde_json::from_str::<EventType>(&json).unwrap(), event_type);
+            assert_eq!(&EventType::from(event_type.as_str()), event_type);
+        }
+    }
+
+    #[test]
+    fn unknown_strings_are_kept() {
+        let event_type: EventType = serde_json::from_str("\"guild.created\"").unwrap();
+        assert_eq!(event_type, EventType::Unknown("guild.created".to_string()));
+        assert!(!event_type.is_known());
+        assert_eq!(serde_json::to_string(&event_type).unwrap(), "\"guild.created\"");
+        assert_eq!(EventType::from("member.join".to_string()), EventType::MemberJoin);
+        assert_eq!(EventType::MemberJoin, "member.join");
+    }
+}
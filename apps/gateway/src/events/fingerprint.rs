@@ -0,0 +1,299 @@
+//! Per-event-type schema fingerprints
+//!
+//! `schema_version` only moves on breaking changes to the whole wire
+//! contract, but event types evolve independently: `message.create` can
+//! gain a field while `member.join` stays put. [`fingerprint`] is a short
+//! hash of one event type's declared `data` shape, so a consumer can notice
+//! that the shape it was built against has changed.
+//!
+//! Shapes are dotted paths like [`content_fields`](super::content) (`*` for
+//! any array index or map key), listing every field the serializer can
+//! emit whether or not the current options emit it. Update the shape when
+//! an arm's `data` changes; the fingerprint follows. Flattened payloads
+//! (`flat_data`) share the nested shape's fingerprint.
+
+use ring::digest;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Hex characters kept from the digest (64 bits)
+const FINGERPRINT_HEX_LEN: usize = 16;
+
+/// event_type → declared `data` paths
+const DATA_SHAPES: &[(&str, &[&str])] = &[
+    (
+        "guild.join",
+        &[
+            "id",
+            "name",
+            "member_count",
+            "large",
+            "presence_count",
+            "banner",
+            "vanity_url_code",
+            "system_channel_id",
+            "rules_channel_id",
+            "public_updates_channel_id",
+        ],
+    ),
+    ("guild.leave", &["unavailable"]),
+    (
+        "guild.update",
+        &[
+            "name",
+            "description",
+            "icon",
+            "banner",
+            "vanity_url_code",
+            "owner_id",
+            "features",
+            "preferred_locale",
+            "premium_tier",
+            "verification_level",
+            "system_channel_id",
+            "rules_channel_id",
+            "public_updates_channel_id",
+        ],
+    ),
+    ("guild.anomaly", &["event_type", "count", "threshold", "window_secs", "rate_per_sec"]),
+    ("guild.ban.add", &["username"]),
+    ("guild.ban.remove", &["username"]),
+    (
+        "member.join",
+        &[
+            "username",
+            "global_name",
+            "discriminator",
+            "flags.did_rejoin",
+            "flags.completed_onboarding",
+            "flags.started_onboarding",
+            "flags.bypassed_verification",
+        ],
+    ),
+    ("member.leave", &["reason", "ban_event_id"]),
+    ("member.update", &["roles", "roles_added", "roles_removed", "nick", "global_name", "communication_disabled_until"]),
+    ("member.onboarding.complete", &[]),
+    ("member.timeout.expired", &[]),
+    (
+        "interaction.create",
+        &[
+            "interaction_id",
+            "interaction_type",
+            "interaction_token",
+            "resolved.users.*.username",
+            "resolved.users.*.global_name",
+            "resolved.users.*.bot",
+            "resolved.members.*.nick",
+            "resolved.members.*.roles",
+            "resolved.channels.*.name",
+            "resolved.channels.*.type",
+            "resolved.roles.*.name",
+            "custom_id",
+            "message_id",
+        ],
+    ),
+    (
+        "interaction.autocomplete",
+        &["interaction_id", "interaction_token", "command_name", "focused", "value"],
+    ),
+    ("interaction.modal", &["interaction_id", "interaction_token", "custom_id", "fields.*"]),
+    (
+        "voice.state.update",
+        &["mute", "deaf", "self_mute", "self_deaf", "self_stream", "self_video", "suppress"],
+    ),
+    (
+        "message.create",
+        &[
+            "message_id",
+            "is_webhook",
+            "is_bot",
+            "attachments",
+            "content",
+            "embeds.*.type",
+            "embeds.*.title",
+            "embeds.*.url",
+            "embeds.*.description",
+            "embeds.*.author",
+            "embeds.*.image_url",
+            "webhook_id",
+            "components.*.type",
+            "components.*.custom_id",
+            "components.*.label",
+            "interaction_metadata.id",
+            "interaction_metadata.type",
+            "interaction_metadata.user_id",
+            "detected_language.code",
+            "detected_language.confidence",
+        ],
+    ),
+    ("message.update", &["message_id", "edited_timestamp", "content"]),
+    ("message.delete", &["message_id"]),
+    ("message.delete.bulk", &["message_ids"]),
+    ("message.embed.update", &["message_id", "embeds.*.type", "embeds.*.url", "embeds.*.title"]),
+    ("message.pin.update", &["message_id", "pinned"]),
+    ("reaction.add", &["message_id", "emoji", "emoji_id", "burst", "burst_colors"]),
+    ("reaction.remove", &["message_id", "emoji", "emoji_id", "burst"]),
+    ("typing.start", &["started_at"]),
+    ("automod.rule.create", &["id", "name", "trigger_type", "enabled"]),
+    ("automod.rule.update", &["id", "name", "trigger_type", "enabled"]),
+    ("automod.rule.delete", &["id", "name", "trigger_type", "enabled"]),
+    ("scheduled_event.user_remove", &["scheduled_event_id"]),
+    (
+        "presence.update",
+        &[
+            "status",
+            "activity.name",
+            "activity.type",
+            "activity.details",
+            "activity.state",
+            "activity.application_id",
+            "activity.party_size",
+        ],
+    ),
+    ("shard.reconnect_requested", &[]),
+    (
+        "thread.create",
+        &["id", "parent_id", "name", "type", "owner_id", "archived", "locked"],
+    ),
+    (
+        "thread.update",
+        &["id", "parent_id", "name", "type", "owner_id", "archived", "locked"],
+    ),
+    ("thread.delete", &["id", "parent_id", "type"]),
+    ("user.update", &["username", "global_name", "avatar", "banner"]),
+    (
+        "member.chunk",
+        &["members.*.id", "members.*.username", "members.*.global_name", "members.*.roles", "chunk_index", "chunk_count", "nonce"],
+    ),
+    (
+        "channel.update",
+        &["id", "parent_id", "name", "type", "topic", "nsfw", "rate_limit_per_user"],
+    ),
+    (
+        "thread.list.sync",
+        &[
+            "channel_ids",
+            "threads.*.id",
+            "threads.*.parent_id",
+            "threads.*.name",
+            "threads.*.type",
+            "threads.*.owner_id",
+            "threads.*.archived",
+            "threads.*.locked",
+        ],
+    ),
+];
+
+/// Schema fingerprint of `event_type`'s `data` (16 hex chars)
+///
+/// Stable across builds while the declared shape is unchanged. Only the
+/// shape is hashed, so event types with the same fields (the three
+/// `automod.rule.*`) share a fingerprint, and so do renamed copies. Event
+/// types without a declared shape fingerprint as having no data fields.
+pub fn fingerprint(event_type: &str) -> String {
+    static REGISTERED: OnceLock<HashMap<&'static str, String>> = OnceLock::new();
+    let registered = REGISTERED.get_or_init(|| {
+        DATA_SHAPES
+            .iter()
+            .map(|(event_type, fields)| (*event_type, shape_fingerprint(fields)))
+            .collect()
+    });
+    registered
+        .get(event_type)
+        .cloned()
+        .unwrap_or_else(|| shape_fingerprint(&[]))
+}
+
+/// Declared `data` paths for `event_type`
+pub fn data_shape(event_type: &str) -> &'static [&'static str] {
+    DATA_SHAPES
+        .iter()
+        .find(|(registered, _)| *registered == event_type)
+        .map_or(&[], |(_, fields)| fields)
+}
+
+/// Hash of the paths, independent of declaration order
+fn shape_fingerprint(fields: &[&str]) -> String {
+    let mut fields = fields.to_vec();
+    fields.sort_unstable();
+    let shape = fields.join(",");
+    let hash = digest::digest(&digest::SHA256, shape.as_bytes());
+    let mut hex: String = hash.as_ref().iter().map(|byte| format!("{byte:02x}")).collect();
+    hex.truncate(FINGERPRINT_HEX_LEN);
+    hex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::serialize::{serialize_event, SerializeConfig};
+    use crate::events::shard_id::ShardId;
+    use crate::events::test_support::{guild_update, member_add};
+
+    /// Dotted paths of every leaf in `value`
+    fn paths(prefix: &str, value: &serde_json::Value, out: &mut Vec<String>) {
+        match value.as_object() {
+            Some(object) => {
+                for (key, value) in object {
+                    let path = if prefix.is_empty() { key.clone() } else { format!("{prefix}.{key}") };
+                    paths(&path, value, out);
+                }
+            }
+            None => out.push(prefix.to_string()),
+        }
+    }
+
+    #[test]
+    fn member_join_fingerprint_tracks_its_fields() {
+        let event = serialize_event(&member_add(123456789012345678, 987654321098765432, &[]), ShardId::ZERO, &SerializeConfig::default())
+            .unwrap();
+        let mut emitted = Vec::new();
+        paths("", &event.data, &mut emitted);
+        emitted.sort();
+        let mut declared: Vec<String> = data_shape("member.join").iter().map(|f| f.to_string()).collect();
+        declared.sort();
+        assert_eq!(emitted, declared, "declared shape matches the serializer");
+
+        let fields = data_shape("member.join");
+        assert_eq!(fingerprint("member.join"), shape_fingerprint(fields));
+        assert_eq!(fingerprint("member.join").len(), FINGERPRINT_HEX_LEN);
+
+        let mut reordered = fields.to_vec();
+        reordered.reverse();
+        assert_eq!(shape_fingerprint(&reordered), fingerprint("member.join"), "order doesn't matter");
+
+        let mut added = fields.to_vec();
+        added.push("avatar");
+        assert_ne!(shape_fingerprint(&added), fingerprint("member.join"));
+        assert_ne!(shape_fingerprint(&fields[1..]), fingerprint("member.join"));
+    }
+
+    #[test]
+    fn guild_update_fingerprint_tracks_its_fields() {
+        let event = serialize_event(&guild_update(123456789012345678, "Renamed"), ShardId::ZERO, &SerializeConfig::default())
+            .unwrap();
+        let mut emitted = Vec::new();
+        paths("", &event.data, &mut emitted);
+        emitted.sort();
+        let mut declared: Vec<String> = data_shape("guild.update").iter().map(|f| f.to_string()).collect();
+        declared.sort();
+        assert_eq!(emitted, declared, "declared shape matches guild_summary");
+    }
+
+    #[test]
+    fn shapes_declare_each_path_once() {
+        for (event_type, fields) in DATA_SHAPES {
+            let mut unique = fields.to_vec();
+            unique.sort_unstable();
+            unique.dedup();
+            assert_eq!(unique.len(), fields.len(), "{event_type} declares a path twice");
+        }
+    }
+
+    #[test]
+    fn fingerprint_depends_only_on_the_shape() {
+        assert_eq!(fingerprint("automod.rule.create"), fingerprint("automod.rule.delete"));
+        assert_ne!(fingerprint("message.update"), fingerprint("message.pin.update"));
+        assert_eq!(fingerprint("not.an.event"), fingerprint("member.onboarding.complete"));
+    }
+}
@@ -0,0 +1,257 @@
+//! Fixed-schema serialization for the hottest event types
+//!
+//! `serialize_event` builds every event's `data` as a `serde_json::Value`
+//! (a map allocation per field) and then serializes that tree. For the
+//! highest-volume types, typing and presence, that's most of the cost.
+//! [`TypingEvent`] and [`PresenceEvent`] are concrete structs serialized
+//! straight to JSON instead. Their JSON is the same as the dynamic path's
+//! and deserializes as a [`GatewayEvent`], so consumers can't tell which
+//! path an event took.
+//!
+//! The schema is fixed: only the base envelope (through `partition_key`)
+//! is written, and the optional extras (`timestamp_ns`, `intent`,
+//! `engagement_weight`, ...) are not. With `fixed_schema_hot_events` set,
+//! [`serialize_event_bytes`] takes this path for typing and presence and
+//! `serialize_event` for everything else. Configs that rewrite the base
+//! envelope (guild id hashing, deterministic ids, a micros
+//! `timestamp_unit`) always take the dynamic path.
+
+use crate::events::channel_filter::channel_forwarded;
+use crate::events::event_type::EventType;
+use crate::events::guild_filter::guild_forwarded;
+use crate::events::outcome::SerializeOutcome;
+use crate::events::serialize::{
+    now_millis, partition_key, serialize_event, GatewayEvent, SerializeConfig, CURRENT_SCHEMA_VERSION,
+};
+use crate::events::shard_id::ShardId;
+use crate::events::timestamp_unit::TimestampUnit;
+use serde::{Deserialize, Serialize};
+use twilight_model::gateway::event::Event;
+use twilight_model::gateway::presence::{Activity, ActivityType, Status};
+use uuid::Uuid;
+
+/// A [`GatewayEvent`] with typed `data` and no optional envelope fields
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FixedEvent<D> {
+    pub schema_version: u16,
+    pub event_id: String,
+    pub event_type: EventType,
+    pub shard_id: ShardId,
+    pub timestamp: u64,
+    pub guild_id: Option<String>,
+    pub channel_id: Option<String>,
+    pub user_id: Option<String>,
+    pub data: D,
+    pub partition_key: String,
+}
+
+/// `typing.start`
+pub type TypingEvent = FixedEvent<TypingData>;
+
+/// `presence.update`
+pub type PresenceEvent = FixedEvent<PresenceData>;
+
+/// `typing.start` data
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypingData {
+    /// When Discord says typing started, Unix milliseconds
+    pub started_at: u64,
+}
+
+/// `presence.update` data
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresenceData {
+    pub status: Status,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub activity: Option<PresenceActivity>,
+}
+
+/// The primary activity of a presence, for now-playing displays
+///
+/// `type` is Discord's activity type (0 playing, 2 listening, ...). For a
+/// Spotify listen `details` is the track and `state` the artist;
+/// `party_size` is `[current, max]` when the activity has a party.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresenceActivity {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: ActivityType,
+    pub details: Option<String>,
+    pub state: Option<String>,
+    pub application_id: Option<String>,
+    pub party_size: Option<[u64; 2]>,
+}
+
+impl From<&Activity> for PresenceActivity {
+    fn from(activity: &Activity) -> Self {
+        Self {
+            name: activity.name.clone(),
+            kind: activity.kind,
+            details: activity.details.clone(),
+            state: activity.state.clone(),
+            application_id: activity.application_id.map(|id| id.to_string()),
+            party_size: activity.party.as_ref().and_then(|party| party.size),
+        }
+    }
+}
+
+/// A typing or presence event in fixed-schema form
+#[derive(Debug, Clone, PartialEq)]
+pub enum HotEvent {
+    Typing(TypingEvent),
+    Presence(PresenceEvent),
+}
+
+impl HotEvent {
+    pub fn event_type(&self) -> &EventType {
+        match self {
+            Self::Typing(event) => &event.event_type,
+            Self::Presence(event) => &event.event_type,
+        }
+    }
+
+    /// The event's JSON
+    pub fn to_vec(&self) -> serde_json::Result<Vec<u8>> {
+        match self {
+            Self::Typing(event) => serde_json::to_vec(event),
+            Self::Presence(event) => serde_json::to_vec(event),
+        }
+    }
+}
+
+/// Serialize a typing or presence event in fixed-schema form
+///
+/// None for every other event, and for events `serialize_event` would
+/// drop (filtered guilds, channels and event types). Doesn't read
+/// `fixed_schema_hot_events`; [`serialize_event_bytes`] does.
+pub fn serialize_hot_event(event: &Event, shard_id: ShardId, config: &SerializeConfig) -> Option<HotEvent> {
+    let hot = match event {
+        Event::TypingStart(typing) => {
+            let guild_id = typing.guild_id.map(|id| id.to_string());
+            HotEvent::Typing(TypingEvent {
+                schema_version: CURRENT_SCHEMA_VERSION,
+                event_id: Uuid::new_v4().to_string(),
+                event_type: EventType::TypingStart,
+                shard_id,
+                timestamp: now_millis(),
+                partition_key: partition_key(guild_id.as_deref(), shard_id),
+                guild_id,
+                channel_id: Some(typing.channel_id.to_string()),
+                user_id: Some(typing.user_id.to_string()),
+                data: TypingData { started_at: typing.timestamp * 1000 },
+            })
+        }
+        Event::PresenceUpdate(presence) => {
+            let guild_id = presence.guild_id.to_string();
+            let activity = presence.activities.iter().find(|a| a.kind != ActivityType::Custom);
+            HotEvent::Presence(PresenceEvent {
+                schema_version: CURRENT_SCHEMA_VERSION,
+                event_id: Uuid::new_v4().to_string(),
+                event_type: EventType::PresenceUpdate,
+                shard_id,
+                timestamp: now_millis(),
+                partition_key: partition_key(Some(&guild_id), shard_id),
+                guild_id: Some(guild_id),
+                channel_id: None,
+                user_id: Some(presence.user.id().to_string()),
+                data: PresenceData {
+                    status: presence.status,
+                    activity: activity.map(PresenceActivity::from),
+                },
+            })
+        }
+        _ => return None,
+    };
+
+    let (event_type, guild_id, channel_id) = match &hot {
+        HotEvent::Typing(event) => (&event.event_type, event.guild_id.as_deref(), event.channel_id.as_deref()),
+        HotEvent::Presence(event) => (&event.event_type, event.guild_id.as_deref(), None),
+    };
+    let forwarded = config.event_filter.is_enabled(event_type)
+        && guild_forwarded(guild_id, config.guild_allowlist.as_ref(), &config.guild_denylist)
+        && channel_forwarded(event_type, channel_id, &config.channel_denylist);
+    forwarded.then_some(hot)
+}
+
+/// Serialize `event` to its published JSON, through the fixed-schema path
+/// for typing and presence when `config.fixed_schema_hot_events` is set
+///
+/// Returns None for events `serialize_event` doesn't forward. Outcomes are
+/// counted in the config's `metrics` on either path.
+pub fn serialize_event_bytes(event: &Event, shard_id: ShardId, config: &SerializeConfig) -> Option<Vec<u8>> {
+    let fixed_eligible = config.fixed_schema_hot_events
+        && config.guild_id_hashing.is_none()
+        && !config.deterministic_ids
+        && config.timestamp_unit == TimestampUnit::Millis;
+    if fixed_eligible {
+        if let Some(hot) = serialize_hot_event(event, shard_id, config) {
+            if let Some(ref metrics) = config.metrics {
+                metrics.record_event_type(SerializeOutcome::Forwarded, hot.event_type().as_str());
+            }
+            // Plain structs of strings and numbers; serde_json can't fail
+            return hot.to_vec().ok();
+        }
+    }
+    let payload: GatewayEvent = serialize_event(event, shard_id, config)?;
+    serde_json::to_vec(&payload).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::channel_filter::parse_channel_ids;
+    use crate::events::test_support::dispatch;
+
+    fn typing() -> Event {
+        dispatch("TYPING_START", serde_json::json!({
+            "channel_id": "333333333333333333",
+            "guild_id": "123456789012345678",
+            "user_id": "987654321098765432",
+            "timestamp": 1700000000
+        }))
+    }
+
+    fn without_ids(mut json: serde_json::Value) -> serde_json::Value {
+        json["event_id"] = serde_json::Value::Null;
+        json["timestamp"] = serde_json::Value::Null;
+        json
+    }
+
+    #[test]
+    fn fixed_typing_event_round_trips_as_a_gateway_event() {
+        let Some(HotEvent::Typing(fixed)) = serialize_hot_event(&typing(), ShardId::ZERO, &SerializeConfig::default())
+        else {
+            panic!("typing is a hot event");
+        };
+        assert_eq!(fixed.data.started_at, 1700000000000);
+
+        let json = serde_json::to_vec(&fixed).unwrap();
+        let parsed: GatewayEvent = serde_json::from_slice(&json).unwrap();
+        assert_eq!(parsed.event_type, EventType::TypingStart);
+        assert_eq!(parsed.channel_id.as_deref(), Some("333333333333333333"));
+        assert_eq!(parsed.partition_key, "guild-123456789012345678");
+        assert_eq!(serde_json::from_slice::<TypingEvent>(&json).unwrap(), fixed);
+
+        let dynamic = serialize_event(&typing(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+        assert_eq!(
+            without_ids(serde_json::to_value(&parsed).unwrap()),
+            without_ids(serde_json::to_value(&dynamic).unwrap()),
+            "same wire form as the dynamic path"
+        );
+    }
+
+    #[test]
+    fn option_selects_the_path_and_filters_still_apply() {
+        let fixed = SerializeConfig { fixed_schema_hot_events: true, ..Default::default() };
+        let json: serde_json::Value = serde_json::from_slice(&serialize_event_bytes(&typing(), ShardId::ZERO, &fixed).unwrap()).unwrap();
+        assert_eq!(json["data"]["started_at"], 1700000000000u64);
+
+        let denied = SerializeConfig {
+            fixed_schema_hot_events: true,
+            channel_denylist: parse_channel_ids("333333333333333333").unwrap(),
+            ..Default::default()
+        };
+        assert!(serialize_hot_event(&typing(), ShardId::ZERO, &denied).is_none());
+        assert!(serialize_event_bytes(&typing(), ShardId::ZERO, &denied).is_none());
+    }
+}
@@ -0,0 +1,129 @@
+//! Flattened `data` for columnar and protobuf consumers
+//!
+//! Nested, arbitrarily shaped `data` maps poorly onto protobuf/Avro or
+//! columnar schemas. [`FlatData`] flattens it into dotted keys with scalar
+//! values only (`embeds.0.url`), giving a consistent flat map per event
+//! type. JSON consumers keep the nested form; flat mode is opt-in.
+
+use crate::events::serialize::GatewayEvent;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// `data` flattened into dotted keys with scalar (or null) values
+///
+/// Object keys and array indices are joined with `.`. Empty objects and
+/// arrays have no scalars and contribute no keys. Keys iterate in sorted
+/// order, so the serialized form is stable.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct FlatData(BTreeMap<String, Value>);
+
+impl FlatData {
+    /// Flatten a nested `data` value
+    ///
+    /// A scalar at the top level (including the `null` some events carry)
+    /// flattens to an empty map.
+    pub fn from_value(data: &Value) -> Self {
+        let mut flat = BTreeMap::new();
+        if data.is_object() || data.is_array() {
+            flatten_into(&mut flat, String::new(), data);
+        }
+        Self(flat)
+    }
+
+    /// Look up a dotted key
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.0.get(key)
+    }
+
+    /// Number of flattened keys
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if there are no flattened keys
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Flattened keys in sorted order
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(String::as_str)
+    }
+
+    /// Convert into a JSON object, for use as an event's `data`
+    pub fn into_value(self) -> Value {
+        Value::Object(self.0.into_iter().collect::<Map<_, _>>())
+    }
+}
+
+/// Replace an event's nested `data` with its flattened form
+pub fn flatten_event(event: &mut GatewayEvent) {
+    event.data = FlatData::from_value(&event.data).into_value();
+}
+
+fn flatten_into(flat: &mut BTreeMap<String, Value>, prefix: String, value: &Value) {
+    let child = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{prefix}.{key}")
+        }
+    };
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                flatten_into(flat, child(key), value);
+            }
+        }
+        Value::Array(items) => {
+            for (index, value) in items.iter().enumerate() {
+                flatten_into(flat, child(&index.to_string()), value);
+            }
+        }
+        scalar => {
+            flat.insert(prefix, scalar.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::serialize::{serialize_event, SerializeConfig};
+    use crate::events::shard_id::ShardId;
+    use crate::events::test_support::{dispatch, message};
+
+    #[test]
+    fn nested_message_event_flattens_to_dotted_keys() {
+        let unfurl = dispatch("MESSAGE_UPDATE", message(serde_json::json!({
+            "embeds": [
+                { "type": "link", "url": "https://example.com", "title": "Example Domain" },
+                { "type": "image", "url": "https://example.com/cat.png" }
+            ]
+        })));
+        let mut event = serialize_event(&unfurl, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+        assert_eq!(event.event_type, "message.embed.update");
+
+        flatten_event(&mut event);
+        let flat = event.data.as_object().expect("flat data is an object");
+        let keys: Vec<&str> = flat.keys().map(String::as_str).collect();
+        assert_eq!(keys, vec![
+            "embeds.0.title", "embeds.0.type", "embeds.0.url",
+            "embeds.1.title", "embeds.1.type", "embeds.1.url",
+            "message_id",
+        ]);
+        assert_eq!(flat["embeds.1.url"], "https://example.com/cat.png");
+        assert_eq!(flat["embeds.1.title"], Value::Null);
+        assert!(flat.values().all(|v| !v.is_object() && !v.is_array()), "values must be scalar");
+    }
+
+    #[test]
+    fn null_and_empty_containers_contribute_no_keys() {
+        assert!(FlatData::from_value(&Value::Null).is_empty());
+
+        let flat = FlatData::from_value(&serde_json::json!({ "roles": [], "nick": null, "meta": {} }));
+        assert_eq!(flat.keys().collect::<Vec<_>>(), vec!["nick"]);
+    }
+}
@@ -0,0 +1,61 @@
+//! Guild allowlist and denylist
+//!
+//! Deployments can scope a gateway to a few guilds (allowlist) or forward
+//! everything except a few noisy or test guilds (denylist). The allowlist
+//! takes precedence: when one is set only its guilds are forwarded and the
+//! denylist is not consulted. Events without a guild (shard control) are
+//! always forwarded.
+
+use crate::error::GatewayError;
+use std::collections::HashSet;
+
+/// Guilds to forward; every other guild is dropped
+pub type GuildAllowlist = HashSet<u64>;
+
+/// Guilds to drop
+pub type GuildDenylist = HashSet<u64>;
+
+/// Parse a comma-separated list of guild ids
+/// (e.g. `123456789012345678,234567890123456789`)
+pub fn parse_guild_ids(spec: &str) -> Result<HashSet<u64>, GatewayError> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| id.parse().map_err(|e| GatewayError::Config(format!("guild id '{id}' must be a valid number: {e}"))))
+        .collect()
+}
+
+/// Returns true if events from `guild_id` pass the allowlist/denylist
+pub fn guild_forwarded(guild_id: Option<&str>, allowlist: Option<&GuildAllowlist>, denylist: &GuildDenylist) -> bool {
+    let Some(guild_id) = guild_id.and_then(|id| id.parse::<u64>().ok()) else {
+        return true;
+    };
+    match allowlist {
+        Some(allowlist) => allowlist.contains(&guild_id),
+        None => !denylist.contains(&guild_id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allowlist_takes_precedence_over_denylist() {
+        let allow = GuildAllowlist::from([1, 2]);
+        let deny = GuildDenylist::from([2, 3]);
+
+        assert!(guild_forwarded(Some("2"), Some(&allow), &deny), "allowed even though denied");
+        assert!(!guild_forwarded(Some("4"), Some(&allow), &deny));
+        assert!(!guild_forwarded(Some("3"), None, &deny));
+        assert!(guild_forwarded(Some("4"), None, &deny));
+        assert!(guild_forwarded(None, Some(&allow), &deny), "guild-less events always pass");
+    }
+
+    #[test]
+    fn parses_comma_separated_ids() {
+        assert_eq!(parse_guild_ids(" 1, 2 ,,3").unwrap(), HashSet::from([1, 2, 3]));
+        assert!(parse_guild_ids("").unwrap().is_empty());
+        assert!(matches!(parse_guild_ids("1,general"), Err(GatewayError::Config(_))));
+    }
+}
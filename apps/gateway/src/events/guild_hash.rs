@@ -0,0 +1,169 @@
+//! Keyed guild id hashing
+//!
+//! Analytics partners segment events by community but shouldn't learn
+//! which guilds they are. With a [`GuildIdHasher`] configured, emitted
+//! events carry an HMAC-SHA256 of the guild id instead of the id: stable
+//! per guild (for the same key) and not reversible without the key.
+//!
+//! The id is replaced inside `data` too, wherever it appears: a
+//! `guild.join` carries the whole guild, whose own id is also the
+//! @everyone role's, and its channels and threads name it as their
+//! `guild_id`. Any other `guild_id` field in `data` is hashed as well. The
+//! hasher keeps a sidecar of the hashes it produced, so internal routing
+//! that sees the hashed stream can still map back with
+//! [`GuildIdHasher::reverse`].
+
+use crate::events::serialize::GatewayEvent;
+use dashmap::DashMap;
+use serde_json::Value;
+use ring::hmac;
+use std::fmt;
+
+/// Hex characters kept from the HMAC (128 bits)
+const HASH_HEX_LEN: usize = 32;
+
+/// Replaces guild ids with a keyed hash
+pub struct GuildIdHasher {
+    key: hmac::Key,
+    /// hashed guild id → raw guild id, for every hash produced
+    sidecar: DashMap<String, String>,
+}
+
+impl GuildIdHasher {
+    /// Hash with `key`; keep it secret, and stable to keep hashes stable
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            key: hmac::Key::new(hmac::HMAC_SHA256, key),
+            sidecar: DashMap::new(),
+        }
+    }
+
+    /// The keyed hash of `guild_id`, as lowercase hex
+    pub fn hash(&self, guild_id: &str) -> String {
+        let tag = hmac::sign(&self.key, guild_id.as_bytes());
+        let mut hashed: String = tag.as_ref().iter().map(|byte| format!("{byte:02x}")).collect();
+        hashed.truncate(HASH_HEX_LEN);
+        self.sidecar.entry(hashed.clone()).or_insert_with(|| guild_id.to_string());
+        hashed
+    }
+
+    /// The raw guild id behind a hash this hasher produced
+    pub fn reverse(&self, hashed: &str) -> Option<String> {
+        self.sidecar.get(hashed).map(|raw| raw.clone())
+    }
+
+    /// Replace the event's guild_id with its hash, in the envelope and
+    /// throughout `data`
+    pub fn apply(&self, event: &mut GatewayEvent) {
+        if let Some(guild_id) = event.guild_id.take() {
+            let hashed = self.hash(&guild_id);
+            replace_id(&mut event.data, &guild_id, &hashed);
+            event.guild_id = Some(hashed);
+        }
+        self.hash_guild_id_fields(&mut event.data);
+    }
+
+    /// Hash the value of every `guild_id` field left in `value` (guilds
+    /// other than the event's own)
+    fn hash_guild_id_fields(&self, value: &mut Value) {
+        match value {
+            Value::Array(items) => items.iter_mut().for_each(|item| self.hash_guild_id_fields(item)),
+            Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    match value {
+                        // Already-hashed ids are hex, never all digits
+                        Value::String(id) if key == "guild_id" && id.bytes().all(|b| b.is_ascii_digit()) => {
+                            *id = self.hash(id);
+                        }
+                        _ => self.hash_guild_id_fields(value),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Replace `raw` with `hashed` in every string and object key of `value`
+/// (ids also turn up inside strings, e.g. message links)
+fn replace_id(value: &mut Value, raw: &str, hashed: &str) {
+    match value {
+        Value::String(s) if s.contains(raw) => *s = s.replace(raw, hashed),
+        Value::Array(items) => items.iter_mut().for_each(|item| replace_id(item, raw, hashed)),
+        Value::Object(map) => {
+            let keys: Vec<String> = map.keys().filter(|key| key.contains(raw)).cloned().collect();
+            for key in keys {
+                if let Some(item) = map.remove(&key) {
+                    map.insert(key.replace(raw, hashed), item);
+                }
+            }
+            map.values_mut().for_each(|item| replace_id(item, raw, hashed));
+        }
+        _ => {}
+    }
+}
+
+impl fmt::Debug for GuildIdHasher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Never print the key
+        f.debug_struct("GuildIdHasher")
+            .field("hashed_guilds", &self.sidecar.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GUILD: &str = "123456789012345678";
+
+    #[test]
+    fn hash_is_stable_and_hides_the_id() {
+        let hasher = GuildIdHasher::new(b"analytics-key");
+        let hashed = hasher.hash(GUILD);
+
+        assert_ne!(hashed, GUILD);
+        assert!(!hashed.contains(GUILD));
+        assert_eq!(hashed.len(), HASH_HEX_LEN);
+        assert_eq!(hasher.hash(GUILD), hashed, "stable per guild");
+        assert_eq!(GuildIdHasher::new(b"analytics-key").hash(GUILD), hashed, "stable across restarts");
+        assert_ne!(GuildIdHasher::new(b"other-key").hash(GUILD), hashed, "keyed");
+        assert_ne!(hasher.hash("234567890123456789"), hashed);
+    }
+
+    #[test]
+    fn sidecar_maps_hashes_back() {
+        let hasher = GuildIdHasher::new(b"analytics-key");
+        let mut event = GatewayEvent {
+            guild_id: Some(GUILD.to_string()),
+            ..Default::default()
+        };
+        hasher.apply(&mut event);
+
+        let hashed = event.guild_id.unwrap();
+        assert_eq!(hasher.reverse(&hashed).as_deref(), Some(GUILD));
+        assert_eq!(hasher.reverse("0123456789abcdef"), None);
+        assert!(!format!("{hasher:?}").contains("analytics-key"));
+    }
+
+    #[test]
+    fn ids_inside_data_are_hashed() {
+        let hasher = GuildIdHasher::new(b"analytics-key");
+        let mut event = GatewayEvent {
+            guild_id: Some(GUILD.to_string()),
+            data: serde_json::json!({
+                "roles": [{ "id": GUILD, "name": "@everyone" }],
+                "link": format!("https://discord.com/channels/{GUILD}/333333333333333333"),
+                "source": { "guild_id": "234567890123456789" },
+            }),
+            ..Default::default()
+        };
+        hasher.apply(&mut event);
+
+        let hashed = hasher.hash(GUILD);
+        assert_eq!(event.data["roles"][0]["id"], hashed.as_str());
+        assert!(event.data["link"].as_str().unwrap().contains(&hashed));
+        assert_eq!(event.data["source"]["guild_id"], hasher.hash("234567890123456789").as_str());
+    }
+}
@@ -0,0 +1,27 @@
+//! Internal account ids for account-linked deployments
+//!
+//! Where Discord users are linked to internal accounts, consumers key
+//! credits, referrals and the like by the internal id. An [`IdMapper`]
+//! set on [`SerializeConfig`](super::serialize::SerializeConfig) resolves
+//! each event's `user_id` and the EventPipeline attaches the result as
+//! `internal_user_id`, leaving the Discord id as is.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+/// Resolves a Discord user id to an internal account id
+///
+/// Called once per forwarded event with a `user_id`, on the shard's event
+/// loop, so implementations should answer from memory (a cache kept warm
+/// elsewhere) rather than block on I/O.
+pub trait IdMapper: Debug + Send + Sync {
+    /// Internal id for `discord_user_id`, or None if the user isn't linked
+    fn internal_user_id(&self, discord_user_id: &str) -> Option<String>;
+}
+
+/// Fixed mapping, Discord id → internal id
+impl IdMapper for HashMap<String, String> {
+    fn internal_user_id(&self, discord_user_id: &str) -> Option<String> {
+        self.get(discord_user_id).cloned()
+    }
+}
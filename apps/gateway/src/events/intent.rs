@@ -0,0 +1,60 @@
+//! Gateway intent behind each event type
+//!
+//! Discord only sends a dispatch when the shard identified with the intent
+//! that covers it, so an event a consumer never sees may simply not have
+//! been subscribed to. [`event_intent`] names that intent per event_type,
+//! and `include_intent` stamps it on each event, so a missing-event report
+//! can be checked against the intents the gateway requested (see
+//! `GatewayConfig::intents`).
+//!
+//! Names are twilight's `Intents` flag names, which match Discord's docs.
+
+/// The intent whose dispatches produce `event_type`
+///
+/// None for events Discord sends regardless of intents (interactions, the
+/// shard's own control events), for events the gateway derives rather than
+/// receives, and for unknown types.
+pub fn event_intent(event_type: &str) -> Option<&'static str> {
+    let intent = match event_type {
+        "guild.join" | "guild.leave" | "guild.update" => "GUILDS",
+        "thread.create" | "thread.update" | "thread.delete" | "thread.list.sync" | "channel.update" => "GUILDS",
+        "guild.ban.add" | "guild.ban.remove" => "GUILD_MODERATION",
+        // Onboarding and timeout expiry are read off member updates
+        "member.join" | "member.leave" | "member.update" | "member.onboarding.complete" | "member.timeout.expired"
+        | "member.chunk" => "GUILD_MEMBERS",
+        "message.create" | "message.update" | "message.embed.update" | "message.pin.update" | "message.delete"
+        | "message.delete.bulk" => "GUILD_MESSAGES",
+        "reaction.add" | "reaction.remove" => "GUILD_MESSAGE_REACTIONS",
+        "typing.start" => "GUILD_MESSAGE_TYPING",
+        "voice.state.update" => "GUILD_VOICE_STATES",
+        "automod.rule.create" | "automod.rule.update" | "automod.rule.delete" => "AUTO_MODERATION_CONFIGURATION",
+        "scheduled_event.user_remove" => "GUILD_SCHEDULED_EVENTS",
+        "presence.update" => "GUILD_PRESENCES",
+        _ => return None,
+    };
+    Some(intent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::event_type::EventType;
+    use twilight_model::gateway::Intents;
+
+    #[test]
+    fn member_events_map_to_guild_members() {
+        for event_type in ["member.join", "member.leave", "member.update"] {
+            assert_eq!(event_intent(event_type), Some("GUILD_MEMBERS"), "{event_type}");
+        }
+        assert_eq!(event_intent("interaction.create"), None, "interactions need no intent");
+    }
+
+    #[test]
+    fn intent_names_are_real_intents() {
+        for event_type in EventType::ALL {
+            if let Some(name) = event_intent(event_type.as_str()) {
+                assert!(Intents::from_name(name).is_some(), "{event_type}: {name}");
+            }
+        }
+    }
+}
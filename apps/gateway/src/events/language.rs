@@ -0,0 +1,50 @@
+//! Message language detection
+//!
+//! Opt-in enrichment that tags `message.create` with the detected language
+//! of the content, so consumers can route messages to language-specific
+//! moderation. Detection is statistical and unreliable on very short text,
+//! so messages below [`MIN_CHARS`] are left untagged (`null`).
+
+use serde_json::Value;
+
+/// Shortest content (in characters, after trimming) worth detecting
+pub const MIN_CHARS: usize = 20;
+
+/// Detected language of `content` as `{ "code", "confidence" }`, or null
+///
+/// `code` is the ISO 639-3 language code (`"eng"`, `"fra"`); `confidence`
+/// is in 0.0..=1.0. Null for short content or when no language is detected.
+pub fn detect_language(content: &str) -> Value {
+    let content = content.trim();
+    if content.chars().count() < MIN_CHARS {
+        return Value::Null;
+    }
+    match whatlang::detect(content) {
+        Some(info) => serde_json::json!({
+            "code": info.lang().code(),
+            "confidence": info.confidence(),
+        }),
+        None => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_english_and_french() {
+        let english = detect_language("Welcome to the server, please read the rules before posting anything");
+        let french = detect_language("Bienvenue sur le serveur, merci de lire les règles avant de publier quoi que ce soit");
+
+        assert_eq!(english["code"], "eng");
+        assert_eq!(french["code"], "fra");
+        assert!(english["confidence"].as_f64().unwrap() > 0.0);
+    }
+
+    #[test]
+    fn short_messages_are_skipped() {
+        assert_eq!(detect_language("gm"), Value::Null);
+        assert_eq!(detect_language("   lol   "), Value::Null);
+    }
+}
@@ -2,6 +2,61 @@
 //!
 //! Provides event serialization and routing to message broker.
 
+pub mod anomaly;
+pub mod batch;
+pub mod channel_filter;
+pub mod compressed;
+pub mod content;
+pub mod dedup;
+pub mod engagement;
+pub mod event_filter;
+pub mod event_id;
+pub mod event_type;
+pub mod fingerprint;
+pub mod fixed;
+pub mod flat;
+pub mod guild_filter;
+pub mod guild_hash;
+pub mod id_map;
+pub mod intent;
+pub mod language;
+pub mod logical_clock;
+pub mod outcome;
+pub mod pipeline;
+pub mod prewarm;
+pub mod priority;
+pub mod rename;
 pub mod serialize;
+pub mod shard_id;
+pub mod state;
+pub mod timestamp_unit;
 
-pub use serialize::{GatewayEvent, InteractionEvent, serialize_event};
+#[cfg(test)]
+pub(crate) mod test_support;
+
+pub use anomaly::{AnomalyConfig, AnomalyDetector};
+pub use batch::{encode_batch, serialize_batch, serialize_batch_by_guild, serialize_batch_bytes, BatchLimits, GatewayEventBatch};
+pub use channel_filter::ChannelDenylist;
+pub use compressed::{serialize_event_compressed, CompressedEvent};
+pub use content::{content_fields, is_content_field};
+pub use engagement::EngagementWeights;
+pub use event_filter::SerializeFilter;
+pub use event_id::deterministic_event_id;
+pub use event_type::EventType;
+pub use fingerprint::fingerprint;
+pub use fixed::{serialize_event_bytes, serialize_hot_event, FixedEvent, HotEvent, PresenceEvent, TypingEvent};
+pub use flat::{flatten_event, FlatData};
+pub use guild_filter::{GuildAllowlist, GuildDenylist};
+pub use guild_hash::GuildIdHasher;
+pub use id_map::IdMapper;
+pub use intent::event_intent;
+pub use logical_clock::{LogicalClock, SharedLogicalClock};
+pub use outcome::{SerializeMetrics, SerializeOutcome};
+pub use pipeline::EventPipeline;
+pub use prewarm::prewarm;
+pub use priority::{event_category, event_priority, EventCategory, EventPriority};
+pub use rename::RenameMap;
+pub use serialize::{GatewayEvent, InteractionEvent, SerializeConfig, CURRENT_SCHEMA_VERSION, SERIALIZATION_ERROR_KEY, partition_key, serialize_event, serialize_interaction, snowflake_timestamp_ms, validate_event, ShardInfo};
+pub use shard_id::ShardId;
+pub use state::{GuildSnapshot, MemberSnapshot, SerializerState};
+pub use timestamp_unit::{timestamp_millis, TimestampUnit};
@@ -0,0 +1,151 @@
+//! Serialization outcome counters
+//!
+//! `serialize_event` returns None alike for dispatches it has no arm for,
+//! heartbeats and filtered events, so its caller can't tell what it's
+//! dropping. A [`SerializeMetrics`] set on
+//! [`SerializeConfig`](super::serialize::SerializeConfig) counts each
+//! [`SerializeOutcome`], plus emitted events by event_type, for the gateway
+//! to export without repeating the serializer's match. Without one, nothing
+//! is counted.
+//!
+//! The EventPipeline counts the same outcomes for what it emits. Events it
+//! holds back on its own (a join deferred until screening, a duplicate) have
+//! their own metrics and aren't counted here.
+
+use crate::events::serialize::{GatewayEvent, SERIALIZATION_ERROR_KEY};
+use dashmap::DashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use twilight_model::gateway::event::Event;
+
+/// What the serializer did with one event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SerializeOutcome {
+    /// Emitted
+    Forwarded,
+    /// A dispatch the gateway doesn't forward
+    DroppedUnhandled,
+    /// A heartbeat or heartbeat ack, never forwarded
+    DroppedHeartbeat,
+    /// Dropped by the guild, channel or event_type filters
+    DroppedFiltered,
+    /// Emitted, but its `data` failed to serialize and carries the
+    /// [`SERIALIZATION_ERROR_KEY`] marker instead
+    SerializeError,
+}
+
+impl SerializeOutcome {
+    /// Outcome of an emitted event
+    pub fn emitted(payload: &GatewayEvent) -> Self {
+        if payload.data.get(SERIALIZATION_ERROR_KEY).is_some() {
+            Self::SerializeError
+        } else {
+            Self::Forwarded
+        }
+    }
+
+    /// Outcome of an event the serializer has no payload for
+    pub fn unhandled(event: &Event) -> Self {
+        match event {
+            Event::GatewayHeartbeat | Event::GatewayHeartbeatAck => Self::DroppedHeartbeat,
+            _ => Self::DroppedUnhandled,
+        }
+    }
+}
+
+/// Counters of [`SerializeOutcome`]s, shared by every shard given the config
+#[derive(Debug, Default)]
+pub struct SerializeMetrics {
+    forwarded: AtomicU64,
+    dropped_unhandled: AtomicU64,
+    dropped_heartbeat: AtomicU64,
+    dropped_filtered: AtomicU64,
+    serialize_error: AtomicU64,
+    /// Emitted events (forwarded or serialize_error), by event_type
+    by_event_type: DashMap<String, AtomicU64>,
+}
+
+impl SerializeMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count one outcome
+    pub fn record(&self, outcome: SerializeOutcome) {
+        self.counter(outcome).fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count an emitted event, by its outcome and event_type
+    pub fn record_emitted(&self, payload: &GatewayEvent) {
+        self.record_event_type(SerializeOutcome::emitted(payload), payload.event_type.as_str());
+    }
+
+    /// Count an emitted event of `event_type` with `outcome`, for
+    /// emitters that don't build a [`GatewayEvent`]
+    pub fn record_event_type(&self, outcome: SerializeOutcome, event_type: &str) {
+        self.record(outcome);
+        self.by_event_type
+            .entry(event_type.to_string())
+            .or_default()
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Events counted with `outcome`
+    pub fn count(&self, outcome: SerializeOutcome) -> u64 {
+        self.counter(outcome).load(Ordering::Relaxed)
+    }
+
+    /// Events of `event_type` emitted
+    pub fn event_type_count(&self, event_type: &str) -> u64 {
+        self.by_event_type.get(event_type).map_or(0, |count| count.load(Ordering::Relaxed))
+    }
+
+    /// Emitted counts for every event_type seen, for export
+    pub fn event_type_counts(&self) -> Vec<(String, u64)> {
+        self.by_event_type
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().load(Ordering::Relaxed)))
+            .collect()
+    }
+
+    fn counter(&self, outcome: SerializeOutcome) -> &AtomicU64 {
+        match outcome {
+            SerializeOutcome::Forwarded => &self.forwarded,
+            SerializeOutcome::DroppedUnhandled => &self.dropped_unhandled,
+            SerializeOutcome::DroppedHeartbeat => &self.dropped_heartbeat,
+            SerializeOutcome::DroppedFiltered => &self.dropped_filtered,
+            SerializeOutcome::SerializeError => &self.serialize_error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::serialize::{serialize_event, SerializeConfig};
+    use crate::events::shard_id::ShardId;
+    use crate::events::test_support::{dispatch, member_add};
+    use std::sync::Arc;
+
+    #[test]
+    fn counters_follow_each_outcome() {
+        let metrics = Arc::new(SerializeMetrics::new());
+        let config = SerializeConfig { metrics: Some(metrics.clone()), ..Default::default() };
+
+        assert!(serialize_event(&member_add(123456789012345678, 1, &[]), ShardId::ZERO, &config).is_some());
+        assert_eq!(metrics.count(SerializeOutcome::Forwarded), 1);
+        assert_eq!(metrics.event_type_count("member.join"), 1);
+
+        assert!(serialize_event(&Event::GatewayHeartbeatAck, ShardId::ZERO, &config).is_none());
+        assert_eq!(metrics.count(SerializeOutcome::DroppedHeartbeat), 1);
+
+        let webhooks = dispatch("WEBHOOKS_UPDATE", serde_json::json!({
+            "guild_id": "123456789012345678",
+            "channel_id": "333333333333333333"
+        }));
+        assert!(serialize_event(&webhooks, ShardId::ZERO, &config).is_none());
+        assert_eq!(metrics.count(SerializeOutcome::DroppedUnhandled), 1);
+
+        assert_eq!(metrics.count(SerializeOutcome::Forwarded), 1, "drops don't count as forwarded");
+        assert_eq!(metrics.event_type_counts(), vec![("member.join".to_string(), 1)]);
+    }
+}
@@ -0,0 +1,988 @@
+//! Stateful per-shard serialization
+//!
+//! `serialize_event` is a pure function of a single event. Payload options
+//! that depend on what the shard saw earlier go through an [`EventPipeline`],
+//! which owns the shard's [`SerializerState`] and post-processes the
+//! stateless payload.
+
+use crate::error::GatewayError;
+use crate::events::anomaly::AnomalyDetector;
+use crate::events::dedup::Deduplicator;
+use crate::events::event_id::deterministic_event_id;
+use crate::events::flat::flatten_event;
+use crate::events::fingerprint::fingerprint;
+use crate::events::intent::event_intent;
+use crate::events::outcome::SerializeOutcome;
+use crate::events::serialize::{
+    banned_member_leave_event, member_join_event, member_onboarding_complete_event, member_timeout_expired_event,
+    message_pin_update_event, monotonic_ns, now_millis, partition_key, serialize_candidate, validate_event, GatewayEvent, SerializeConfig, ShardInfo, CURRENT_SCHEMA_VERSION, PRODUCER_VERSION,
+};
+use crate::events::shard_id::ShardId;
+use crate::events::state::{GuildSnapshot, MessageSnapshot, SerializerState};
+use metrics::counter;
+use std::sync::Arc;
+use std::time::Instant;
+use twilight_model::gateway::event::Event;
+use twilight_model::gateway::payload::incoming::GuildCreate;
+use twilight_model::gateway::OpCode;
+use twilight_model::guild::MemberFlags;
+
+/// Serializer for a single shard's event stream
+pub struct EventPipeline {
+    shard_id: ShardId,
+    config: SerializeConfig,
+    state: SerializerState,
+    anomaly: Option<AnomalyDetector>,
+    dedup: Option<Deduplicator>,
+    shard_info: Option<ShardInfo>,
+    /// Unix milliseconds, for timeout expiry
+    clock: Arc<dyn Fn() -> u64 + Send + Sync>,
+}
+
+impl EventPipeline {
+    /// Create a pipeline for `shard_id` with empty state
+    pub fn new(shard_id: ShardId, config: SerializeConfig) -> Self {
+        Self {
+            shard_id,
+            anomaly: config.anomaly.clone().map(AnomalyDetector::new),
+            dedup: config.dedup_window.map(Deduplicator::new),
+            config,
+            state: SerializerState::default(),
+            shard_info: None,
+            clock: Arc::new(now_millis),
+        }
+    }
+
+    /// Resume from a [`SerializerState`] restored from a snapshot; the next
+    /// forwarded event continues the snapshot's sequence
+    pub fn with_state(mut self, state: SerializerState) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// Set the shard's topology, attached to payloads when the config's
+    /// `include_shard_info` is set
+    pub fn with_shard_info(mut self, shard_info: ShardInfo) -> Self {
+        self.shard_info = Some(shard_info);
+        self
+    }
+
+    /// Read the time (Unix milliseconds) from `clock` instead of the system
+    /// clock
+    pub fn with_clock(mut self, clock: impl Fn() -> u64 + Send + Sync + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Get the shard-local state
+    pub fn state(&self) -> &SerializerState {
+        &self.state
+    }
+
+    /// [`SerializerState::resolve_channel_id`] for an event this pipeline
+    /// emitted, mapping a hashed `guild_id` back through the
+    /// `guild_id_hashing` sidecar first
+    pub fn resolve_channel_id(&self, event: &GatewayEvent) -> Option<String> {
+        let guild_id = match (&self.config.guild_id_hashing, &event.guild_id) {
+            (Some(hasher), Some(hashed)) => hasher.reverse(hashed),
+            (_, guild_id) => guild_id.clone(),
+        };
+        self.state.resolve_channel_id_in(event, guild_id.as_deref())
+    }
+
+    /// Serialize an event, consulting and updating shard-local state
+    ///
+    /// Returns the payloads to publish: none for events we don't forward
+    /// (same as `serialize_event`), and several when the [`SerializeConfig`]
+    /// options or the shard's state derive more events from this one.
+    /// Payloads that fail [`validate_event`] are an error; rejected events
+    /// leave state untouched and don't consume a sequence.
+    pub fn process(&mut self, event: &Event) -> Result<Vec<GatewayEvent>, GatewayError> {
+        self.process_frame(event, None)
+    }
+
+    /// [`process`](Self::process) an event along with the Discord sequence
+    /// of the dispatch frame it came from, for `protocol_debug`
+    pub fn process_frame(&mut self, event: &Event, gateway_seq: Option<u64>) -> Result<Vec<GatewayEvent>, GatewayError> {
+        let frame = self.config.protocol_debug.then(|| gateway_frame(event, gateway_seq));
+        let candidate = serialize_candidate(event, self.shard_id, &self.config);
+        let dropped = candidate.as_ref().err().copied();
+        let mut payload = candidate.ok();
+        if let Some(ref payload) = payload {
+            validate_event(payload)?;
+        }
+        let now = (self.clock)();
+        let mut timeouts_expired: Vec<GatewayEvent> = if self.config.timeout_expiry {
+            self.state
+                .take_expired_timeouts(now)
+                .into_iter()
+                .map(|(guild_id, user_id, until)| member_timeout_expired_event(guild_id, user_id, self.shard_id, until))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let mut deferred_join = None;
+        let mut onboarding_complete = None;
+        let mut ban_leave = None;
+
+        match event {
+            Event::MemberAdd(member) => {
+                let snapshot = self.state.member_mut(member.guild_id.get(), member.user.id.get());
+                snapshot.roles = sorted_roles(member.roles.iter().map(|r| r.get()));
+                snapshot.pending = self.config.join_after_screening && member.pending;
+                snapshot.onboarded = Some(member.flags.contains(MemberFlags::COMPLETED_ONBOARDING));
+                if snapshot.pending {
+                    payload = None;
+                }
+            }
+            Event::MemberUpdate(member) => {
+                let (guild_id, user_id) = (member.guild_id.get(), member.user.id.get());
+                let roles = sorted_roles(member.roles.iter().map(|r| r.get()));
+
+                let was_pending = self.state.member(guild_id, user_id).is_some_and(|m| m.pending);
+                let onboarded = member.flags.map(|f| f.contains(MemberFlags::COMPLETED_ONBOARDING));
+                // Only a transition we saw counts; a member first seen
+                // already onboarded may have finished long ago
+                let was_onboarded = self.state.member(guild_id, user_id).and_then(|m| m.onboarded);
+                if was_onboarded == Some(false) && onboarded == Some(true) {
+                    let timestamp = payload.as_ref().map_or(0, |p| p.timestamp);
+                    onboarding_complete = Some(member_onboarding_complete_event(
+                        member.guild_id,
+                        member.user.id,
+                        self.shard_id,
+                        timestamp,
+                    ));
+                }
+                if was_pending && !member.pending {
+                    let timestamp = payload.as_ref().map_or(0, |p| p.timestamp);
+                    let flags = member.flags.unwrap_or(MemberFlags::empty());
+                    deferred_join = Some(member_join_event(member.guild_id, &member.user, flags, self.shard_id, timestamp));
+                }
+
+                // Omit `roles` only when we know the prior set and it didn't
+                // change; an explicit `[]` then always means "all roles removed".
+                let previous = self.state.member(guild_id, user_id).map(|m| m.roles.as_slice());
+                let unchanged = previous.is_some_and(|previous| previous == roles);
+                if let Some(data) = payload.as_mut().and_then(|p| p.data.as_object_mut()) {
+                    if self.config.role_diffs {
+                        if let Some(previous) = previous {
+                            let (added, removed) = role_diff(previous, &roles);
+                            data.insert("roles_added".to_string(), added.into());
+                            data.insert("roles_removed".to_string(), removed.into());
+                        }
+                    }
+                    if self.config.omit_unchanged_roles && unchanged {
+                        data.remove("roles");
+                    }
+                }
+                // Scheduled only for updates that pass the guild filter
+                if self.config.timeout_expiry && payload.is_some() {
+                    let until = member
+                        .communication_disabled_until
+                        .map(|t| (t.as_micros() / 1000) as u64)
+                        .filter(|&until| until > now);
+                    self.state.set_timeout(guild_id, user_id, until);
+                }
+                let snapshot = self.state.member_mut(guild_id, user_id);
+                snapshot.roles = roles;
+                snapshot.pending = was_pending && member.pending;
+                if onboarded.is_some() {
+                    snapshot.onboarded = onboarded;
+                }
+            }
+            Event::PresenceUpdate(presence) if self.config.compact_presence => {
+                let previous = self.state.set_presence(presence.guild_id.get(), presence.user.id().get(), presence.status);
+                if previous == Some(presence.status) {
+                    payload = None;
+                }
+            }
+            Event::MemberRemove(member) => {
+                let (guild_id, user_id) = (member.guild_id.get(), member.user.id.get());
+                self.state.remove_member(guild_id, user_id);
+                if self.config.leave_on_ban {
+                    if self.state.take_ban_leave(guild_id, user_id) {
+                        payload = None;
+                    } else {
+                        self.state.record_leave(guild_id, user_id);
+                    }
+                }
+            }
+            Event::BanAdd(ban) if self.config.leave_on_ban => {
+                let (guild_id, user_id) = (ban.guild_id.get(), ban.user.id.get());
+                // Bans by id can target users who never joined; if they are
+                // members after all, their real leave goes through
+                let known = self.state.member(guild_id, user_id).is_some();
+                self.state.remove_member(guild_id, user_id);
+                // A leave that arrived first already counted the departure
+                if !self.state.take_recent_leave(guild_id, user_id) && known {
+                    if let Some(ref ban) = payload {
+                        ban_leave = Some(banned_member_leave_event(ban));
+                        self.state.expect_ban_leave(guild_id, user_id);
+                    }
+                }
+            }
+            Event::MessageCreate(message) => {
+                self.state.set_message(message.id.get(), MessageSnapshot {
+                    pinned: message.pinned,
+                    edited_at: message.edited_timestamp.map(|t| t.as_micros()),
+                });
+            }
+            Event::MessageUpdate(message) => {
+                let current = MessageSnapshot {
+                    pinned: message.pinned,
+                    edited_at: message.edited_timestamp.map(|t| t.as_micros()),
+                };
+                // An edit moves edited_timestamp; a pin alone doesn't
+                let previous = self.state.set_message(message.id.get(), current);
+                let pin_only = previous.is_some_and(|p| p.pinned != current.pinned && p.edited_at == current.edited_at);
+                if pin_only {
+                    payload = payload.map(|update| message_pin_update_event(update, message.id, message.pinned));
+                }
+            }
+            Event::MessageDelete(message) => {
+                self.state.remove_message(message.id.get());
+            }
+            Event::MessageDeleteBulk(messages) => {
+                for id in &messages.ids {
+                    self.state.remove_message(id.get());
+                }
+            }
+            Event::GuildCreate(guild) => {
+                // Consumers rebuild from the full guild.join
+                self.state.clear_guild_update(guild.id().get());
+                if let GuildCreate::Available(guild) = guild.as_ref() {
+                    self.state.set_guild(guild.id.get(), GuildSnapshot {
+                        system_channel_id: guild.system_channel_id.map(|id| id.get()),
+                        rules_channel_id: guild.rules_channel_id.map(|id| id.get()),
+                    });
+                }
+            }
+            Event::GuildUpdate(guild) => {
+                self.state.set_guild(guild.id.get(), GuildSnapshot {
+                    system_channel_id: guild.system_channel_id.map(|id| id.get()),
+                    rules_channel_id: guild.rules_channel_id.map(|id| id.get()),
+                });
+                if self.config.guild_update_deltas {
+                    if let Some(serde_json::Value::Object(data)) = payload.as_mut().map(|p| &mut p.data) {
+                        if let Some(previous) = self.state.set_guild_update(guild.id.get(), data.clone()) {
+                            *data = merge_patch(&previous, data);
+                        }
+                    }
+                }
+            }
+            Event::GuildDelete(guild) if guild.unavailable != Some(true) => {
+                self.state.remove_guild(guild.id.get());
+            }
+            Event::Ready(_) => {
+                self.state.new_session();
+            }
+            _ => {}
+        }
+
+        // Joins and onboarding completions are built from the raw member
+        // update, so they follow it through serialize_event's guild filter
+        // and guild id hashing (timeout expiries were only scheduled for
+        // updates that passed the filter)
+        if payload.is_none() {
+            deferred_join = None;
+            onboarding_complete = None;
+        }
+        if let Some(ref hasher) = self.config.guild_id_hashing {
+            deferred_join
+                .iter_mut()
+                .chain(onboarding_complete.iter_mut())
+                .chain(timeouts_expired.iter_mut())
+                .for_each(|p| hasher.apply(p));
+        }
+        // After hashing, like serialize_event; the ban's leave derives from
+        // the ban's (already hashed) envelope
+        if self.config.deterministic_ids {
+            deferred_join
+                .iter_mut()
+                .chain(onboarding_complete.iter_mut())
+                .chain(timeouts_expired.iter_mut())
+                .chain(ban_leave.iter_mut())
+                .for_each(|p| p.event_id = deterministic_event_id(p));
+        }
+
+        let mut payloads = Vec::new();
+        let candidates = timeouts_expired.into_iter().chain(deferred_join).chain(payload).chain(onboarding_complete).chain(ban_leave);
+        if let (Some(metrics), Some(outcome)) = (&self.config.metrics, dropped) {
+            metrics.record(outcome);
+        }
+        for mut payload in candidates {
+            if !self.config.event_filter.is_enabled(&payload.event_type) {
+                if let Some(ref metrics) = self.config.metrics {
+                    metrics.record(SerializeOutcome::DroppedFiltered);
+                }
+                continue;
+            }
+            if self.dedup.as_mut().is_some_and(|d| d.is_duplicate(&payload, Instant::now())) {
+                counter!("gateway_events_deduplicated_total", "event_type" => payload.event_type.to_string()).increment(1);
+                continue;
+            }
+            if self.config.flat_data {
+                flatten_event(&mut payload);
+            }
+            let alert = self.anomaly.as_mut().and_then(|d| d.observe(&payload, Instant::now()));
+            if let Some(ref metrics) = self.config.metrics {
+                metrics.record_emitted(&payload);
+            }
+            payloads.push(self.stamp(payload, frame));
+            if let Some(alert) = alert.filter(|a| self.config.event_filter.is_enabled(&a.event_type)) {
+                if let Some(ref metrics) = self.config.metrics {
+                    metrics.record_emitted(&alert);
+                }
+                payloads.push(self.stamp(alert, frame));
+            }
+        }
+
+        Ok(payloads
+            .into_iter()
+            .flat_map(|p| self.config.renames.apply(p))
+            .collect())
+    }
+
+    /// Assign envelope fields the pipeline owns to a forwarded payload
+    fn stamp(&mut self, mut payload: GatewayEvent, frame: Option<(OpCode, Option<u64>)>) -> GatewayEvent {
+        payload.schema_version = CURRENT_SCHEMA_VERSION;
+        // Payloads the pipeline generated itself have none yet
+        payload.partition_key = partition_key(payload.guild_id.as_deref(), payload.shard_id);
+        payload.sequence = Some(self.state.next_sequence());
+        payload.producer_epoch = self.state.producer_epoch();
+        if let Some(ref clock) = self.config.logical_clock {
+            payload.logical_clock = Some(clock.tick());
+        }
+        if self.config.include_shard_info {
+            payload.shard_info = self.shard_info;
+        }
+        if self.config.include_cache_epoch && payload.event_type.starts_with("guild.") {
+            payload.cache_epoch = Some(self.state.cache_epoch());
+        }
+        // Payloads the pipeline generated itself (anomaly alerts, deferred
+        // joins) didn't pass through serialize_event
+        if self.config.include_timestamp_ns && payload.timestamp_ns.is_none() {
+            payload.timestamp_ns = Some(monotonic_ns());
+        }
+        if self.config.include_producer_version {
+            payload.producer_version = Some(PRODUCER_VERSION.to_string());
+        }
+        // The pipeline may have changed the type (pin-only message updates)
+        if self.config.include_schema_fingerprint {
+            payload.schema_fingerprint = Some(fingerprint(&payload.event_type));
+        }
+        if self.config.include_intent {
+            payload.intent = event_intent(&payload.event_type).map(str::to_string);
+        }
+        payload.engagement_weight = self.config.engagement_weights.weight(&payload.event_type);
+        if let Some((op, seq)) = frame {
+            payload.gateway_op = Some(op as u8);
+            payload.gateway_seq = seq;
+        }
+        if let (Some(mapper), Some(user_id)) = (&self.config.id_mapper, &payload.user_id) {
+            payload.internal_user_id = mapper.internal_user_id(user_id);
+        }
+        // Last: everything before works in milliseconds
+        self.config.timestamp_unit.apply(&mut payload);
+        payload
+    }
+}
+
+/// Opcode and sequence of the gateway frame `event` arrived in
+///
+/// Control events come from non-dispatch frames, which carry no sequence.
+fn gateway_frame(event: &Event, gateway_seq: Option<u64>) -> (OpCode, Option<u64>) {
+    match event {
+        Event::GatewayReconnect => (OpCode::Reconnect, None),
+        _ => (OpCode::Dispatch, gateway_seq),
+    }
+}
+
+/// The JSON merge patch (RFC 7396) turning `previous` into `current`, for
+/// flat objects: changed or added keys with their new value, removed keys
+/// as null
+fn merge_patch(
+    previous: &serde_json::Map<String, serde_json::Value>,
+    current: &serde_json::Map<String, serde_json::Value>,
+) -> serde_json::Map<String, serde_json::Value> {
+    let mut patch: serde_json::Map<_, _> = current
+        .iter()
+        .filter(|(key, value)| previous.get(*key) != Some(value))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    for key in previous.keys().filter(|key| !current.contains_key(*key)) {
+        patch.insert(key.clone(), serde_json::Value::Null);
+    }
+    patch
+}
+
+/// Roles in `current` but not `previous`, and in `previous` but not
+/// `current`, as snowflake strings (both inputs sorted)
+fn role_diff(previous: &[u64], current: &[u64]) -> (Vec<String>, Vec<String>) {
+    let added = current.iter().filter(|r| previous.binary_search(r).is_err());
+    let removed = previous.iter().filter(|r| current.binary_search(r).is_err());
+    (added.map(u64::to_string).collect(), removed.map(u64::to_string).collect())
+}
+
+fn sorted_roles(roles: impl Iterator<Item = u64>) -> Vec<u64> {
+    let mut roles: Vec<u64> = roles.collect();
+    roles.sort_unstable();
+    roles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::anomaly::{AnomalyConfig, DEFAULT_WINDOW};
+    use crate::events::event_filter::SerializeFilter;
+    use crate::events::guild_hash::GuildIdHasher;
+    use crate::events::logical_clock::SharedLogicalClock;
+    use crate::events::rename::RenameMap;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::Duration;
+    use crate::events::test_support::{
+        ban_add, dispatch, guild_create, guild_update, member_add, member_remove, member_update, message, ready, user,
+    };
+
+    const GUILD: u64 = 123456789012345678;
+    const USER: u64 = 987654321098765432;
+
+    fn omit_unchanged() -> SerializeConfig {
+        SerializeConfig { omit_unchanged_roles: true, ..Default::default() }
+    }
+
+    #[test]
+    fn unchanged_roles_are_omitted() {
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, omit_unchanged());
+        pipeline.process(&member_add(GUILD, USER, &[111, 222])).unwrap();
+
+        // Nickname-only update: same role set (in a different order)
+        let payload = pipeline.process(&member_update(GUILD, USER, &[222, 111])).unwrap().remove(0);
+        assert!(payload.data.get("roles").is_none(), "unchanged roles must be omitted");
+        assert!(payload.data.get("nick").is_some());
+    }
+
+    #[test]
+    fn cleared_roles_emit_empty_array() {
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, omit_unchanged());
+        pipeline.process(&member_add(GUILD, USER, &[111, 222])).unwrap();
+
+        let payload = pipeline.process(&member_update(GUILD, USER, &[])).unwrap().remove(0);
+        assert_eq!(payload.data["roles"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn unknown_prior_state_keeps_roles() {
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, omit_unchanged());
+
+        let payload = pipeline.process(&member_update(GUILD, USER, &[])).unwrap().remove(0);
+        assert_eq!(payload.data["roles"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn restored_state_continues_the_sequence() {
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, omit_unchanged());
+        pipeline.process(&member_add(GUILD, USER, &[111])).unwrap();
+        for _ in 0..4 {
+            pipeline.process(&member_update(GUILD, USER, &[111])).unwrap();
+        }
+        assert_eq!(pipeline.state().sequence(), 5);
+        let epoch = pipeline.state().producer_epoch();
+        assert!(epoch.is_some());
+        let snapshot = pipeline.state().snapshot().unwrap();
+
+        // Restart
+        let state = SerializerState::restore(&snapshot).unwrap();
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, omit_unchanged()).with_state(state);
+        let payload = pipeline.process(&member_update(GUILD, USER, &[111])).unwrap().remove(0);
+        assert_eq!(payload.sequence, Some(6));
+        assert_eq!(payload.producer_epoch, epoch, "a restored sequence keeps its epoch");
+        // The member cache survived too: roles are still known unchanged
+        assert!(payload.data.get("roles").is_none());
+
+        assert!(matches!(SerializerState::restore(b"not json"), Err(GatewayError::StateSnapshot(_))));
+    }
+
+    #[test]
+    fn ban_emits_one_correlated_leave_in_either_order() {
+        let config = SerializeConfig { leave_on_ban: true, ..Default::default() };
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, config);
+        pipeline.process(&member_add(GUILD, USER, &[])).unwrap();
+
+        // Ban first: the synthetic leave follows it, the real one is dropped
+        let payloads = pipeline.process(&ban_add(GUILD, USER)).unwrap();
+        let types: Vec<_> = payloads.iter().map(|p| p.event_type.as_str()).collect();
+        assert_eq!(types, ["guild.ban.add", "member.leave"]);
+        assert_eq!(payloads[1].data["reason"], "banned");
+        assert_eq!(payloads[1].data["ban_event_id"], payloads[0].event_id.as_str());
+        assert_eq!(payloads[1].user_id.as_deref(), Some("987654321098765432"));
+        assert!(pipeline.process(&member_remove(GUILD, USER)).unwrap().is_empty());
+
+        // Leave first: the ban adds no second leave
+        let other = 42;
+        assert_eq!(pipeline.process(&member_remove(GUILD, other)).unwrap()[0].data, serde_json::Value::Null);
+        let payloads = pipeline.process(&ban_add(GUILD, other)).unwrap();
+        assert_eq!(payloads.len(), 1);
+        assert_eq!(payloads[0].event_type, "guild.ban.add");
+    }
+
+    #[test]
+    fn banning_a_non_member_emits_no_leave() {
+        let config = SerializeConfig { leave_on_ban: true, ..Default::default() };
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, config);
+
+        // A pre-emptive ban by id of a user who never joined
+        let payloads = pipeline.process(&ban_add(GUILD, USER)).unwrap();
+        assert_eq!(payloads.len(), 1);
+        assert_eq!(payloads[0].event_type, "guild.ban.add");
+
+        // Had they been an untracked member, Discord's own leave still counts
+        assert_eq!(pipeline.process(&member_remove(GUILD, USER)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn role_diffs_against_last_known_roles() {
+        let config = SerializeConfig { role_diffs: true, ..Default::default() };
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, config);
+        pipeline.process(&member_add(GUILD, USER, &[111, 222, 333])).unwrap();
+
+        let payload = pipeline.process(&member_update(GUILD, USER, &[444, 111, 333])).unwrap().remove(0);
+        assert_eq!(payload.data["roles_added"], serde_json::json!(["444"]));
+        assert_eq!(payload.data["roles_removed"], serde_json::json!(["222"]));
+        assert_eq!(payload.data["roles"].as_array().unwrap().len(), 3, "full list kept");
+
+        let payload = pipeline.process(&member_update(GUILD, USER, &[111, 333, 444])).unwrap().remove(0);
+        assert_eq!(payload.data["roles_added"], serde_json::json!([]));
+        assert_eq!(payload.data["roles_removed"], serde_json::json!([]));
+
+        // No prior set to diff against
+        let payload = pipeline.process(&member_update(GUILD, 42, &[111])).unwrap().remove(0);
+        assert!(payload.data.get("roles_added").is_none());
+    }
+
+    #[test]
+    fn forwarded_events_get_increasing_sequence() {
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, SerializeConfig::default());
+        let first = pipeline.process(&member_add(GUILD, USER, &[])).unwrap().remove(0);
+        assert!(pipeline.process(&Event::GatewayHeartbeatAck).unwrap().is_empty());
+        let second = pipeline.process(&member_update(GUILD, USER, &[])).unwrap().remove(0);
+
+        assert_eq!(first.sequence, Some(1));
+        assert_eq!(second.sequence, Some(2), "dropped events must not consume a sequence");
+    }
+
+    #[test]
+    fn renamed_event_is_published_under_both_types() {
+        let mut renames = RenameMap::new();
+        renames.insert("member.join", "member.created");
+        let config = SerializeConfig { renames, ..Default::default() };
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, config);
+
+        let payloads = pipeline.process(&member_add(GUILD, USER, &[])).unwrap();
+        let types: Vec<&str> = payloads.iter().map(|p| p.event_type.as_str()).collect();
+        assert_eq!(types, vec!["member.join", "member.created"]);
+        assert!(payloads.iter().all(|p| p.sequence == Some(1)));
+    }
+
+    #[test]
+    fn flat_data_mode_flattens_after_role_diffing() {
+        let config = SerializeConfig { flat_data: true, ..Default::default() };
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, config);
+
+        let payload = pipeline.process(&member_update(GUILD, USER, &[111, 222])).unwrap().remove(0);
+        assert_eq!(payload.data["roles.0"], "111");
+        assert_eq!(payload.data["roles.1"], "222");
+        assert_eq!(payload.data["nick"], "testnick");
+    }
+
+    #[test]
+    fn join_burst_emits_anomaly_after_triggering_event() {
+        let config = SerializeConfig {
+            anomaly: Some(AnomalyConfig { threshold: 3, window: DEFAULT_WINDOW }),
+            ..Default::default()
+        };
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, config);
+
+        let mut published = Vec::new();
+        for user in 1..=6 {
+            published.extend(pipeline.process(&member_add(GUILD, user, &[])).unwrap());
+        }
+
+        let types: Vec<&str> = published.iter().map(|p| p.event_type.as_str()).collect();
+        assert_eq!(types, vec![
+            "member.join", "member.join", "member.join", "member.join", "guild.anomaly",
+            "member.join", "member.join",
+        ]);
+        let alert = &published[4];
+        assert_eq!(alert.data["event_type"], "member.join");
+        assert_eq!(alert.guild_id.as_deref(), Some("123456789012345678"));
+        assert_eq!(alert.sequence, Some(5));
+    }
+
+    #[test]
+    fn guild_create_caches_system_channel_for_resolution() {
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, SerializeConfig::default());
+        pipeline.process(&guild_create(GUILD, Some(444444444444444444))).unwrap();
+
+        let join = pipeline.process(&member_add(GUILD, USER, &[])).unwrap().remove(0);
+        assert_eq!(join.channel_id, None, "wire payload is unchanged");
+        assert_eq!(pipeline.state().resolve_channel_id(&join).as_deref(), Some("444444444444444444"));
+    }
+
+    #[test]
+    fn channel_resolution_sees_through_hashed_guild_ids() {
+        let config = SerializeConfig {
+            guild_id_hashing: Some(Arc::new(GuildIdHasher::new(b"analytics-key"))),
+            ..Default::default()
+        };
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, config);
+        pipeline.process(&guild_create(GUILD, Some(444444444444444444))).unwrap();
+
+        let join = pipeline.process(&member_add(GUILD, USER, &[])).unwrap().remove(0);
+        assert_ne!(join.guild_id.as_deref(), Some(GUILD.to_string().as_str()));
+        assert_eq!(pipeline.state().resolve_channel_id(&join), None, "the hash isn't a guild the state knows");
+        assert_eq!(pipeline.resolve_channel_id(&join).as_deref(), Some("444444444444444444"));
+    }
+
+    #[test]
+    fn shard_info_is_attached_for_configured_topology() {
+        let shard_id = ShardId::new(30, 60).unwrap();
+        let topology = ShardInfo::for_shard(shard_id, 60, 25);
+        let config = SerializeConfig { include_shard_info: true, ..Default::default() };
+        let mut pipeline = EventPipeline::new(shard_id, config).with_shard_info(topology);
+
+        let payload = pipeline.process(&member_add(GUILD, USER, &[])).unwrap().remove(0);
+        assert_eq!(payload.shard_info, Some(ShardInfo {
+            shard_id: 30,
+            shard_count: 60,
+            cluster_id: 1,
+            cluster_count: 3,
+        }));
+
+        // Off by default, even with a topology set
+        let mut pipeline = EventPipeline::new(shard_id, SerializeConfig::default()).with_shard_info(topology);
+        let payload = pipeline.process(&member_add(GUILD, USER, &[])).unwrap().remove(0);
+        assert_eq!(payload.shard_info, None);
+    }
+
+    #[test]
+    fn logical_clock_increases_across_shards() {
+        let config = SerializeConfig {
+            logical_clock: Some(Arc::new(SharedLogicalClock::new())),
+            ..Default::default()
+        };
+        let mut shard_0 = EventPipeline::new(ShardId::ZERO, config.clone());
+        let mut shard_1 = EventPipeline::new(ShardId::new(1, 2).unwrap(), config);
+
+        let clocks: Vec<u64> = [
+            shard_0.process(&member_add(GUILD, USER, &[])),
+            shard_1.process(&member_add(GUILD + 1, USER, &[])),
+            shard_0.process(&member_update(GUILD, USER, &[])),
+            shard_1.process(&member_remove(GUILD + 1, USER)),
+        ]
+        .into_iter()
+        .flat_map(Result::unwrap)
+        .map(|p| p.logical_clock.expect("clock is stamped"))
+        .collect();
+        assert!(clocks.windows(2).all(|w| w[0] < w[1]), "{clocks:?}");
+
+        let unclocked = EventPipeline::new(ShardId::ZERO, SerializeConfig::default())
+            .process(&member_add(GUILD, USER, &[]))
+            .unwrap()
+            .remove(0);
+        assert_eq!(unclocked.logical_clock, None);
+    }
+
+    #[test]
+    fn pin_only_update_becomes_pin_event() {
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, SerializeConfig::default());
+        let update = |overrides| dispatch("MESSAGE_UPDATE", message(overrides));
+
+        // Never seen: can't tell a pin from other updates
+        let unseen = pipeline.process(&update(serde_json::json!({ "pinned": true }))).unwrap().remove(0);
+        assert_eq!(unseen.event_type, "message.update");
+
+        let unpin = pipeline.process(&update(serde_json::json!({ "pinned": false }))).unwrap().remove(0);
+        assert_eq!(unpin.event_type, "message.pin.update");
+        assert_eq!(unpin.data["pinned"], false);
+        assert_eq!(unpin.user_id, None);
+
+        // An edit that also pins is still an edit
+        let edit = update(serde_json::json!({
+            "pinned": true,
+            "edited_timestamp": "2023-01-01T00:05:00.000000+00:00"
+        }));
+        assert_eq!(pipeline.process(&edit).unwrap().remove(0).event_type, "message.update");
+    }
+
+    #[test]
+    fn guild_update_delta_carries_only_changed_fields() {
+        let config = SerializeConfig { guild_update_deltas: true, ..Default::default() };
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, config);
+
+        // No prior update: every field
+        let first = pipeline.process(&guild_update(GUILD, "Test Guild")).unwrap().remove(0);
+        assert_eq!(first.event_type, "guild.update");
+        assert_eq!(first.data["owner_id"], "987654321098765432");
+        assert!(first.data.get("rules_channel_id").is_some());
+
+        let delta = pipeline.process(&guild_update(GUILD, "Spice Traders")).unwrap().remove(0);
+        assert_eq!(delta.data, serde_json::json!({ "name": "Spice Traders" }));
+
+        // A GUILD_CREATE resets the baseline
+        pipeline.process(&guild_create(GUILD, None)).unwrap();
+        let full = pipeline.process(&guild_update(GUILD, "Spice Traders")).unwrap().remove(0);
+        assert_eq!(full.data.as_object().unwrap().len(), first.data.as_object().unwrap().len());
+    }
+
+    #[test]
+    fn merge_patch_nulls_removed_keys() {
+        let previous = serde_json::json!({ "name": "a", "icon": "abc" });
+        let current = serde_json::json!({ "name": "a" });
+        let patch = merge_patch(previous.as_object().unwrap(), current.as_object().unwrap());
+        assert_eq!(serde_json::Value::Object(patch), serde_json::json!({ "icon": null }));
+    }
+
+    #[test]
+    fn protocol_debug_attaches_gateway_frame() {
+        let config = SerializeConfig { protocol_debug: true, forward_reconnects: true, ..Default::default() };
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, config);
+
+        let payload = pipeline.process_frame(&member_add(GUILD, USER, &[]), Some(42)).unwrap().remove(0);
+        assert_eq!(payload.gateway_op, Some(0));
+        assert_eq!(payload.gateway_seq, Some(42));
+        assert_eq!(payload.sequence, Some(1), "our sequence is independent of Discord's");
+
+        // OP 7 frames carry no sequence
+        let payload = pipeline.process_frame(&Event::GatewayReconnect, Some(42)).unwrap().remove(0);
+        assert_eq!(payload.gateway_op, Some(7));
+        assert_eq!(payload.gateway_seq, None);
+
+        // Off by default
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, SerializeConfig::default());
+        let payload = pipeline.process_frame(&member_add(GUILD, USER, &[]), Some(42)).unwrap().remove(0);
+        let json = serde_json::to_value(&payload).unwrap();
+        assert!(json.get("gateway_op").is_none());
+        assert!(json.get("gateway_seq").is_none());
+    }
+
+    fn screening_member(event_name: &str, pending: bool) -> Event {
+        dispatch(event_name, serde_json::json!({
+            "guild_id": GUILD.to_string(),
+            "user": user(USER),
+            "roles": [],
+            "nick": null,
+            "joined_at": "2023-01-01T00:00:00.000000+00:00",
+            "deaf": false,
+            "mute": false,
+            "flags": 0,
+            "pending": pending
+        }))
+    }
+
+    #[test]
+    fn pending_join_is_emitted_once_screening_passes() {
+        let config = SerializeConfig { join_after_screening: true, ..Default::default() };
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, config);
+
+        let mut published = Vec::new();
+        published.extend(pipeline.process(&screening_member("GUILD_MEMBER_ADD", true)).unwrap());
+        assert!(published.is_empty(), "pending join is held back");
+        // An unrelated update while still pending doesn't release it
+        published.extend(pipeline.process(&screening_member("GUILD_MEMBER_UPDATE", true)).unwrap());
+        published.extend(pipeline.process(&screening_member("GUILD_MEMBER_UPDATE", false)).unwrap());
+        published.extend(pipeline.process(&screening_member("GUILD_MEMBER_UPDATE", false)).unwrap());
+
+        let types: Vec<&str> = published.iter().map(|p| p.event_type.as_str()).collect();
+        assert_eq!(types, vec!["member.update", "member.join", "member.update", "member.update"]);
+        let join = &published[1];
+        assert_eq!(join.user_id.as_deref(), Some("987654321098765432"));
+        assert_eq!(join.data["username"], "testuser");
+        assert_eq!(join.sequence, Some(2));
+    }
+
+    fn timed_out_member(until: &str) -> Event {
+        dispatch("GUILD_MEMBER_UPDATE", serde_json::json!({
+            "guild_id": GUILD.to_string(),
+            "user": user(USER),
+            "roles": [],
+            "nick": null,
+            "joined_at": "2023-01-01T00:00:00.000000+00:00",
+            "communication_disabled_until": until
+        }))
+    }
+
+    #[test]
+    fn timeout_expiry_fires_once_the_timeout_lifts() {
+        // 2023-11-14T22:13:20Z
+        const TIMED_OUT_AT: u64 = 1_700_000_000_000;
+        let clock = Arc::new(AtomicU64::new(TIMED_OUT_AT));
+        let config = SerializeConfig { timeout_expiry: true, ..Default::default() };
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, config).with_clock({
+            let clock = clock.clone();
+            move || clock.load(Ordering::Relaxed)
+        });
+
+        let update = pipeline.process(&timed_out_member("2023-11-14T22:23:20.000000+00:00")).unwrap();
+        assert_eq!(update[0].data["communication_disabled_until"], "2023-11-14T22:23:20.000000+00:00");
+
+        // Still timed out nine minutes later
+        clock.store(TIMED_OUT_AT + 9 * 60_000, Ordering::Relaxed);
+        let payloads = pipeline.process(&Event::GatewayHeartbeatAck).unwrap();
+        assert!(payloads.is_empty());
+
+        clock.store(TIMED_OUT_AT + 10 * 60_000 + 1, Ordering::Relaxed);
+        let payloads = pipeline.process(&member_add(GUILD, 2, &[])).unwrap();
+        let types: Vec<&str> = payloads.iter().map(|p| p.event_type.as_str()).collect();
+        assert_eq!(types, vec!["member.timeout.expired", "member.join"]);
+        let expired = &payloads[0];
+        assert_eq!(expired.user_id.as_deref(), Some("987654321098765432"));
+        assert_eq!(expired.timestamp, TIMED_OUT_AT + 10 * 60_000, "stamped with when the timeout lifted");
+
+        assert!(pipeline.process(&Event::GatewayHeartbeatAck).unwrap().is_empty(), "fires once");
+    }
+
+    #[test]
+    fn cleared_timeout_never_expires() {
+        let clock = Arc::new(AtomicU64::new(1_700_000_000_000));
+        let config = SerializeConfig { timeout_expiry: true, ..Default::default() };
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, config).with_clock({
+            let clock = clock.clone();
+            move || clock.load(Ordering::Relaxed)
+        });
+
+        pipeline.process(&timed_out_member("2023-11-14T22:23:20.000000+00:00")).unwrap();
+        pipeline.process(&member_update(GUILD, USER, &[])).unwrap();
+        clock.store(1_800_000_000_000, Ordering::Relaxed);
+        assert!(pipeline.process(&Event::GatewayHeartbeatAck).unwrap().is_empty());
+    }
+
+    #[test]
+    fn consecutive_duplicates_are_dropped_within_the_window() {
+        let config = SerializeConfig { dedup_window: Some(Duration::from_secs(60)), ..Default::default() };
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, config);
+
+        let update = member_update(GUILD, USER, &[1]);
+        assert_eq!(pipeline.process(&update).unwrap().len(), 1);
+        assert!(pipeline.process(&update).unwrap().is_empty(), "identical repeat is dropped");
+
+        let changed = pipeline.process(&member_update(GUILD, USER, &[1, 2])).unwrap();
+        assert_eq!(changed.len(), 1, "a differing event always passes");
+        assert_eq!(changed[0].sequence, Some(2), "dropped repeats don't consume sequences");
+        assert_eq!(pipeline.process(&update).unwrap().len(), 1, "only the previous event is compared");
+    }
+
+    #[test]
+    fn event_filter_applies_to_derived_events() {
+        let config = SerializeConfig {
+            join_after_screening: true,
+            event_filter: SerializeFilter::only(["member.join"]),
+            ..Default::default()
+        };
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, config);
+
+        assert!(pipeline.process(&screening_member("GUILD_MEMBER_ADD", true)).unwrap().is_empty());
+        // member.update is filtered out, but the join it releases isn't
+        let payloads = pipeline.process(&screening_member("GUILD_MEMBER_UPDATE", false)).unwrap();
+        let types: Vec<&str> = payloads.iter().map(|p| p.event_type.as_str()).collect();
+        assert_eq!(types, vec!["member.join"]);
+        assert_eq!(payloads[0].sequence, Some(1), "filtered events don't consume sequences");
+    }
+
+    #[test]
+    fn pending_join_is_emitted_immediately_by_default() {
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, SerializeConfig::default());
+        let payloads = pipeline.process(&screening_member("GUILD_MEMBER_ADD", true)).unwrap();
+        assert_eq!(payloads[0].event_type, "member.join");
+        let payloads = pipeline.process(&screening_member("GUILD_MEMBER_UPDATE", false)).unwrap();
+        assert_eq!(payloads.len(), 1, "no second join");
+    }
+
+    fn presence(status: &str) -> Event {
+        dispatch("PRESENCE_UPDATE", serde_json::json!({
+            "guild_id": GUILD.to_string(),
+            "user": { "id": USER.to_string() },
+            "status": status,
+            "activities": [],
+            "client_status": { "desktop": status }
+        }))
+    }
+
+    #[test]
+    fn unchanged_presence_status_is_compacted() {
+        let config = SerializeConfig { compact_presence: true, ..Default::default() };
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, config);
+
+        let mut published = Vec::new();
+        for status in ["idle", "idle", "online"] {
+            published.extend(pipeline.process(&presence(status)).unwrap());
+        }
+        let statuses: Vec<&serde_json::Value> = published.iter().map(|p| &p.data["status"]).collect();
+        assert_eq!(statuses, vec!["idle", "online"]);
+
+        // A member leaving drops their tracked status
+        pipeline.process(&member_remove(GUILD, USER)).unwrap();
+        assert_eq!(pipeline.state().presence(GUILD, USER), None);
+
+        // Without compaction every update is forwarded
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, SerializeConfig::default());
+        pipeline.process(&presence("idle")).unwrap();
+        assert_eq!(pipeline.process(&presence("idle")).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn presence_is_untracked_without_compaction() {
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, SerializeConfig::default());
+        pipeline.process(&presence("online")).unwrap();
+
+        assert_eq!(pipeline.state().presence(GUILD, USER), None);
+        assert!(!String::from_utf8(pipeline.state().snapshot().unwrap()).unwrap().contains(&USER.to_string()));
+    }
+
+    #[test]
+    fn cache_epoch_increments_across_reconnect() {
+        let config = SerializeConfig { include_cache_epoch: true, ..Default::default() };
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, config);
+
+        pipeline.process(&ready()).unwrap();
+        let first = pipeline.process(&guild_create(GUILD, None)).unwrap().remove(0);
+        let member = pipeline.process(&member_add(GUILD, USER, &[])).unwrap().remove(0);
+
+        // Resuming keeps the session (and cache); a new READY rebuilds it
+        pipeline.process(&Event::Resumed).unwrap();
+        let resumed = pipeline.process(&guild_create(GUILD, None)).unwrap().remove(0);
+        pipeline.process(&ready()).unwrap();
+        let rebuilt = pipeline.process(&guild_create(GUILD, None)).unwrap().remove(0);
+
+        assert_eq!(first.cache_epoch, Some(1));
+        assert_eq!(resumed.cache_epoch, Some(1));
+        assert_eq!(rebuilt.cache_epoch, Some(2));
+        assert_eq!(member.cache_epoch, None, "only guild events carry the epoch");
+    }
+
+    #[test]
+    fn linked_users_get_internal_user_id() {
+        let mapper: HashMap<String, String> = [(USER.to_string(), "acct_42".to_string())].into();
+        let config = SerializeConfig { id_mapper: Some(Arc::new(mapper)), ..Default::default() };
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, config);
+
+        let linked = pipeline.process(&member_add(GUILD, USER, &[])).unwrap().remove(0);
+        let unlinked = pipeline.process(&member_add(GUILD, 1, &[])).unwrap().remove(0);
+
+        assert_eq!(linked.internal_user_id.as_deref(), Some("acct_42"));
+        assert_eq!(linked.user_id.as_deref(), Some("987654321098765432"), "Discord id is kept");
+        assert_eq!(unlinked.internal_user_id, None);
+        assert!(serde_json::to_value(&unlinked).unwrap().get("internal_user_id").is_none());
+    }
+
+    #[test]
+    fn roles_always_present_by_default() {
+        let mut pipeline = EventPipeline::new(ShardId::ZERO, SerializeConfig::default());
+        pipeline.process(&member_add(GUILD, USER, &[111])).unwrap();
+
+        let payload = pipeline.process(&member_update(GUILD, USER, &[111])).unwrap().remove(0);
+        assert_eq!(payload.data["roles"], serde_json::json!(["111"]));
+    }
+}
@@ -0,0 +1,243 @@
+//! Serializer prewarm
+//!
+//! The first serialization of each event type pays one-time costs (code
+//! paths paged in, allocator pools grown, serde visitor setup). `prewarm`
+//! runs every forwarding arm once on dummy data at startup, so steady-state
+//! latency is predictable from the first real event and any lazy
+//! initialization problem surfaces before the shards connect.
+
+use crate::error::GatewayError;
+use crate::events::serialize::{serialize_event, SerializeConfig};
+use crate::events::shard_id::ShardId;
+use serde::de::DeserializeSeed;
+use twilight_model::gateway::event::{DispatchEventWithTypeDeserializer, Event};
+
+/// One raw Discord dispatch payload per forwarding arm
+///
+/// New arms in `serialize_event` should add a sample here.
+const SAMPLES: &[(&str, &str)] = &[
+    ("GUILD_CREATE", r#"{"id":"1","unavailable":true}"#),
+    ("GUILD_DELETE", r#"{"id":"1","unavailable":false}"#),
+    (
+        "GUILD_UPDATE",
+        r#"{"id":"1","name":"prewarm","icon":null,"splash":null,"discovery_splash":null,
+            "owner_id":"2","afk_channel_id":null,"afk_timeout":300,"verification_level":0,
+            "default_message_notifications":0,"explicit_content_filter":0,"roles":[],"emojis":[],
+            "features":[],"mfa_level":0,"system_channel_id":null,"system_channel_flags":0,
+            "rules_channel_id":null,"banner":null,"premium_tier":0,"preferred_locale":"en-US",
+            "nsfw_level":0,"premium_progress_bar_enabled":false}"#,
+    ),
+    (
+        "GUILD_MEMBER_ADD",
+        r#"{"guild_id":"1","user":{"id":"2","username":"prewarm","discriminator":"0","avatar":null},
+            "roles":["3"],"nick":null,"joined_at":"2023-01-01T00:00:00.000000+00:00",
+            "deaf":false,"mute":false,"flags":0}"#,
+    ),
+    (
+        "GUILD_MEMBER_UPDATE",
+        r#"{"guild_id":"1","user":{"id":"2","username":"prewarm","discriminator":"0","avatar":null},
+            "roles":["3"],"nick":"prewarm","joined_at":"2023-01-01T00:00:00.000000+00:00"}"#,
+    ),
+    (
+        "GUILD_MEMBER_REMOVE",
+        r#"{"guild_id":"1","user":{"id":"2","username":"prewarm","discriminator":"0","avatar":null}}"#,
+    ),
+    (
+        "THREAD_LIST_SYNC",
+        r#"{"guild_id":"1","channel_ids":["4"],"members":[],
+            "threads":[{"id":"11","guild_id":"1","parent_id":"4","name":"prewarm","type":11,
+                "thread_metadata":{"archived":false,"auto_archive_duration":60,
+                    "archive_timestamp":"2023-01-01T00:00:00.000000+00:00","locked":false}}]}"#,
+    ),
+    (
+        "THREAD_CREATE",
+        r#"{"id":"11","guild_id":"1","parent_id":"4","name":"prewarm","type":11,
+            "thread_metadata":{"archived":false,"auto_archive_duration":60,
+                "archive_timestamp":"2023-01-01T00:00:00.000000+00:00","locked":false}}"#,
+    ),
+    (
+        "THREAD_DELETE",
+        r#"{"id":"11","guild_id":"1","parent_id":"4","type":11}"#,
+    ),
+    (
+        "CHANNEL_UPDATE",
+        r#"{"id":"4","guild_id":"1","parent_id":null,"name":"prewarm","type":0,"topic":null,
+            "nsfw":false,"rate_limit_per_user":0,"permission_overwrites":[]}"#,
+    ),
+    (
+        "GUILD_MEMBERS_CHUNK",
+        r#"{"guild_id":"1","chunk_index":0,"chunk_count":1,"members":[{
+            "user":{"id":"2","username":"prewarm","discriminator":"0","avatar":null},
+            "roles":["3"],"nick":null,"joined_at":"2023-01-01T00:00:00.000000+00:00",
+            "deaf":false,"mute":false,"flags":0}]}"#,
+    ),
+    (
+        "USER_UPDATE",
+        r#"{"id":"2","username":"prewarm","discriminator":"0","avatar":null,"banner":null,
+            "bot":true,"mfa_enabled":false}"#,
+    ),
+    (
+        "GUILD_BAN_ADD",
+        r#"{"guild_id":"1","user":{"id":"2","username":"prewarm","discriminator":"0","avatar":null}}"#,
+    ),
+    (
+        "GUILD_BAN_REMOVE",
+        r#"{"guild_id":"1","user":{"id":"2","username":"prewarm","discriminator":"0","avatar":null}}"#,
+    ),
+    (
+        "VOICE_STATE_UPDATE",
+        r#"{"guild_id":"1","channel_id":"4","user_id":"2","session_id":"prewarm","deaf":false,
+            "mute":false,"self_deaf":false,"self_mute":false,"self_stream":false,
+            "self_video":false,"suppress":false,"request_to_speak_timestamp":null}"#,
+    ),
+    (
+        "MESSAGE_CREATE",
+        r#"{"id":"5","channel_id":"4","guild_id":"1",
+            "author":{"id":"2","username":"prewarm","discriminator":"0","avatar":null},
+            "content":"prewarming the language detector","timestamp":"2023-01-01T00:00:00.000000+00:00",
+            "edited_timestamp":null,"tts":false,"mention_everyone":false,"mentions":[],
+            "mention_roles":[],"attachments":[],"embeds":[],"pinned":false,"type":0}"#,
+    ),
+    (
+        "MESSAGE_REACTION_ADD",
+        r##"{"user_id":"2","channel_id":"4","message_id":"5","guild_id":"1",
+            "emoji":{"id":null,"name":"🔥"},"burst":true,"burst_colors":["#FF6A00"],"type":1}"##,
+    ),
+    (
+        "MESSAGE_REACTION_REMOVE",
+        r#"{"user_id":"2","channel_id":"4","message_id":"5","guild_id":"1",
+            "emoji":{"id":"6","name":"spice","animated":false},"burst":false,"type":0}"#,
+    ),
+    (
+        "TYPING_START",
+        r#"{"channel_id":"4","guild_id":"1","user_id":"2","timestamp":1700000000}"#,
+    ),
+    (
+        "MESSAGE_UPDATE",
+        r#"{"id":"5","channel_id":"4","guild_id":"1",
+            "author":{"id":"2","username":"prewarm","discriminator":"0","avatar":null},
+            "content":"","timestamp":"2023-01-01T00:00:00.000000+00:00",
+            "edited_timestamp":"2023-01-01T00:00:00.000000+00:00","tts":false,
+            "mention_everyone":false,"mentions":[],"mention_roles":[],"attachments":[],
+            "embeds":[],"pinned":false,"type":0}"#,
+    ),
+    (
+        "MESSAGE_DELETE",
+        r#"{"id":"5","channel_id":"4","guild_id":"1"}"#,
+    ),
+    (
+        "MESSAGE_DELETE_BULK",
+        r#"{"ids":["5","8"],"channel_id":"4","guild_id":"1"}"#,
+    ),
+    (
+        "AUTO_MODERATION_RULE_CREATE",
+        r#"{"id":"9","guild_id":"1","name":"prewarm","creator_id":"2","event_type":1,
+            "trigger_type":1,"trigger_metadata":{},"actions":[],"enabled":true,
+            "exempt_roles":[],"exempt_channels":[]}"#,
+    ),
+    (
+        "INTERACTION_CREATE",
+        r#"{"id":"6","application_id":"7","type":4,"token":"prewarm","version":1,
+            "guild_id":"1","channel":{"id":"4","type":0},
+            "member":{"user":{"id":"2","username":"prewarm","discriminator":"0","avatar":null},
+                "roles":[],"joined_at":"2023-01-01T00:00:00.000000+00:00","deaf":false,
+                "mute":false,"flags":0,"permissions":"0"},
+            "entitlements":[],"authorizing_integration_owners":{},
+            "data":{"id":"8","name":"prewarm","type":1,
+                "options":[{"name":"query","type":3,"value":"pre","focused":true}]}}"#,
+    ),
+    (
+        "PRESENCE_UPDATE",
+        r#"{"guild_id":"1","user":{"id":"2"},"status":"online","activities":[],"client_status":{}}"#,
+    ),
+    (
+        "GUILD_SCHEDULED_EVENT_USER_REMOVE",
+        r#"{"guild_id":"1","guild_scheduled_event_id":"10","user_id":"2"}"#,
+    ),
+    (
+        "INTERACTION_CREATE",
+        r#"{"id":"6","application_id":"7","type":2,"token":"prewarm","version":1,
+            "guild_id":"1","channel":{"id":"4","type":0},
+            "member":{"user":{"id":"2","username":"prewarm","discriminator":"0","avatar":null},
+                "roles":[],"joined_at":"2023-01-01T00:00:00.000000+00:00","deaf":false,
+                "mute":false,"flags":0,"permissions":"0"},
+            "entitlements":[],"authorizing_integration_owners":{},
+            "data":{"id":"8","name":"prewarm","type":1,
+                "resolved":{"users":{"2":{"id":"2","username":"prewarm","discriminator":"0","avatar":null}}}}}"#,
+    ),
+    (
+        "INTERACTION_CREATE",
+        r#"{"id":"6","application_id":"7","type":3,"token":"prewarm","version":1,
+            "guild_id":"1","channel":{"id":"4","type":0},
+            "member":{"user":{"id":"2","username":"prewarm","discriminator":"0","avatar":null},
+                "roles":[],"joined_at":"2023-01-01T00:00:00.000000+00:00","deaf":false,
+                "mute":false,"flags":0,"permissions":"0"},
+            "entitlements":[],"authorizing_integration_owners":{},
+            "data":{"custom_id":"prewarm","component_type":2}}"#,
+    ),
+    (
+        "INTERACTION_CREATE",
+        r#"{"id":"6","application_id":"7","type":5,"token":"prewarm","version":1,
+            "guild_id":"1","channel":{"id":"4","type":0},
+            "member":{"user":{"id":"2","username":"prewarm","discriminator":"0","avatar":null},
+                "roles":[],"joined_at":"2023-01-01T00:00:00.000000+00:00","deaf":false,
+                "mute":false,"flags":0,"permissions":"0"},
+            "entitlements":[],"authorizing_integration_owners":{},
+            "data":{"custom_id":"prewarm","components":[{"type":1,"id":1,
+                "components":[{"type":4,"id":2,"custom_id":"field","value":"pre"}]}]}}"#,
+    ),
+];
+
+/// Run every forwarding arm once with dummy data
+///
+/// Exercises both Twilight deserialization and payload encoding for each
+/// sample. Returns the number of samples that produced a payload; a sample
+/// Twilight can't parse is an error.
+pub fn prewarm(config: &SerializeConfig) -> Result<usize, GatewayError> {
+    let mut warmed = 0;
+    for (event_name, payload) in SAMPLES {
+        let event = parse_sample(event_name, payload)?;
+        if let Some(serialized) = serialize_event(&event, ShardId::ZERO, config) {
+            serde_json::to_vec(&serialized).map_err(|source| GatewayError::SerializationFailed {
+                event_type: serialized.event_type.to_string(),
+                shard_id: 0,
+                source,
+            })?;
+            warmed += 1;
+        }
+    }
+    Ok(warmed)
+}
+
+fn parse_sample(event_name: &str, payload: &str) -> Result<Event, GatewayError> {
+    let mut deserializer = serde_json::Deserializer::from_str(payload);
+    DispatchEventWithTypeDeserializer::new(event_name)
+        .deserialize(&mut deserializer)
+        .map(Event::from)
+        .map_err(|source| GatewayError::SerializationFailed {
+            event_type: event_name.to_string(),
+            shard_id: 0,
+            source,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::test_support::member_add;
+
+    #[test]
+    fn prewarm_exercises_every_sample() {
+        let config = SerializeConfig { include_resolved: true, ..Default::default() };
+        assert_eq!(prewarm(&config).unwrap(), SAMPLES.len());
+    }
+
+    #[test]
+    fn serialization_succeeds_after_prewarm() {
+        prewarm(&SerializeConfig::default()).unwrap();
+
+        let event = serialize_event(&member_add(123456789012345678, 987654321098765432, &[]), ShardId::ZERO, &SerializeConfig::default())
+            .expect("member.join is forwarded");
+        assert_eq!(event.event_type, "member.join");
+    }
+}
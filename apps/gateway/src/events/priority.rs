@@ -0,0 +1,113 @@
+//! Event categories and priority tiers
+//!
+//! A worker pool answering interactions has three seconds to respond and
+//! shouldn't queue behind a burst of presence updates. [`event_category`]
+//! and [`event_priority`] classify every event_type, so the publisher can
+//! encode both in the subject (see `PUBLISH_PRIORITY_SUBJECTS`) and
+//! consumers can subscribe per tier with a wildcard.
+//!
+//! Tiers run from p0 (latency-critical) to p3 (high-volume state that is
+//! stale by the time it queues). Backpressure sheds exactly the p3 tier.
+
+use std::fmt;
+
+/// What an event is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventCategory {
+    /// Produced by the gateway about itself (shard lifecycle, anomalies)
+    Control,
+    /// A user waiting on a response (interactions)
+    Command,
+    /// Everything else Discord tells us about guilds
+    Data,
+}
+
+impl EventCategory {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Control => "control",
+            Self::Command => "command",
+            Self::Data => "data",
+        }
+    }
+}
+
+impl fmt::Display for EventCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Priority tier, p0 highest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EventPriority {
+    /// Control events and interactions
+    P0,
+    /// Guild, membership and moderation changes
+    P1,
+    /// Messages, reactions and bulk syncs; unknown types
+    P2,
+    /// High-volume state: presence, voice, typing, embed unfurls
+    P3,
+}
+
+impl EventPriority {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::P0 => "p0",
+            Self::P1 => "p1",
+            Self::P2 => "p2",
+            Self::P3 => "p3",
+        }
+    }
+}
+
+impl fmt::Display for EventPriority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The category of `event_type`; Data for unknown types
+pub fn event_category(event_type: &str) -> EventCategory {
+    match event_type {
+        "guild.anomaly" => EventCategory::Control,
+        t if t.starts_with("shard.") => EventCategory::Control,
+        t if t.starts_with("interaction.") => EventCategory::Command,
+        _ => EventCategory::Data,
+    }
+}
+
+/// The priority tier of `event_type`; P2 for unknown types
+pub fn event_priority(event_type: &str) -> EventPriority {
+    if event_category(event_type) != EventCategory::Data {
+        return EventPriority::P0;
+    }
+    match event_type {
+        "guild.join" | "guild.leave" | "guild.update" | "guild.ban.add" | "guild.ban.remove" => EventPriority::P1,
+        "member.join" | "member.leave" | "member.update" | "member.onboarding.complete" | "member.timeout.expired" => {
+            EventPriority::P1
+        }
+        "automod.rule.create" | "automod.rule.update" | "automod.rule.delete" => EventPriority::P1,
+        "thread.create" | "thread.update" | "thread.delete" | "channel.update" | "user.update" => EventPriority::P1,
+        "presence.update" | "voice.state.update" | "typing.start" | "message.embed.update" => EventPriority::P3,
+        _ => EventPriority::P2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interactions_and_control_events_are_p0() {
+        assert_eq!(event_priority("interaction.create"), EventPriority::P0);
+        assert_eq!(event_category("interaction.create"), EventCategory::Command);
+        assert_eq!(event_category("shard.reconnect_requested"), EventCategory::Control);
+        assert_eq!(event_priority("guild.anomaly"), EventPriority::P0);
+        assert_eq!(event_priority("member.join"), EventPriority::P1);
+        assert_eq!(event_priority("message.create"), EventPriority::P2);
+        assert_eq!(event_priority("typing.start"), EventPriority::P3);
+        assert_eq!(event_priority("future.event"), EventPriority::P2);
+    }
+}
@@ -0,0 +1,127 @@
+//! event_type renames for consumer migration
+//!
+//! Renaming an event_type (say `guild.join` → `guild.created`) breaks every
+//! consumer still matching the old name. A [`RenameMap`] decouples the two
+//! sides: during the migration window the producer emits each renamed event
+//! under both names, and consumers can map legacy names to the new ones
+//! with [`RenameMap::canonical`] so either copy is handled the same way.
+
+use crate::error::GatewayError;
+use crate::events::event_type::EventType;
+use crate::events::serialize::GatewayEvent;
+use std::collections::HashMap;
+
+/// Legacy → new event_type mapping
+#[derive(Debug, Clone, Default)]
+pub struct RenameMap {
+    renames: HashMap<String, String>,
+}
+
+impl RenameMap {
+    /// Empty map (no renames)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse `legacy=new` pairs separated by commas
+    /// (e.g. `guild.join=guild.created,guild.leave=guild.deleted`)
+    pub fn parse(spec: &str) -> Result<Self, GatewayError> {
+        let mut map = Self::new();
+        for pair in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (legacy, new) = pair
+                .split_once('=')
+                .map(|(l, n)| (l.trim(), n.trim()))
+                .filter(|(l, n)| !l.is_empty() && !n.is_empty() && l != n)
+                .ok_or_else(|| GatewayError::Config(format!(
+                    "event_type rename '{pair}' must be legacy=new with distinct names"
+                )))?;
+            map.insert(legacy, new);
+        }
+        Ok(map)
+    }
+
+    /// Emit `legacy` events under `new` as well
+    pub fn insert(&mut self, legacy: impl Into<String>, new: impl Into<String>) {
+        self.renames.insert(legacy.into(), new.into());
+    }
+
+    /// Returns true if no renames are configured
+    pub fn is_empty(&self) -> bool {
+        self.renames.is_empty()
+    }
+
+    /// Producer side: the event, plus a copy under the new name if renamed
+    ///
+    /// Both copies keep the same `event_id` and `sequence`, so a consumer
+    /// subscribed to both names can dedup them as one logical event.
+    pub fn apply(&self, event: GatewayEvent) -> Vec<GatewayEvent> {
+        match self.renames.get(event.event_type.as_str()) {
+            Some(new) => {
+                let renamed = GatewayEvent {
+                    event_type: EventType::from(new.as_str()),
+                    ..event.clone()
+                };
+                vec![event, renamed]
+            }
+            None => vec![event],
+        }
+    }
+
+    /// Consumer side: the new name for a legacy event_type, or the
+    /// event_type unchanged
+    pub fn canonical<'a>(&'a self, event_type: &'a str) -> &'a str {
+        self.renames.get(event_type).map_or(event_type, String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::test_support::gateway_event;
+
+    fn guild_join() -> GatewayEvent {
+        gateway_event("guild.join")
+            .event_id("00000000-0000-4000-8000-000000000001")
+            .guild("123456789012345678")
+            .sequence(7)
+            .build()
+    }
+
+    #[test]
+    fn renamed_event_is_emitted_under_legacy_and_new_type() {
+        let renames = RenameMap::parse("guild.join=guild.created").unwrap();
+
+        let events = renames.apply(guild_join());
+        let types: Vec<&str> = events.iter().map(|e| e.event_type.as_str()).collect();
+        assert_eq!(types, vec!["guild.join", "guild.created"]);
+
+        // Same logical event: consumers reading both can dedup
+        assert_eq!(events[0].event_id, events[1].event_id);
+        assert_eq!(events[0].sequence, events[1].sequence);
+        assert_eq!(events[0].guild_id, events[1].guild_id);
+    }
+
+    #[test]
+    fn unmapped_event_passes_through_once() {
+        let renames = RenameMap::parse("member.join=member.created").unwrap();
+        let events = renames.apply(guild_join());
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, "guild.join");
+    }
+
+    #[test]
+    fn consumer_maps_legacy_type_to_canonical() {
+        let renames = RenameMap::parse("guild.join=guild.created").unwrap();
+        assert_eq!(renames.canonical("guild.join"), "guild.created");
+        assert_eq!(renames.canonical("guild.created"), "guild.created");
+        assert_eq!(renames.canonical("member.join"), "member.join");
+    }
+
+    #[test]
+    fn malformed_spec_is_rejected() {
+        assert!(RenameMap::parse("guild.join").is_err());
+        assert!(RenameMap::parse("guild.join=").is_err());
+        assert!(RenameMap::parse("guild.join=guild.join").is_err());
+        assert!(RenameMap::parse("").unwrap().is_empty());
+    }
+}
@@ -2,72 +2,889 @@
 //!
 //! Converts Twilight events to JSON payloads for NATS publishing.
 
+use crate::error::GatewayError;
+use crate::events::anomaly::AnomalyConfig;
+use crate::events::guild_hash::GuildIdHasher;
+use crate::events::channel_filter::{channel_forwarded, ChannelDenylist};
+use crate::events::event_filter::SerializeFilter;
+use crate::events::engagement::EngagementWeights;
+use crate::events::event_id::deterministic_event_id;
+use crate::events::event_type::EventType;
+use crate::events::fingerprint::fingerprint;
+use crate::events::fixed::PresenceActivity;
+use crate::events::guild_filter::{guild_forwarded, GuildAllowlist, GuildDenylist};
+use crate::events::id_map::IdMapper;
+use crate::events::language::detect_language;
+use crate::events::intent::event_intent;
+use crate::events::logical_clock::LogicalClock;
+use crate::events::outcome::{SerializeMetrics, SerializeOutcome};
+use crate::events::rename::RenameMap;
+use crate::events::shard_id::ShardId;
+use crate::events::timestamp_unit::TimestampUnit;
+use metrics::{counter, histogram};
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 use tracing::warn;
+use twilight_model::application::interaction::application_command::{CommandDataOption, CommandOptionValue};
+use twilight_model::application::interaction::modal::ModalInteractionComponent;
+use twilight_model::application::interaction::{InteractionData, InteractionDataResolved, InteractionType};
+use twilight_model::channel::message::{Component, EmojiReactionType, Embed};
+use twilight_model::channel::Channel;
 use twilight_model::gateway::event::Event;
+use twilight_model::gateway::GatewayReaction;
+use twilight_model::gateway::payload::incoming::{GuildCreate, InteractionCreate};
+use twilight_model::gateway::presence::ActivityType;
+use twilight_model::guild::auto_moderation::AutoModerationRule;
+use twilight_model::guild::{MemberFlags, PartialGuild};
+use twilight_model::id::marker::{GuildMarker, MessageMarker, UserMarker};
+use twilight_model::id::Id;
+use twilight_model::user::User;
 use uuid::Uuid;
 
+/// Options controlling what `serialize_event` puts into payloads
+///
+/// The default matches the minimal payloads workers rely on today;
+/// every option is opt-in.
+#[derive(Debug, Clone, Default)]
+pub struct SerializeConfig {
+    /// Include the `resolved` users/members/channels/roles referenced by
+    /// slash command options in interaction `data`. Off by default to bound
+    /// payload size.
+    pub include_resolved: bool,
+
+    /// Omit `roles` from `member.update` when the shard's last-known role
+    /// set for that member is unchanged, so an empty array unambiguously
+    /// means "all roles removed". Requires an [`EventPipeline`](super::pipeline::EventPipeline).
+    pub omit_unchanged_roles: bool,
+
+    /// Add `roles_added`/`roles_removed` to `member.update`, diffed against
+    /// the shard's last-known role set for that member (absent when the
+    /// prior set is unknown). Applied by the EventPipeline.
+    pub role_diffs: bool,
+
+    /// event_type renames in migration: renamed events are emitted under
+    /// both the legacy and new names. Applied by the EventPipeline.
+    pub renames: RenameMap,
+
+    /// Attach `engagement_weight` from this table to events of the types it
+    /// lists (see [`EngagementWeights`]); empty attaches none.
+    pub engagement_weights: EngagementWeights,
+
+    /// Flatten `data` into dotted keys with scalar values (see
+    /// [`FlatData`](super::flat::FlatData)) for protobuf/columnar consumers.
+    /// Applied by the EventPipeline; off keeps the nested JSON form.
+    pub flat_data: bool,
+
+    /// Emit `guild.anomaly`, right after the event that tripped it, when a
+    /// guild's join or message rate exceeds this baseline. Applied by the
+    /// EventPipeline; None disables detection.
+    pub anomaly: Option<AnomalyConfig>,
+
+    /// Drop an event identical to the one forwarded just before it, if
+    /// that one went out within this window (see
+    /// [`Deduplicator`](crate::events::dedup::Deduplicator)). Applied by
+    /// the EventPipeline; None forwards every event.
+    pub dedup_window: Option<Duration>,
+
+    /// Payloads above this many bytes of JSON are zstd-compressed by
+    /// [`serialize_event_compressed`](crate::events::compressed::serialize_event_compressed);
+    /// None leaves every payload uncompressed.
+    pub compress_threshold: Option<usize>,
+
+    /// Serialize typing and presence events from fixed-schema structs
+    /// instead of a dynamic `data` tree, in
+    /// [`serialize_event_bytes`](crate::events::fixed::serialize_event_bytes)
+    pub fixed_schema_hot_events: bool,
+
+    /// Attach [`ShardInfo`] to every event. The topology comes from the
+    /// shard pool, which sets it on each shard's EventPipeline.
+    pub include_shard_info: bool,
+
+    /// Include the text `content` in `message.create`. Message content is
+    /// privileged (the MESSAGE_CONTENT intent) and sensitive, so by default
+    /// only metadata is forwarded.
+    pub capture_message_content: bool,
+
+    /// Include `components` in `message.create`: the buttons and select
+    /// menus in the message's action rows, for consumers driving stateful
+    /// UIs off bot messages. Off by default since most messages have none.
+    pub include_components: bool,
+
+    /// Tag `message.create` with the content's `detected_language`.
+    /// Needs the privileged MESSAGE_CONTENT intent to see content.
+    pub detect_language: bool,
+
+    /// For guilds with membership screening, hold back `member.join` for a
+    /// `pending` member until the member.update that clears `pending`
+    /// (the join goes out just before it), so welcome flows don't fire
+    /// before the rules are accepted. Tracked by the EventPipeline.
+    pub join_after_screening: bool,
+
+    /// Emit `member.timeout.expired` when a timeout seen in a member.update
+    /// lifts, since Discord sends nothing then. Scheduled by the
+    /// EventPipeline and fired ahead of the first gateway frame's event
+    /// (heartbeat acks included) after the expiry.
+    pub timeout_expiry: bool,
+
+    /// Follow each `guild.ban.add` of a member the shard has seen with a
+    /// `member.leave` (`reason: "banned"`), and drop the real leave Discord
+    /// sends alongside it, so member counts see exactly one leave whatever
+    /// the arrival order. Tracked by the EventPipeline.
+    pub leave_on_ban: bool,
+
+    /// Reduce `guild.update` data to the fields that changed since the
+    /// shard's last `guild.update` for that guild (a JSON merge patch:
+    /// changed fields with their new value, cleared fields as null). The
+    /// first update per guild after a GUILD_CREATE carries every field.
+    /// Tracked by the EventPipeline.
+    pub guild_update_deltas: bool,
+
+    /// Drop `presence.update` when the user's status is unchanged from the
+    /// shard's last-known value (activity-only churn). Applied by the
+    /// EventPipeline.
+    pub compact_presence: bool,
+
+    /// Emit `shard.reconnect_requested` when Discord asks the shard to
+    /// reconnect (OP 7), so monitoring can line reconnects up with event
+    /// gaps. Off keeps the OP 7 dropped like other gateway opcodes.
+    pub forward_reconnects: bool,
+
+    /// Attach the shard's `cache_epoch` to `guild.*` events. Applied by the
+    /// EventPipeline.
+    pub include_cache_epoch: bool,
+
+    /// Attach `timestamp_ns` from the monotonic clock, for ordering events
+    /// within the same millisecond.
+    pub include_timestamp_ns: bool,
+
+    /// Unit of every emitted `timestamp` (see [`TimestampUnit`]). Micros
+    /// multiplies the value by 1000 and flags each event with
+    /// `timestamp_unit`, so only switch once every consumer expects it.
+    pub timestamp_unit: TimestampUnit,
+
+    /// Attach `producer_version`, the gateway crate version, for correlating
+    /// wire issues with a specific build.
+    pub include_producer_version: bool,
+
+    /// Attach `schema_fingerprint`, a hash of the event type's declared
+    /// `data` shape (see [`fingerprint`]), so consumers can detect when one
+    /// event type changes shape within a schema version.
+    pub include_schema_fingerprint: bool,
+
+    /// Attach `intent`, the gateway intent that made Discord send the event
+    /// (see [`event_intent`]), for tracking down missing events.
+    pub include_intent: bool,
+
+    /// Derive `event_id` from the event's content (see
+    /// [`deterministic_event_id`]) instead of generating a random one, so a
+    /// redelivered event keeps its id and consumers can use it as an
+    /// idempotency key.
+    pub deterministic_ids: bool,
+
+    /// Attach `gateway_op` and `gateway_seq` (the raw frame's opcode and
+    /// Discord sequence) for correlating events with gateway frames while
+    /// debugging. Applied by the EventPipeline.
+    pub protocol_debug: bool,
+
+    /// Stamp `logical_clock` from this clock, shared by every shard given
+    /// this config, for consumers that need one order across shards (see
+    /// [`LogicalClock`]). Applied by the EventPipeline.
+    pub logical_clock: Option<Arc<dyn LogicalClock>>,
+
+    /// Attach `internal_user_id` resolved from each event's `user_id`.
+    /// Library hook for account-linked deployments; applied by the
+    /// EventPipeline.
+    pub id_mapper: Option<Arc<dyn IdMapper>>,
+
+    /// Count each event's [`SerializeOutcome`] here, shared by every shard
+    /// given this config (see [`SerializeMetrics`]). Library hook; None
+    /// counts nothing.
+    pub metrics: Option<Arc<SerializeMetrics>>,
+
+    /// Replace `guild_id` with a keyed hash (see [`GuildIdHasher`]) in
+    /// emitted events, for sharing streams without guild identities.
+    /// Allow/deny lists still match raw ids.
+    pub guild_id_hashing: Option<Arc<GuildIdHasher>>,
+
+    /// Forward only these guilds. Takes precedence over the denylist;
+    /// None forwards every guild not denied.
+    pub guild_allowlist: Option<GuildAllowlist>,
+
+    /// Drop events from these guilds (ignored when an allowlist is set)
+    pub guild_denylist: GuildDenylist,
+
+    /// Drop message, reaction and typing events from these channels
+    pub channel_denylist: ChannelDenylist,
+
+    /// Event types to forward; the default forwards all of them
+    pub event_filter: SerializeFilter,
+}
+
 /// Generic gateway event payload
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Deserialization ignores unknown fields by default, so consumers on an
+/// older schema keep working when newer producers add fields. The `strict`
+/// feature rejects unknown fields instead, for catching drift in CI.
+///
+/// `guild_id`, `channel_id` and `user_id` are part of the base envelope and
+/// always present (null when absent), as is `partition_key`. Optional fields
+/// beyond them are omitted when unset, so payloads don't pay for options
+/// that are off.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct GatewayEvent {
+    /// Wire contract the payload was produced under
+    /// ([`CURRENT_SCHEMA_VERSION`]); 0 for payloads from producers that
+    /// predate the field
+    #[serde(default)]
+    pub schema_version: u16,
     pub event_id: String,
-    pub event_type: String,
-    pub shard_id: u64,
+    /// Serialized as its wire string (`member.join`); strings this build
+    /// doesn't know deserialize as [`EventType::Unknown`]
+    pub event_type: EventType,
+    pub shard_id: ShardId,
+    /// UTC Unix epoch milliseconds, or microseconds when `timestamp_unit`
+    /// says so. When Discord created the message or interaction
+    /// for `message.create` and `interaction.*` (from its snowflake id) and
+    /// when the timeout lifted for `member.timeout.expired`; otherwise when
+    /// the gateway serialized the event.
     pub timestamp: u64,
     pub guild_id: Option<String>,
     pub channel_id: Option<String>,
     pub user_id: Option<String>,
     pub data: serde_json::Value,
+    /// Ordering key (see [`partition_key`]): events sharing it must be
+    /// processed in order, so publishers route them to one ordered subject.
+    /// Empty on events from older producers.
+    #[serde(default)]
+    pub partition_key: String,
+    /// Per-shard monotonic sequence, stamped by the shard's EventPipeline.
+    /// Consumers use it with a replay cursor to skip already-processed
+    /// events after a restart. Absent on events from older producers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<u64>,
+    /// Unix ms at which the producing shard's `sequence` started at 1.
+    /// A shard that can't resume its exact sequence numbers from 1 again
+    /// under a later epoch, so no `(producer_epoch, sequence)` is stamped
+    /// twice and consumers compare the pair rather than `sequence` alone.
+    /// Absent on events from older producers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub producer_epoch: Option<u64>,
+    /// Cluster-wide logical clock, when a `logical_clock` is configured.
+    /// Strictly increasing across every shard sharing the clock, so it
+    /// totally orders their events; unrelated to `sequence`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logical_clock: Option<u64>,
+    /// Sharding topology of the producing gateway, when
+    /// `include_shard_info` is set. Lets consumers know how many shards
+    /// (and clusters) to expect events from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shard_info: Option<ShardInfo>,
+    /// Number of fresh sessions (READY) the producing shard has seen, when
+    /// `include_cache_epoch` is set. A shard rebuilds its guild cache on
+    /// each new session, so consumers caching guild state invalidate when
+    /// the epoch changes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_epoch: Option<u64>,
+    /// Nanoseconds on the producing process's monotonic clock, when
+    /// `include_timestamp_ns` is set. Strictly increasing across all events
+    /// of one gateway process, but restarts from zero with the process, so
+    /// it only orders events that share a process; `timestamp` stays in
+    /// Unix time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_ns: Option<u128>,
+    /// `"micros"` when the `timestamp_unit` option scaled `timestamp` to
+    /// microseconds; absent for the default milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_unit: Option<String>,
+    /// Internal account id for `user_id`, when an `id_mapper` is set and
+    /// the user is linked. Absent for unlinked users.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub internal_user_id: Option<String>,
+    /// Version of the gateway that produced the event, when
+    /// `include_producer_version` is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub producer_version: Option<String>,
+    /// Fingerprint of this event type's `data` shape, when
+    /// `include_schema_fingerprint` is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_fingerprint: Option<String>,
+    /// Gateway intent that enabled this event type, when `include_intent`
+    /// is set. Absent for events that need no intent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intent: Option<String>,
+    /// Ranking weight of this event type, when `engagement_weights` lists it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub engagement_weight: Option<f32>,
+    /// Discord gateway opcode of the frame the event came from (0 for
+    /// dispatches), when `protocol_debug` is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gateway_op: Option<u8>,
+    /// Discord's `s` sequence of the dispatch frame the event came from,
+    /// when `protocol_debug` is set. Unrelated to `sequence`; absent for
+    /// non-dispatch frames, which carry none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gateway_seq: Option<u64>,
+    /// Set when the publisher split `data` off onto
+    /// `discord.payload.<data_ref>`; `data` is then null
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_ref: Option<String>,
+}
+
+/// Wire contract version stamped as `schema_version`
+///
+/// Bumped on breaking changes to the envelope or a data schema (renamed,
+/// removed or retyped fields). Added fields don't bump it; consumers ignore
+/// fields they don't know.
+pub const CURRENT_SCHEMA_VERSION: u16 = 1;
+
+/// Crate version stamped as `producer_version`
+pub const PRODUCER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Position of the producing shard in the full sharding topology
+///
+/// A cluster is one gateway process (a shard pool).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardInfo {
+    pub shard_id: u64,
+    pub shard_count: u64,
+    pub cluster_id: u64,
+    pub cluster_count: u64,
+}
+
+impl ShardInfo {
+    /// Topology for `shard_id` when shards are split into consecutive
+    /// clusters of `shards_per_cluster`
+    pub fn for_shard(shard_id: ShardId, shard_count: u64, shards_per_cluster: u64) -> Self {
+        let shard_id = shard_id.get();
+        Self {
+            shard_id,
+            shard_count,
+            cluster_id: shard_id / shards_per_cluster,
+            cluster_count: shard_count.div_ceil(shards_per_cluster),
+        }
+    }
 }
 
-/// Interaction-specific event payload
-#[derive(Debug, Clone, Serialize)]
+/// Interaction-specific event payload, built by [`serialize_interaction`]
+///
+/// The command routing fields are extracted from the interaction, so
+/// workers don't re-parse `data`. As with the generic payload, the
+/// response token is `interaction_token`, never a bare `token`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct InteractionEvent {
+    /// As [`GatewayEvent::schema_version`]
+    #[serde(default)]
+    pub schema_version: u16,
     pub event_id: String,
-    pub shard_id: u64,
+    pub shard_id: ShardId,
     pub timestamp: u64,
     pub interaction_id: String,
     pub interaction_token: String,
     pub guild_id: Option<String>,
-    pub channel_id: String,
+    /// Absent for interactions Discord sends without a channel (some DM
+    /// and user-installed app contexts)
+    pub channel_id: Option<String>,
     pub user_id: String,
+    /// Application command name; None for other interaction types
     pub command_name: Option<String>,
+    /// Subcommand invoked, prefixed by its group if any (`roles add`)
     pub subcommand: Option<String>,
+    /// `interaction_type`, plus `custom_id`/`message_id` for components
     pub data: serde_json::Value,
 }
 
+/// `member.join` payload for a user joining a guild
+///
+/// Shared with the EventPipeline, which emits a deferred join when a member
+/// passes membership screening. `flags` are decoded into booleans for
+/// anti-raid (rejoins, bypassed verification) and onboarding funnels.
+pub(crate) fn member_join_event(
+    guild_id: Id<GuildMarker>,
+    user: &User,
+    flags: MemberFlags,
+    shard_id: ShardId,
+    timestamp: u64,
+) -> GatewayEvent {
+    GatewayEvent {
+        event_id: Uuid::new_v4().to_string(),
+        event_type: EventType::MemberJoin,
+        shard_id,
+        timestamp,
+        guild_id: Some(guild_id.to_string()),
+        channel_id: None,
+        user_id: Some(user.id.to_string()),
+        data: serde_json::json!({
+            "username": user.name,
+            "global_name": user.global_name,
+            // Migrated accounts have discriminator "0", which means none
+            "discriminator": (user.discriminator != 0).then_some(user.discriminator),
+            "flags": {
+                "did_rejoin": flags.contains(MemberFlags::DID_REJOIN),
+                "completed_onboarding": flags.contains(MemberFlags::COMPLETED_ONBOARDING),
+                "started_onboarding": flags.contains(MemberFlags::STARTED_ONBOARDING),
+                "bypassed_verification": flags.contains(MemberFlags::BYPASSES_VERIFICATION),
+            },
+        }),
+        ..Default::default()
+    }
+}
+
+/// `member.leave` the EventPipeline emits for a ban
+///
+/// Shares the ban's envelope; `ban_event_id` correlates the two.
+pub(crate) fn banned_member_leave_event(ban: &GatewayEvent) -> GatewayEvent {
+    GatewayEvent {
+        event_id: Uuid::new_v4().to_string(),
+        event_type: EventType::MemberLeave,
+        shard_id: ban.shard_id,
+        timestamp: ban.timestamp,
+        guild_id: ban.guild_id.clone(),
+        channel_id: None,
+        user_id: ban.user_id.clone(),
+        data: serde_json::json!({
+            "reason": "banned",
+            "ban_event_id": ban.event_id,
+        }),
+        ..Default::default()
+    }
+}
+
+/// `member.timeout.expired` the EventPipeline emits once a member's
+/// timeout lifts; `timestamp` is when it lifted
+pub(crate) fn member_timeout_expired_event(guild_id: u64, user_id: u64, shard_id: ShardId, until: u64) -> GatewayEvent {
+    GatewayEvent {
+        event_id: Uuid::new_v4().to_string(),
+        event_type: EventType::MemberTimeoutExpired,
+        shard_id,
+        timestamp: until,
+        guild_id: Some(guild_id.to_string()),
+        channel_id: None,
+        user_id: Some(user_id.to_string()),
+        data: serde_json::Value::Null,
+        ..Default::default()
+    }
+}
+
+/// `guild.ban.add` or `guild.ban.remove`: the banned user is the
+/// envelope's user_id
+fn guild_ban_event(event_type: EventType, guild_id: Id<GuildMarker>, user: &User, shard_id: ShardId, timestamp: u64) -> GatewayEvent {
+    GatewayEvent {
+        event_id: Uuid::new_v4().to_string(),
+        event_type,
+        shard_id,
+        timestamp,
+        guild_id: Some(guild_id.to_string()),
+        channel_id: None,
+        user_id: Some(user.id.to_string()),
+        data: serde_json::json!({ "username": user.name }),
+        ..Default::default()
+    }
+}
+
+/// `message.pin.update` the EventPipeline emits in place of a message
+/// update that only changed the message's pin state
+///
+/// Keeps the update's envelope but not its `user_id`: that's the author,
+/// and Discord doesn't say who pinned the message.
+pub(crate) fn message_pin_update_event(update: GatewayEvent, message_id: Id<MessageMarker>, pinned: bool) -> GatewayEvent {
+    GatewayEvent {
+        event_type: EventType::MessagePinUpdate,
+        user_id: None,
+        data: serde_json::json!({
+            "message_id": message_id.to_string(),
+            "pinned": pinned,
+        }),
+        ..update
+    }
+}
+
+/// `member.onboarding.complete` payload
+///
+/// Discord has no onboarding event; the EventPipeline derives this from a
+/// member.update that sets the member's COMPLETED_ONBOARDING flag.
+pub(crate) fn member_onboarding_complete_event(
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    shard_id: ShardId,
+    timestamp: u64,
+) -> GatewayEvent {
+    GatewayEvent {
+        event_id: Uuid::new_v4().to_string(),
+        event_type: EventType::MemberOnboardingComplete,
+        shard_id,
+        timestamp,
+        guild_id: Some(guild_id.to_string()),
+        channel_id: None,
+        user_id: Some(user_id.to_string()),
+        data: serde_json::Value::Null,
+        ..Default::default()
+    }
+}
+
 /// Serialize a Twilight event to a GatewayEvent payload
 ///
 /// Returns None for events we don't need to forward (e.g., heartbeats)
-pub fn serialize_event(event: &Event, shard_id: u64) -> Option<GatewayEvent> {
-    let timestamp = std::time::SystemTime::now()
+/// and for event types the config's `event_filter` disables.
+/// Forwarded events record their serialization time in the
+/// `gateway_serialize_duration_seconds` histogram, by event_type, and
+/// every event's outcome is counted in the config's `metrics`, if set.
+pub fn serialize_event(event: &Event, shard_id: ShardId, config: &SerializeConfig) -> Option<GatewayEvent> {
+    let result = serialize_candidate(event, shard_id, config).and_then(|mut payload| {
+        if config.event_filter.is_enabled(&payload.event_type) {
+            config.timestamp_unit.apply(&mut payload);
+            Ok(payload)
+        } else {
+            Err(SerializeOutcome::DroppedFiltered)
+        }
+    });
+    if let Some(ref metrics) = config.metrics {
+        match &result {
+            Ok(payload) => metrics.record_emitted(payload),
+            Err(outcome) => metrics.record(*outcome),
+        }
+    }
+    result.ok()
+}
+
+/// `serialize_event` without the event_type filter, outcome counting or
+/// `timestamp_unit` conversion, failing with the reason an event isn't
+/// forwarded
+///
+/// The EventPipeline derives some events from others (a deferred join
+/// from a member update), so it filters and counts only what it finally
+/// emits.
+pub(crate) fn serialize_candidate(
+    event: &Event,
+    shard_id: ShardId,
+    config: &SerializeConfig,
+) -> Result<GatewayEvent, SerializeOutcome> {
+    let start = Instant::now();
+    let mut payload = serialize_arm(event, shard_id, config).ok_or_else(|| SerializeOutcome::unhandled(event))?;
+    if !guild_forwarded(payload.guild_id.as_deref(), config.guild_allowlist.as_ref(), &config.guild_denylist) {
+        return Err(SerializeOutcome::DroppedFiltered);
+    }
+    if !channel_forwarded(&payload.event_type, payload.channel_id.as_deref(), &config.channel_denylist) {
+        return Err(SerializeOutcome::DroppedFiltered);
+    }
+    if let Some(ref hasher) = config.guild_id_hashing {
+        hasher.apply(&mut payload);
+    }
+    payload.partition_key = partition_key(payload.guild_id.as_deref(), payload.shard_id);
+    if config.deterministic_ids {
+        payload.event_id = deterministic_event_id(&payload);
+    }
+    payload.schema_version = CURRENT_SCHEMA_VERSION;
+    if config.include_timestamp_ns {
+        payload.timestamp_ns = Some(monotonic_ns());
+    }
+    if config.include_producer_version {
+        payload.producer_version = Some(PRODUCER_VERSION.to_string());
+    }
+    if config.include_schema_fingerprint {
+        payload.schema_fingerprint = Some(fingerprint(&payload.event_type));
+    }
+    if config.include_intent {
+        payload.intent = event_intent(&payload.event_type).map(str::to_string);
+    }
+    payload.engagement_weight = config.engagement_weights.weight(&payload.event_type);
+    histogram!(
+        "gateway_serialize_duration_seconds",
+        "event_type" => payload.event_type.to_string()
+    )
+    .record(start.elapsed().as_secs_f64());
+    Ok(payload)
+}
+
+/// Strictly increasing nanoseconds since the first call in this process
+///
+/// Backed by `Instant`; when two calls land on the same clock reading the
+/// later one is bumped by a nanosecond so no two events tie.
+pub(crate) fn monotonic_ns() -> u128 {
+    static ORIGIN: OnceLock<Instant> = OnceLock::new();
+    static LAST: AtomicU64 = AtomicU64::new(0);
+
+    let elapsed = ORIGIN.get_or_init(Instant::now).elapsed().as_nanos() as u64;
+    let previous = LAST
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |last| Some(elapsed.max(last + 1)))
+        .expect("update closure always returns Some");
+    u128::from(elapsed.max(previous + 1))
+}
+
+/// The parts of an embed analytics consumers use, without the full tree
+/// (fields, footer, provider, proxy urls). `author` is the author's name.
+fn embed_summary(embed: &Embed) -> serde_json::Value {
+    serde_json::json!({
+        "type": embed.kind,
+        "title": embed.title,
+        "url": embed.url,
+        "description": embed.description,
+        "author": embed.author.as_ref().map(|author| &author.name),
+        "image_url": embed.image.as_ref().map(|image| &image.url),
+    })
+}
+
+/// The interactive components in a message's action rows, in order
+///
+/// `type` is Discord's component type (2 button, 3 string select, ...).
+/// `custom_id` is null for link buttons and `label` for selects and
+/// icon-only buttons. Components outside action rows (Components V2
+/// layout, text, media) aren't interactive on their own and are skipped.
+fn component_summaries(components: &[Component]) -> Vec<serde_json::Value> {
+    components
+        .iter()
+        .filter_map(|component| match component {
+            Component::ActionRow(row) => Some(&row.components),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|component| {
+            let (custom_id, label) = match component {
+                Component::Button(button) => (button.custom_id.as_deref(), button.label.as_deref()),
+                Component::SelectMenu(select) => (Some(select.custom_id.as_str()), None),
+                _ => return None,
+            };
+            Some(serde_json::json!({
+                "type": u8::from(component.kind()),
+                "custom_id": custom_id,
+                "label": label,
+            }))
+        })
+        .collect()
+}
+
+/// Compact guild settings for `guild.update`
+///
+/// Every key is always present (null when unset), so comparing two
+/// summaries key by key finds cleared fields too.
+pub(crate) fn guild_summary(guild: &PartialGuild) -> serde_json::Value {
+    serde_json::json!({
+        "name": guild.name,
+        "description": guild.description,
+        "icon": guild.icon.map(|hash| hash.to_string()),
+        "banner": guild.banner.map(|hash| hash.to_string()),
+        "vanity_url_code": guild.vanity_url_code,
+        "owner_id": guild.owner_id.to_string(),
+        "features": guild.features,
+        "preferred_locale": guild.preferred_locale,
+        "premium_tier": guild.premium_tier,
+        "verification_level": guild.verification_level,
+        "system_channel_id": guild.system_channel_id.map(|id| id.to_string()),
+        "rules_channel_id": guild.rules_channel_id.map(|id| id.to_string()),
+        "public_updates_channel_id": guild.public_updates_channel_id.map(|id| id.to_string()),
+    })
+}
+
+/// A reaction.add or reaction.remove event
+///
+/// `emoji` is the unicode emoji or the custom emoji's name; `emoji_id` is
+/// set for custom emoji only, so a unicode emoji never matches a custom one
+/// that happens to share its name.
+fn reaction_event(event_type: EventType, reaction: &GatewayReaction, shard_id: ShardId, timestamp: u64) -> GatewayEvent {
+    let (emoji, emoji_id) = match &reaction.emoji {
+        EmojiReactionType::Custom { id, name, .. } => (name.clone(), Some(id.to_string())),
+        EmojiReactionType::Unicode { name } => (Some(name.clone()), None),
+    };
+    // Super reactions cost the user, so consumers weight them apart
+    let mut data = serde_json::json!({
+        "message_id": reaction.message_id.to_string(),
+        "emoji": emoji,
+        "emoji_id": emoji_id,
+        "burst": reaction.burst,
+    });
+    if !reaction.burst_colors.is_empty() {
+        data["burst_colors"] = serde_json::json!(reaction.burst_colors);
+    }
+    GatewayEvent {
+        event_id: Uuid::new_v4().to_string(),
+        event_type,
+        shard_id,
+        timestamp,
+        guild_id: reaction.guild_id.map(|id| id.to_string()),
+        channel_id: Some(reaction.channel_id.to_string()),
+        user_id: Some(reaction.user_id.to_string()),
+        data,
+        ..Default::default()
+    }
+}
+
+/// `thread.create` or `thread.update`; the thread is the envelope's
+/// channel and `data` has the same shape as a `thread.list.sync` entry
+fn thread_event(event_type: EventType, thread: &Channel, shard_id: ShardId, timestamp: u64) -> GatewayEvent {
+    GatewayEvent {
+        event_id: Uuid::new_v4().to_string(),
+        event_type,
+        shard_id,
+        timestamp,
+        guild_id: thread.guild_id.map(|id| id.to_string()),
+        channel_id: Some(thread.id.to_string()),
+        user_id: None,
+        data: thread_summary(thread),
+        ..Default::default()
+    }
+}
+
+/// A thread's identity and archive state, without its full channel object
+fn thread_summary(thread: &Channel) -> serde_json::Value {
+    let metadata = thread.thread_metadata.as_ref();
+    serde_json::json!({
+        "id": thread.id.to_string(),
+        "parent_id": thread.parent_id.map(|id| id.to_string()),
+        "name": thread.name,
+        "type": thread.kind,
+        "owner_id": thread.owner_id.map(|id| id.to_string()),
+        "archived": metadata.is_some_and(|m| m.archived),
+        "locked": metadata.is_some_and(|m| m.locked),
+    })
+}
+
+/// Serialize an INTERACTION_CREATE to the dedicated [`InteractionEvent`]
+///
+/// Returns None for other events, and for interactions without a user to
+/// attribute them to. A sibling of the generic `interaction.create` path
+/// in [`serialize_event`], which is unchanged.
+pub fn serialize_interaction(event: &Event, shard_id: ShardId) -> Option<InteractionEvent> {
+    let Event::InteractionCreate(interaction) = event else {
+        return None;
+    };
+    let (command_name, subcommand) = match &interaction.data {
+        Some(InteractionData::ApplicationCommand(command)) => {
+            (Some(command.name.clone()), subcommand_path(&command.options))
+        }
+        _ => (None, None),
+    };
+    let mut data = serde_json::json!({
+        "interaction_type": format!("{:?}", interaction.kind),
+    });
+    component_fields(interaction, &mut data);
+    Some(InteractionEvent {
+        schema_version: CURRENT_SCHEMA_VERSION,
+        event_id: Uuid::new_v4().to_string(),
+        shard_id,
+        timestamp: snowflake_timestamp_ms(interaction.id.get()),
+        interaction_id: interaction.id.to_string(),
+        interaction_token: interaction.token.clone(),
+        guild_id: interaction.guild_id.map(|id| id.to_string()),
+        channel_id: interaction.channel.as_ref().map(|c| c.id.to_string()),
+        user_id: interaction.author_id()?.to_string(),
+        command_name,
+        subcommand,
+        data,
+    })
+}
+
+/// `subcommand` or `group subcommand` from a command's top-level options
+fn subcommand_path(options: &[CommandDataOption]) -> Option<String> {
+    let option = options.first()?;
+    match &option.value {
+        CommandOptionValue::SubCommand(_) => Some(option.name.clone()),
+        CommandOptionValue::SubCommandGroup(options) => Some(match subcommand_path(options) {
+            Some(subcommand) => format!("{} {subcommand}", option.name),
+            None => option.name.clone(),
+        }),
+        _ => None,
+    }
+}
+
+/// Component interactions (buttons, selects): which component fired, and
+/// on which message, for stateful component flows
+fn component_fields(interaction: &InteractionCreate, data: &mut serde_json::Value) {
+    if let Some(InteractionData::MessageComponent(component)) = &interaction.data {
+        data["custom_id"] = component.custom_id.clone().into();
+        if let Some(message) = &interaction.message {
+            data["message_id"] = message.id.to_string().into();
+        }
+    }
+}
+
+/// Ordering key for an event: `guild-<guild_id>`, or `shard-<shard_id>`
+/// for events outside any guild
+///
+/// Every event of one guild shares a key, so routing by it keeps a
+/// member's join and leave in order. Taken from the emitted `guild_id`,
+/// so it is the hash when guild ids are hashed. No dots, so it fits in a
+/// single NATS subject token.
+pub fn partition_key(guild_id: Option<&str>, shard_id: ShardId) -> String {
+    match guild_id {
+        Some(guild_id) => format!("guild-{guild_id}"),
+        None => format!("shard-{}", shard_id.get()),
+    }
+}
+
+/// Discord's snowflake epoch (2015-01-01T00:00:00Z), in Unix milliseconds
+const DISCORD_EPOCH_MS: u64 = 1_420_070_400_000;
+
+/// Unix milliseconds at which Discord minted the snowflake `id`
+pub fn snowflake_timestamp_ms(id: u64) -> u64 {
+    (id >> 22) + DISCORD_EPOCH_MS
+}
+
+pub(crate) fn now_millis() -> u64 {
+    std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap()
-        .as_millis() as u64;
+        .as_millis() as u64
+}
+
+/// Key set to true in `data` that the gateway failed to serialize
+pub const SERIALIZATION_ERROR_KEY: &str = "_serialization_error";
+
+/// `value` as event `data`, or a marker when serde fails
+///
+/// The marker (`{"_serialization_error": true, "_error": "..."}`) keeps the
+/// event and says what went wrong, instead of a `null` consumers can't tell
+/// from a payload that really had no data. Failures are also logged and
+/// counted in `gateway_serialization_failures_total`, by event_type.
+fn serialized_data(value: &impl Serialize, event_type: &EventType, shard_id: ShardId) -> serde_json::Value {
+    serde_json::to_value(value).unwrap_or_else(|e| {
+        warn!(shard_id = shard_id.get(), event_type = %event_type, error = %e, "Failed to serialize event data");
+        counter!("gateway_serialization_failures_total", "event_type" => event_type.to_string()).increment(1);
+        serde_json::json!({
+            SERIALIZATION_ERROR_KEY: true,
+            "_error": e.to_string(),
+        })
+    })
+}
+
+fn serialize_arm(event: &Event, shard_id: ShardId, config: &SerializeConfig) -> Option<GatewayEvent> {
+    // Processing time, for events without a snowflake minted by the event
+    // itself. Guild and user ids date from the entity's creation, so
+    // joins and updates keep the wall clock.
+    let timestamp = now_millis();
 
     match event {
         Event::GuildCreate(guild) => {
             // GuildCreate is an enum in twilight-model 0.17; extract data via serde
-            let guild_data = serde_json::to_value(guild.as_ref())
-                .unwrap_or_else(|e| {
-                    warn!(shard_id, error = %e, "Failed to serialize GuildCreate data");
-                    serde_json::Value::Null
-                });
+            let mut guild_data = serialized_data(guild.as_ref(), &EventType::GuildJoin, shard_id);
+            // Chunking hints: a `large` guild arrives without its offline
+            // members, so consumers request member chunks. Always present
+            // (member_count null if unknown); presence_count is Discord's
+            // approximation when sent, else the presences that came along.
+            if let (GuildCreate::Available(guild), Some(data)) = (guild.as_ref(), guild_data.as_object_mut()) {
+                let presence_count = guild.approximate_presence_count.unwrap_or(guild.presences.len() as u64);
+                data.insert("large".to_string(), guild.large.into());
+                data.insert("member_count".to_string(), guild.member_count.into());
+                data.insert("presence_count".to_string(), presence_count.into());
+            }
             Some(GatewayEvent {
                 event_id: Uuid::new_v4().to_string(),
-                event_type: "guild.join".to_string(),
+                event_type: EventType::GuildJoin,
                 shard_id,
                 timestamp,
                 guild_id: Some(guild.id().to_string()),
                 channel_id: None,
                 user_id: None,
                 data: guild_data,
+                ..Default::default()
             })
         }
 
         Event::GuildDelete(guild) => Some(GatewayEvent {
             event_id: Uuid::new_v4().to_string(),
-            event_type: "guild.leave".to_string(),
+            event_type: EventType::GuildLeave,
             shard_id,
             timestamp,
             guild_id: Some(guild.id.to_string()),
@@ -76,36 +893,100 @@ pub fn serialize_event(event: &Event, shard_id: u64) -> Option<GatewayEvent> {
             data: serde_json::json!({
                 "unavailable": guild.unavailable,
             }),
+            ..Default::default()
         }),
 
-        Event::MemberAdd(member) => Some(GatewayEvent {
+        Event::GuildUpdate(guild) => Some(GatewayEvent {
             event_id: Uuid::new_v4().to_string(),
-            event_type: "member.join".to_string(),
+            event_type: EventType::GuildUpdate,
             shard_id,
             timestamp,
-            guild_id: Some(member.guild_id.to_string()),
+            guild_id: Some(guild.id.to_string()),
             channel_id: None,
-            user_id: Some(member.user.id.to_string()),
-            data: serde_json::json!({
-                "username": member.user.name,
-                "discriminator": member.user.discriminator,
-            }),
+            user_id: None,
+            data: guild_summary(guild),
+            ..Default::default()
         }),
 
+        Event::MemberAdd(member) => Some(member_join_event(member.guild_id, &member.user, member.flags, shard_id, timestamp)),
+
         Event::MemberRemove(member) => Some(GatewayEvent {
             event_id: Uuid::new_v4().to_string(),
-            event_type: "member.leave".to_string(),
+            event_type: EventType::MemberLeave,
             shard_id,
             timestamp,
             guild_id: Some(member.guild_id.to_string()),
             channel_id: None,
             user_id: Some(member.user.id.to_string()),
             data: serde_json::Value::Null,
+            ..Default::default()
+        }),
+
+        // Sent on connect (and when the bot gains access to a channel) with
+        // the active threads, so consumers can hydrate thread state.
+        // `channel_ids` lists the parents synced; empty means the whole guild.
+        Event::ThreadListSync(sync) => Some(GatewayEvent {
+            event_id: Uuid::new_v4().to_string(),
+            event_type: EventType::ThreadListSync,
+            shard_id,
+            timestamp,
+            guild_id: Some(sync.guild_id.to_string()),
+            channel_id: None,
+            user_id: None,
+            data: serde_json::json!({
+                "channel_ids": sync.channel_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+                "threads": sync.threads.iter().map(thread_summary).collect::<Vec<_>>(),
+            }),
+            ..Default::default()
+        }),
+
+        Event::ThreadCreate(thread) => Some(thread_event(EventType::ThreadCreate, thread, shard_id, timestamp)),
+        Event::ThreadUpdate(thread) => Some(thread_event(EventType::ThreadUpdate, thread, shard_id, timestamp)),
+
+        // Discord sends only the ids and type of a deleted thread
+        Event::ThreadDelete(thread) => Some(GatewayEvent {
+            event_id: Uuid::new_v4().to_string(),
+            event_type: EventType::ThreadDelete,
+            shard_id,
+            timestamp,
+            guild_id: Some(thread.guild_id.to_string()),
+            channel_id: Some(thread.id.to_string()),
+            user_id: None,
+            data: serde_json::json!({
+                "id": thread.id.to_string(),
+                "parent_id": thread.parent_id.to_string(),
+                "type": thread.kind,
+            }),
+            ..Default::default()
+        }),
+
+        // Moderation settings (topic, nsfw, slowmode) alongside identity
+        Event::ChannelUpdate(channel) => Some(GatewayEvent {
+            event_id: Uuid::new_v4().to_string(),
+            event_type: EventType::ChannelUpdate,
+            shard_id,
+            timestamp,
+            guild_id: channel.guild_id.map(|id| id.to_string()),
+            channel_id: Some(channel.id.to_string()),
+            user_id: None,
+            data: serde_json::json!({
+                "id": channel.id.to_string(),
+                "parent_id": channel.parent_id.map(|id| id.to_string()),
+                "name": channel.name,
+                "type": channel.kind,
+                "topic": channel.topic,
+                "nsfw": channel.nsfw.unwrap_or(false),
+                "rate_limit_per_user": channel.rate_limit_per_user.unwrap_or(0),
+            }),
+            ..Default::default()
         }),
 
+        Event::BanAdd(ban) => Some(guild_ban_event(EventType::GuildBanAdd, ban.guild_id, &ban.user, shard_id, timestamp)),
+        Event::BanRemove(ban) => Some(guild_ban_event(EventType::GuildBanRemove, ban.guild_id, &ban.user, shard_id, timestamp)),
+
         Event::MemberUpdate(member) => Some(GatewayEvent {
             event_id: Uuid::new_v4().to_string(),
-            event_type: "member.update".to_string(),
+            event_type: EventType::MemberUpdate,
             shard_id,
             timestamp,
             guild_id: Some(member.guild_id.to_string()),
@@ -114,54 +995,1415 @@ pub fn serialize_event(event: &Event, shard_id: u64) -> Option<GatewayEvent> {
             data: serde_json::json!({
                 "roles": member.roles.iter().map(|r| r.to_string()).collect::<Vec<_>>(),
                 "nick": member.nick,
+                "global_name": member.user.global_name,
+                "communication_disabled_until": member.communication_disabled_until.map(|t| t.iso_8601().to_string()),
             }),
+            ..Default::default()
         }),
 
-        Event::InteractionCreate(interaction) => {
-            // Interactions are serialized as generic events.
-            // The interaction_token is Discord's response token (15-min TTL),
-            // needed by the command handler to reply. NATS is internal-only,
-            // but explicit naming prevents accidental external logging.
+        // One batch of a guild member request. Consumers reassemble a
+        // request's chunks by nonce and know it's complete once they hold
+        // chunk_count of them; presences are left out.
+        Event::MemberChunk(chunk) => Some(GatewayEvent {
+            event_id: Uuid::new_v4().to_string(),
+            event_type: EventType::MemberChunk,
+            shard_id,
+            timestamp,
+            guild_id: Some(chunk.guild_id.to_string()),
+            channel_id: None,
+            user_id: None,
+            data: serde_json::json!({
+                "members": chunk.members.iter().map(|member| serde_json::json!({
+                    "id": member.user.id.to_string(),
+                    "username": member.user.name,
+                    "global_name": member.user.global_name,
+                    "roles": member.roles.iter().map(|r| r.to_string()).collect::<Vec<_>>(),
+                })).collect::<Vec<_>>(),
+                "chunk_index": chunk.chunk_index,
+                "chunk_count": chunk.chunk_count,
+                "nonce": chunk.nonce,
+            }),
+            ..Default::default()
+        }),
+
+        // Discord sends USER_UPDATE only for the bot's own user. Its
+        // avatar decoration isn't modelled by twilight's CurrentUser, so
+        // only the banner joins the profile fields.
+        Event::UserUpdate(user) => Some(GatewayEvent {
+            event_id: Uuid::new_v4().to_string(),
+            event_type: EventType::UserUpdate,
+            shard_id,
+            timestamp,
+            guild_id: None,
+            channel_id: None,
+            user_id: Some(user.id.to_string()),
+            data: serde_json::json!({
+                "username": user.name,
+                "global_name": user.global_name,
+                "avatar": user.avatar.map(|hash| hash.to_string()),
+                "banner": user.banner.map(|hash| hash.to_string()),
+            }),
+            ..Default::default()
+        }),
+
+        Event::VoiceStateUpdate(voice) => Some(GatewayEvent {
+            event_id: Uuid::new_v4().to_string(),
+            event_type: EventType::VoiceStateUpdate,
+            shard_id,
+            timestamp,
+            guild_id: voice.guild_id.map(|id| id.to_string()),
+            // None when the user disconnected from voice
+            channel_id: voice.channel_id.map(|id| id.to_string()),
+            user_id: Some(voice.user_id.to_string()),
+            data: serde_json::json!({
+                "mute": voice.mute,
+                "deaf": voice.deaf,
+                "self_mute": voice.self_mute,
+                "self_deaf": voice.self_deaf,
+                // Going live (screen share / Go Live)
+                "self_stream": voice.self_stream,
+                // Camera on
+                "self_video": voice.self_video,
+                // Stage channels: true for audience, false for speakers
+                "suppress": voice.suppress,
+            }),
+            ..Default::default()
+        }),
+
+        Event::PresenceUpdate(presence) => {
+            let mut data = serde_json::json!({
+                "status": presence.status,
+            });
+            // A custom status isn't something the user is doing, so the
+            // primary activity is the first other one
+            if let Some(activity) = presence.activities.iter().find(|a| a.kind != ActivityType::Custom) {
+                data["activity"] = serialized_data(&PresenceActivity::from(activity), &EventType::PresenceUpdate, shard_id);
+            }
             Some(GatewayEvent {
                 event_id: Uuid::new_v4().to_string(),
-                event_type: "interaction.create".to_string(),
+                event_type: EventType::PresenceUpdate,
                 shard_id,
                 timestamp,
-                guild_id: interaction.guild_id.map(|id| id.to_string()),
-                channel_id: interaction.channel.as_ref().map(|c| c.id.to_string()),
-                user_id: interaction.author_id().map(|id| id.to_string()),
-                data: serde_json::json!({
-                    "interaction_id": interaction.id.to_string(),
-                    "interaction_type": format!("{:?}", interaction.kind),
-                    "interaction_token": interaction.token,
-                }),
+                guild_id: Some(presence.guild_id.to_string()),
+                channel_id: None,
+                user_id: Some(presence.user.id().to_string()),
+                data,
+                ..Default::default()
             })
         }
 
-        // Events we don't forward
-        Event::GatewayHeartbeat
-        | Event::GatewayHeartbeatAck
-        | Event::GatewayHello(_)
-        | Event::GatewayInvalidateSession(_)
-        | Event::GatewayReconnect => None,
-
-        // Ready event - useful for debugging but not forwarded to workers
-        Event::Ready(_) => None,
+        Event::MessageCreate(message) => {
+            // Webhook messages have a placeholder author that isn't a guild
+            // member, so they carry no user_id
+            let is_webhook = message.webhook_id.is_some();
+            let embeds: Vec<_> = message.embeds.iter().map(embed_summary).collect();
+            let attachments: Vec<_> = message.attachments.iter().map(|a| a.url.as_str()).collect();
+            let mut data = serde_json::json!({
+                "message_id": message.id.to_string(),
+                "is_webhook": is_webhook,
+                "is_bot": message.author.bot,
+                "attachments": attachments,
+                "embeds": embeds,
+            });
+            if config.capture_message_content {
+                data["content"] = message.content.clone().into();
+            }
+            if let Some(webhook_id) = message.webhook_id {
+                data["webhook_id"] = webhook_id.to_string().into();
+            }
+            if config.include_components {
+                data["components"] = component_summaries(&message.components).into();
+            }
+            // Response to a slash command (or other interaction): links
+            // back to the interaction and the user who ran it
+            if let Some(metadata) = &message.interaction_metadata {
+                data["interaction_metadata"] = serde_json::json!({
+                    "id": metadata.id.to_string(),
+                    "type": metadata.kind,
+                    "user_id": metadata.user.id.to_string(),
+                });
+            }
+            if config.detect_language {
+                data["detected_language"] = detect_language(&message.content);
+            }
+            Some(GatewayEvent {
+                event_id: Uuid::new_v4().to_string(),
+                event_type: EventType::MessageCreate,
+                shard_id,
+                timestamp: snowflake_timestamp_ms(message.id.get()),
+                guild_id: message.guild_id.map(|id| id.to_string()),
+                channel_id: Some(message.channel_id.to_string()),
+                user_id: (!is_webhook).then(|| message.author.id.to_string()),
+                data,
+                ..Default::default()
+            })
+        }
 
-        // Default: log but don't forward
-        _ => None,
-    }
-}
+        Event::ReactionAdd(reaction) => {
+            Some(reaction_event(EventType::ReactionAdd, reaction, shard_id, timestamp))
+        }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        Event::ReactionRemove(reaction) => {
+            Some(reaction_event(EventType::ReactionRemove, reaction, shard_id, timestamp))
+        }
 
-    #[test]
-    fn test_serialize_returns_none_for_heartbeat() {
-        let event = Event::GatewayHeartbeatAck;
-        assert!(serialize_event(&event, 0).is_none());
-    }
+        Event::TypingStart(typing) => Some(GatewayEvent {
+            event_id: Uuid::new_v4().to_string(),
+            event_type: EventType::TypingStart,
+            shard_id,
+            timestamp,
+            guild_id: typing.guild_id.map(|id| id.to_string()),
+            channel_id: Some(typing.channel_id.to_string()),
+            user_id: Some(typing.user_id.to_string()),
+            data: serde_json::json!({
+                // Discord sends seconds
+                "started_at": typing.timestamp * 1000,
+            }),
+            ..Default::default()
+        }),
+
+        Event::MessageUpdate(message) => {
+            // Discord also fires MessageUpdate when it unfurls a link into an
+            // embed. Unfurls never set edited_timestamp; user edits always do.
+            let embed_only = message.edited_timestamp.is_none() && !message.embeds.is_empty();
+            let (event_type, data) = if embed_only {
+                let embeds: Vec<_> = message.embeds.iter().map(|embed| serde_json::json!({
+                    "type": embed.kind,
+                    "url": embed.url,
+                    "title": embed.title,
+                })).collect();
+                (EventType::MessageEmbedUpdate, serde_json::json!({
+                    "message_id": message.id.to_string(),
+                    "embeds": embeds,
+                }))
+            } else {
+                let mut data = serde_json::json!({
+                    "message_id": message.id.to_string(),
+                    "edited_timestamp": message.edited_timestamp.map(|t| t.iso_8601().to_string()),
+                });
+                if config.capture_message_content {
+                    data["content"] = message.content.clone().into();
+                }
+                (EventType::MessageUpdate, data)
+            };
+            Some(GatewayEvent {
+                event_id: Uuid::new_v4().to_string(),
+                event_type,
+                shard_id,
+                timestamp,
+                guild_id: message.guild_id.map(|id| id.to_string()),
+                channel_id: Some(message.channel_id.to_string()),
+                user_id: Some(message.author.id.to_string()),
+                data,
+                ..Default::default()
+            })
+        }
+
+        Event::MessageDelete(message) => Some(GatewayEvent {
+            event_id: Uuid::new_v4().to_string(),
+            event_type: EventType::MessageDelete,
+            shard_id,
+            timestamp,
+            guild_id: message.guild_id.map(|id| id.to_string()),
+            channel_id: Some(message.channel_id.to_string()),
+            user_id: None,
+            data: serde_json::json!({ "message_id": message.id.to_string() }),
+            ..Default::default()
+        }),
+
+        // A purge: one event for the whole batch rather than one per message
+        Event::MessageDeleteBulk(messages) => Some(GatewayEvent {
+            event_id: Uuid::new_v4().to_string(),
+            event_type: EventType::MessageDeleteBulk,
+            shard_id,
+            timestamp,
+            guild_id: messages.guild_id.map(|id| id.to_string()),
+            channel_id: Some(messages.channel_id.to_string()),
+            user_id: None,
+            data: serde_json::json!({
+                "message_ids": messages.ids.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+            }),
+            ..Default::default()
+        }),
+
+        Event::AutoModerationRuleCreate(rule) => {
+            Some(automod_rule_event(EventType::AutomodRuleCreate, rule, shard_id, timestamp))
+        }
+        Event::AutoModerationRuleUpdate(rule) => {
+            Some(automod_rule_event(EventType::AutomodRuleUpdate, rule, shard_id, timestamp))
+        }
+        Event::AutoModerationRuleDelete(rule) => {
+            Some(automod_rule_event(EventType::AutomodRuleDelete, rule, shard_id, timestamp))
+        }
+
+        // RSVP withdrawn: consumers decrement the event's interested count
+        Event::GuildScheduledEventUserRemove(rsvp) => Some(GatewayEvent {
+            event_id: Uuid::new_v4().to_string(),
+            event_type: EventType::ScheduledEventUserRemove,
+            shard_id,
+            timestamp,
+            guild_id: Some(rsvp.guild_id.to_string()),
+            channel_id: None,
+            user_id: Some(rsvp.user_id.to_string()),
+            data: serde_json::json!({
+                "scheduled_event_id": rsvp.guild_scheduled_event_id.to_string(),
+            }),
+            ..Default::default()
+        }),
+
+        Event::InteractionCreate(interaction)
+            if interaction.kind == InteractionType::ApplicationCommandAutocomplete =>
+        {
+            Some(autocomplete_event(interaction, shard_id, snowflake_timestamp_ms(interaction.id.get())))
+        }
+
+        Event::InteractionCreate(interaction) if interaction.kind == InteractionType::ModalSubmit => {
+            Some(modal_submit_event(interaction, shard_id, snowflake_timestamp_ms(interaction.id.get())))
+        }
+
+        Event::InteractionCreate(interaction) => {
+            // Interactions are serialized as generic events.
+            // The interaction_token is Discord's response token (15-min TTL),
+            // needed by the command handler to reply. NATS is internal-only,
+            // but explicit naming prevents accidental external logging.
+            let mut data = serde_json::json!({
+                "interaction_id": interaction.id.to_string(),
+                "interaction_type": format!("{:?}", interaction.kind),
+                "interaction_token": interaction.token,
+            });
+            if config.include_resolved {
+                if let Some(resolved) = interaction_resolved(interaction) {
+                    data["resolved"] = serialize_resolved(resolved);
+                }
+            }
+            component_fields(interaction, &mut data);
+            Some(GatewayEvent {
+                event_id: Uuid::new_v4().to_string(),
+                event_type: EventType::InteractionCreate,
+                shard_id,
+                timestamp: snowflake_timestamp_ms(interaction.id.get()),
+                guild_id: interaction.guild_id.map(|id| id.to_string()),
+                channel_id: interaction.channel.as_ref().map(|c| c.id.to_string()),
+                user_id: interaction.author_id().map(|id| id.to_string()),
+                data,
+                ..Default::default()
+            })
+        }
+
+        // Control event: the shard that was asked to reconnect is the
+        // envelope's shard_id
+        Event::GatewayReconnect if config.forward_reconnects => Some(GatewayEvent {
+            event_id: Uuid::new_v4().to_string(),
+            event_type: EventType::ShardReconnectRequested,
+            shard_id,
+            timestamp,
+            guild_id: None,
+            channel_id: None,
+            user_id: None,
+            data: serde_json::Value::Null,
+            ..Default::default()
+        }),
+
+        // Events we don't forward
+        Event::GatewayHeartbeat
+        | Event::GatewayHeartbeatAck
+        | Event::GatewayHello(_)
+        | Event::GatewayInvalidateSession(_)
+        | Event::GatewayReconnect => None,
+
+        // Ready event - useful for debugging but not forwarded to workers
+        Event::Ready(_) => None,
+
+        // Default: log but don't forward
+        _ => None,
+    }
+}
+
+/// AutoMod rule lifecycle event; all three carry the full rule
+fn automod_rule_event(
+    event_type: EventType,
+    rule: &AutoModerationRule,
+    shard_id: ShardId,
+    timestamp: u64,
+) -> GatewayEvent {
+    GatewayEvent {
+        event_id: Uuid::new_v4().to_string(),
+        event_type,
+        shard_id,
+        timestamp,
+        guild_id: Some(rule.guild_id.to_string()),
+        channel_id: None,
+        user_id: None,
+        data: serde_json::json!({
+            "id": rule.id.to_string(),
+            "name": rule.name,
+            // Discord's numeric trigger type (1 = keyword, 3 = spam, ...)
+            "trigger_type": u8::from(rule.trigger_type),
+            "enabled": rule.enabled,
+        }),
+        ..Default::default()
+    }
+}
+
+/// Event type prefixes that only exist inside a guild
+///
+/// Events of these types must carry a `guild_id`; one without is malformed
+/// (a failed extraction upstream) and is rejected rather than published
+/// with `guild_id: null`.
+const GUILD_SCOPED_PREFIXES: &[&str] = &["thread."];
+
+/// Check a serialized event against envelope invariants
+pub fn validate_event(event: &GatewayEvent) -> Result<(), GatewayError> {
+    let guild_scoped = GUILD_SCOPED_PREFIXES
+        .iter()
+        .any(|prefix| event.event_type.starts_with(prefix));
+    if guild_scoped && event.guild_id.is_none() {
+        return Err(GatewayError::MissingGuildId {
+            event_type: event.event_type.to_string(),
+            shard_id: event.shard_id.get(),
+        });
+    }
+    Ok(())
+}
+
+/// Autocomplete request: the command and the option being typed into
+///
+/// Carries the interaction token like `interaction.create`, since the
+/// worker answers with the suggestions. `focused`/`value` are null if
+/// Discord sent no focused option.
+fn autocomplete_event(interaction: &InteractionCreate, shard_id: ShardId, timestamp: u64) -> GatewayEvent {
+    let command = match &interaction.data {
+        Some(InteractionData::ApplicationCommand(command)) => Some(command),
+        _ => None,
+    };
+    let focused = command.and_then(|c| focused_option(&c.options));
+    GatewayEvent {
+        event_id: Uuid::new_v4().to_string(),
+        event_type: EventType::InteractionAutocomplete,
+        shard_id,
+        timestamp,
+        guild_id: interaction.guild_id.map(|id| id.to_string()),
+        channel_id: interaction.channel.as_ref().map(|c| c.id.to_string()),
+        user_id: interaction.author_id().map(|id| id.to_string()),
+        data: serde_json::json!({
+            "interaction_id": interaction.id.to_string(),
+            "interaction_token": interaction.token,
+            "command_name": command.map(|c| c.name.as_str()),
+            "focused": focused.map(|(name, _)| name),
+            "value": focused.map(|(_, value)| value),
+        }),
+        ..Default::default()
+    }
+}
+
+/// `interaction.modal`: the submitted form, as `fields` keyed by each
+/// component's custom_id
+fn modal_submit_event(interaction: &InteractionCreate, shard_id: ShardId, timestamp: u64) -> GatewayEvent {
+    let modal = match &interaction.data {
+        Some(InteractionData::ModalSubmit(modal)) => Some(modal),
+        _ => None,
+    };
+    let mut fields = serde_json::Map::new();
+    if let Some(modal) = modal {
+        modal_fields(&modal.components, &mut fields);
+    }
+    GatewayEvent {
+        event_id: Uuid::new_v4().to_string(),
+        event_type: EventType::InteractionModal,
+        shard_id,
+        timestamp,
+        guild_id: interaction.guild_id.map(|id| id.to_string()),
+        channel_id: interaction.channel.as_ref().map(|c| c.id.to_string()),
+        user_id: interaction.author_id().map(|id| id.to_string()),
+        data: serde_json::json!({
+            "interaction_id": interaction.id.to_string(),
+            "interaction_token": interaction.token,
+            "custom_id": modal.map(|m| m.custom_id.as_str()),
+            "fields": fields,
+        }),
+        ..Default::default()
+    }
+}
+
+/// Text inputs as their string, selects and file uploads as an array of
+/// the chosen values (ids for user/role/channel selects and uploads),
+/// searching into every action row and label
+fn modal_fields(components: &[ModalInteractionComponent], fields: &mut serde_json::Map<String, serde_json::Value>) {
+    fn values<T: ToString>(values: &[T]) -> serde_json::Value {
+        values.iter().map(ToString::to_string).collect::<Vec<_>>().into()
+    }
+    for component in components {
+        let (custom_id, value) = match component {
+            ModalInteractionComponent::ActionRow(row) => {
+                modal_fields(&row.components, fields);
+                continue;
+            }
+            ModalInteractionComponent::Label(label) => {
+                modal_fields(std::slice::from_ref(&label.component), fields);
+                continue;
+            }
+            ModalInteractionComponent::TextInput(input) => (&input.custom_id, input.value.clone().into()),
+            ModalInteractionComponent::StringSelect(select) => (&select.custom_id, values(&select.values)),
+            ModalInteractionComponent::UserSelect(select) => (&select.custom_id, values(&select.values)),
+            ModalInteractionComponent::RoleSelect(select) => (&select.custom_id, values(&select.values)),
+            ModalInteractionComponent::MentionableSelect(select) => (&select.custom_id, values(&select.values)),
+            ModalInteractionComponent::ChannelSelect(select) => (&select.custom_id, values(&select.values)),
+            ModalInteractionComponent::FileUpload(upload) => (&upload.custom_id, values(&upload.values)),
+            // Display-only or unknown: nothing submitted
+            ModalInteractionComponent::TextDisplay(_) | ModalInteractionComponent::Unknown(_) => continue,
+        };
+        fields.insert(custom_id.clone(), value);
+    }
+}
+
+/// Name and partial value of the focused option, searching into
+/// subcommands and subcommand groups
+fn focused_option(options: &[CommandDataOption]) -> Option<(&str, &str)> {
+    options.iter().find_map(|option| match &option.value {
+        CommandOptionValue::Focused(value, _) => Some((option.name.as_str(), value.as_str())),
+        CommandOptionValue::SubCommand(nested) | CommandOptionValue::SubCommandGroup(nested) => {
+            focused_option(nested)
+        }
+        _ => None,
+    })
+}
+
+/// Resolved option objects carried by a slash command, if any
+fn interaction_resolved(interaction: &InteractionCreate) -> Option<&InteractionDataResolved> {
+    match interaction.data.as_ref()? {
+        InteractionData::ApplicationCommand(command) => command.resolved.as_ref(),
+        _ => None,
+    }
+}
+
+/// Compact form of the interaction `resolved` map
+///
+/// Only the fields consumers need to act on a command argument without a
+/// REST fetch (names and roles), keyed by snowflake.
+fn serialize_resolved(resolved: &InteractionDataResolved) -> serde_json::Value {
+    let users: serde_json::Map<_, _> = resolved
+        .users
+        .iter()
+        .map(|(id, user)| {
+            (id.to_string(), serde_json::json!({
+                "username": user.name,
+                "global_name": user.global_name,
+                "bot": user.bot,
+            }))
+        })
+        .collect();
+    let members: serde_json::Map<_, _> = resolved
+        .members
+        .iter()
+        .map(|(id, member)| {
+            (id.to_string(), serde_json::json!({
+                "nick": member.nick,
+                "roles": member.roles.iter().map(|r| r.to_string()).collect::<Vec<_>>(),
+            }))
+        })
+        .collect();
+    let channels: serde_json::Map<_, _> = resolved
+        .channels
+        .iter()
+        .map(|(id, channel)| {
+            (id.to_string(), serde_json::json!({
+                "name": channel.name,
+                "type": u8::from(channel.kind),
+            }))
+        })
+        .collect();
+    let roles: serde_json::Map<_, _> = resolved
+        .roles
+        .iter()
+        .map(|(id, role)| (id.to_string(), serde_json::json!({ "name": role.name })))
+        .collect();
+
+    serde_json::json!({
+        "users": users,
+        "members": members,
+        "channels": channels,
+        "roles": roles,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::test_support::{dispatch, gateway_event, guild, guild_update, member_add, member_update, message, user};
+
+    /// Slash command `/profile target:@targetuser` with resolved user/member
+    fn user_option_command() -> Event {
+        dispatch("INTERACTION_CREATE", serde_json::json!({
+            "id": "444444444444444444",
+            "application_id": "555555555555555555",
+            "type": 2,
+            "token": "aW50ZXJhY3Rpb25fdG9rZW5fZXhhbXBsZQ",
+            "version": 1,
+            "guild_id": "123456789012345678",
+            "channel": { "id": "333333333333333333", "type": 0 },
+            "member": {
+                "user": {
+                    "id": "987654321098765432",
+                    "username": "testuser",
+                    "discriminator": "0",
+                    "avatar": null
+                },
+                "roles": [],
+                "joined_at": "2023-01-01T00:00:00.000000+00:00",
+                "deaf": false,
+                "mute": false,
+                "flags": 0,
+                "permissions": "0"
+            },
+            "entitlements": [],
+            "authorizing_integration_owners": {},
+            "data": {
+                "id": "666666666666666666",
+                "name": "profile",
+                "type": 1,
+                "options": [{ "name": "target", "type": 6, "value": "777777777777777777" }],
+                "resolved": {
+                    "users": {
+                        "777777777777777777": {
+                            "id": "777777777777777777",
+                            "username": "targetuser",
+                            "global_name": "Target User",
+                            "discriminator": "0",
+                            "avatar": null
+                        }
+                    },
+                    "members": {
+                        "777777777777777777": {
+                            "roles": ["111111111111111111"],
+                            "nick": "targetnick",
+                            "joined_at": "2023-01-01T00:00:00.000000+00:00",
+                            "pending": false,
+                            "flags": 0,
+                            "permissions": "0"
+                        }
+                    }
+                }
+            }
+        }))
+    }
+
+    /// `/config roles add role:@Mod`: a subcommand in a subcommand group
+    fn subcommand_group_command() -> Event {
+        dispatch("INTERACTION_CREATE", serde_json::json!({
+            "id": "444444444444444444",
+            "application_id": "555555555555555555",
+            "type": 2,
+            "token": "aW50ZXJhY3Rpb25fdG9rZW5fZXhhbXBsZQ",
+            "version": 1,
+            "guild_id": "123456789012345678",
+            "channel": { "id": "333333333333333333", "type": 0 },
+            "member": {
+                "user": {
+                    "id": "987654321098765432",
+                    "username": "testuser",
+                    "discriminator": "0",
+                    "avatar": null
+                },
+                "roles": [],
+                "joined_at": "2023-01-01T00:00:00.000000+00:00",
+                "deaf": false,
+                "mute": false,
+                "flags": 0,
+                "permissions": "0"
+            },
+            "entitlements": [],
+            "authorizing_integration_owners": {},
+            "data": {
+                "id": "666666666666666666",
+                "name": "config",
+                "type": 1,
+                "options": [{
+                    "name": "roles",
+                    "type": 2,
+                    "options": [{
+                        "name": "add",
+                        "type": 1,
+                        "options": [{ "name": "role", "type": 8, "value": "111111111111111111" }]
+                    }]
+                }]
+            }
+        }))
+    }
+
+    /// Button click on a bot message
+    fn button_click() -> Event {
+        dispatch("INTERACTION_CREATE", serde_json::json!({
+            "id": "444444444444444444",
+            "application_id": "555555555555555555",
+            "type": 3,
+            "token": "aW50ZXJhY3Rpb25fdG9rZW5fZXhhbXBsZQ",
+            "version": 1,
+            "guild_id": "123456789012345678",
+            "channel": { "id": "333333333333333333", "type": 0 },
+            "member": {
+                "user": {
+                    "id": "987654321098765432",
+                    "username": "testuser",
+                    "discriminator": "0",
+                    "avatar": null
+                },
+                "roles": [],
+                "joined_at": "2023-01-01T00:00:00.000000+00:00",
+                "deaf": false,
+                "mute": false,
+                "flags": 0,
+                "permissions": "0"
+            },
+            "entitlements": [],
+            "authorizing_integration_owners": {},
+            "message": {
+                "id": "888888888888888888",
+                "channel_id": "333333333333333333",
+                "author": {
+                    "id": "555555555555555555",
+                    "username": "arrakis",
+                    "discriminator": "0",
+                    "avatar": null,
+                    "bot": true
+                },
+                "content": "Verify your wallet",
+                "timestamp": "2023-01-01T00:00:00.000000+00:00",
+                "edited_timestamp": null,
+                "tts": false,
+                "mention_everyone": false,
+                "mentions": [],
+                "mention_roles": [],
+                "attachments": [],
+                "embeds": [],
+                "pinned": false,
+                "type": 0,
+                "flags": 0
+            },
+            "data": {
+                "custom_id": "verify:start",
+                "component_type": 2
+            }
+        }))
+    }
+
+    /// User edit of a message's content
+    fn content_edit() -> Event {
+        dispatch("MESSAGE_UPDATE", message(serde_json::json!({
+            "content": "check out https://example.org",
+            "edited_timestamp": "2023-01-01T00:05:00.000000+00:00"
+        })))
+    }
+
+    /// Discord unfurling the message's link into an embed
+    fn link_unfurl() -> Event {
+        dispatch("MESSAGE_UPDATE", message(serde_json::json!({
+            "embeds": [{
+                "type": "link",
+                "url": "https://example.com",
+                "title": "Example Domain"
+            }]
+        })))
+    }
+
+    /// AutoMod keyword rule in the given lifecycle dispatch
+    fn automod_rule(event_name: &str, enabled: bool) -> Event {
+        dispatch(event_name, serde_json::json!({
+            "id": "999999999999999999",
+            "guild_id": "123456789012345678",
+            "name": "Block scam links",
+            "creator_id": "987654321098765432",
+            "event_type": 1,
+            "trigger_type": 1,
+            "trigger_metadata": { "keyword_filter": ["free nitro"] },
+            "actions": [{ "type": 1, "metadata": {} }],
+            "enabled": enabled,
+            "exempt_roles": [],
+            "exempt_channels": []
+        }))
+    }
+
+    /// User going live with camera on in a stage channel, as a speaker
+    fn streaming_voice_state() -> Event {
+        dispatch("VOICE_STATE_UPDATE", serde_json::json!({
+            "guild_id": "123456789012345678",
+            "channel_id": "333333333333333333",
+            "user_id": "987654321098765432",
+            "session_id": "90326bd25d71d39b9ef95b299e3872ff",
+            "deaf": false,
+            "mute": false,
+            "self_deaf": false,
+            "self_mute": false,
+            "self_stream": true,
+            "self_video": true,
+            "suppress": false,
+            "request_to_speak_timestamp": null
+        }))
+    }
+
+    /// Autocomplete while typing into `/role add name:<...>`
+    fn role_name_autocomplete() -> Event {
+        dispatch("INTERACTION_CREATE", serde_json::json!({
+            "id": "444444444444444444",
+            "application_id": "555555555555555555",
+            "type": 4,
+            "token": "aW50ZXJhY3Rpb25fdG9rZW5fZXhhbXBsZQ",
+            "version": 1,
+            "guild_id": "123456789012345678",
+            "channel": { "id": "333333333333333333", "type": 0 },
+            "member": {
+                "user": {
+                    "id": "987654321098765432",
+                    "username": "testuser",
+                    "discriminator": "0",
+                    "avatar": null
+                },
+                "roles": [],
+                "joined_at": "2023-01-01T00:00:00.000000+00:00",
+                "deaf": false,
+                "mute": false,
+                "flags": 0,
+                "permissions": "0"
+            },
+            "entitlements": [],
+            "authorizing_integration_owners": {},
+            "data": {
+                "id": "666666666666666666",
+                "name": "role",
+                "type": 1,
+                "options": [{
+                    "name": "add",
+                    "type": 1,
+                    "options": [
+                        { "name": "user", "type": 6, "value": "777777777777777777" },
+                        { "name": "name", "type": 3, "value": "mod", "focused": true }
+                    ]
+                }]
+            }
+        }))
+    }
+
+    /// Two-row application form submitted from a modal
+    fn application_modal_submit() -> Event {
+        let text_input = |id: i32, custom_id: &str, value: &str| serde_json::json!({
+            "type": 1,
+            "id": id,
+            "components": [{ "type": 4, "id": id + 1, "custom_id": custom_id, "value": value }]
+        });
+        dispatch("INTERACTION_CREATE", serde_json::json!({
+            "id": "444444444444444444",
+            "application_id": "555555555555555555",
+            "type": 5,
+            "token": "aW50ZXJhY3Rpb25fdG9rZW5fZXhhbXBsZQ",
+            "version": 1,
+            "guild_id": "123456789012345678",
+            "channel": { "id": "333333333333333333", "type": 0 },
+            "member": {
+                "user": {
+                    "id": "987654321098765432",
+                    "username": "testuser",
+                    "discriminator": "0",
+                    "avatar": null
+                },
+                "roles": [],
+                "joined_at": "2023-01-01T00:00:00.000000+00:00",
+                "deaf": false,
+                "mute": false,
+                "flags": 0,
+                "permissions": "0"
+            },
+            "entitlements": [],
+            "authorizing_integration_owners": {},
+            "data": {
+                "custom_id": "application",
+                "components": [
+                    text_input(1, "reason", "I'd like to help moderate"),
+                    text_input(3, "timezone", "UTC+2")
+                ]
+            }
+        }))
+    }
+
+    /// Message posted by a webhook (e.g. a GitHub integration)
+    fn webhook_message() -> Event {
+        dispatch("MESSAGE_CREATE", message(serde_json::json!({
+            "webhook_id": "999999999999999999",
+            "author": {
+                "id": "999999999999999999",
+                "username": "GitHub",
+                "discriminator": "0000",
+                "avatar": null,
+                "bot": true
+            },
+            "content": "New commit pushed to main"
+        })))
+    }
+
+    /// A member who left and came back, let in past the verification level
+    fn rejoin_member_add() -> Event {
+        dispatch("GUILD_MEMBER_ADD", serde_json::json!({
+            "guild_id": "123456789012345678",
+            "user": {
+                "id": "987654321098765432",
+                "username": "testuser",
+                "discriminator": "0",
+                "avatar": null
+            },
+            "roles": [],
+            "nick": null,
+            "joined_at": "2023-01-01T00:00:00.000000+00:00",
+            "deaf": false,
+            "mute": false,
+            "flags": 5
+        }))
+    }
+
+    /// A link to an article, unfurled on send
+    fn rich_embed_message() -> Event {
+        dispatch("MESSAGE_CREATE", message(serde_json::json!({
+            "content": "worth a read https://example.com/spice",
+            "embeds": [{
+                "type": "article",
+                "title": "The Spice Must Flow",
+                "url": "https://example.com/spice",
+                "description": "A history of melange harvesting",
+                "author": { "name": "Liet Kynes", "url": "https://example.com/kynes" },
+                "image": { "url": "https://example.com/spice.png", "width": 1200, "height": 630 },
+                "footer": { "text": "example.com" },
+                "fields": [{ "name": "Reading time", "value": "8 min", "inline": true }]
+            }]
+        })))
+    }
+
+    fn attachment_message() -> Event {
+        let url = "https://cdn.discordapp.com/attachments/333333333333333333/777777777777777777/harvester.png";
+        dispatch("MESSAGE_CREATE", message(serde_json::json!({
+            "content": "new harvester schematics attached",
+            "attachments": [{
+                "id": "777777777777777777",
+                "filename": "harvester.png",
+                "size": 48213,
+                "url": url,
+                "proxy_url": url.replace("cdn.discordapp.com", "media.discordapp.net"),
+                "content_type": "image/png"
+            }]
+        })))
+    }
+
+    /// Bot's reply to `/profile`, run by the test user
+    fn command_response_message() -> Event {
+        dispatch("MESSAGE_CREATE", message(serde_json::json!({
+            "author": {
+                "id": "555555555555555555",
+                "username": "arrakis",
+                "discriminator": "0",
+                "avatar": null,
+                "bot": true
+            },
+            "content": "Here's your profile",
+            "type": 20,
+            "interaction_metadata": {
+                "id": "444444444444444444",
+                "type": 2,
+                "user": {
+                    "id": "987654321098765432",
+                    "username": "testuser",
+                    "discriminator": "0",
+                    "avatar": null
+                },
+                "authorizing_integration_owners": { "0": "123456789012345678" }
+            }
+        })))
+    }
+
+    fn button_message() -> Event {
+        dispatch("MESSAGE_CREATE", message(serde_json::json!({
+            "author": {
+                "id": "555555555555555555",
+                "username": "arrakis",
+                "discriminator": "0",
+                "avatar": null,
+                "bot": true
+            },
+            "content": "Verify your wallet to get the holder role",
+            "components": [{
+                "type": 1,
+                "components": [
+                    { "type": 2, "style": 1, "custom_id": "verify:start", "label": "Verify" },
+                    { "type": 2, "style": 5, "url": "https://example.org/docs", "label": "Docs" }
+                ]
+            }]
+        })))
+    }
+
+    fn idle_presence() -> Event {
+        dispatch("PRESENCE_UPDATE", serde_json::json!({
+            "guild_id": "123456789012345678",
+            "user": { "id": "987654321098765432" },
+            "status": "idle",
+            "activities": [],
+            "client_status": { "desktop": "idle" }
+        }))
+    }
+
+    fn scheduled_event_unsubscribe() -> Event {
+        dispatch("GUILD_SCHEDULED_EVENT_USER_REMOVE", serde_json::json!({
+            "guild_id": "123456789012345678",
+            "guild_scheduled_event_id": "555555555555555555",
+            "user_id": "987654321098765432"
+        }))
+    }
+
+    #[test]
+    fn normal_reactions_are_not_burst() {
+        let reaction = dispatch("MESSAGE_REACTION_ADD", serde_json::json!({
+            "user_id": "987654321098765432",
+            "channel_id": "333333333333333333",
+            "message_id": "888888888888888888",
+            "guild_id": "123456789012345678",
+            "emoji": { "id": "777777777777777777", "name": "sietch", "animated": false },
+            "burst": false,
+            "type": 0
+        }));
+        let event = serialize_event(&reaction, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+        assert_eq!(event.data["burst"], false);
+        assert_eq!(event.data["emoji_id"], "777777777777777777");
+        assert!(event.data.get("burst_colors").is_none());
+    }
+
+    #[test]
+    fn test_serialize_returns_none_for_heartbeat() {
+        let event = Event::GatewayHeartbeatAck;
+        assert!(serialize_event(&event, ShardId::ZERO, &SerializeConfig::default()).is_none());
+    }
+
+    #[test]
+    fn reconnect_request_is_forwarded_only_when_enabled() {
+        assert!(serialize_event(&Event::GatewayReconnect, ShardId::ZERO, &SerializeConfig::default()).is_none());
+
+        let config = SerializeConfig { forward_reconnects: true, ..Default::default() };
+        let shard_id = ShardId::new(5, 8).unwrap();
+        let event = serialize_event(&Event::GatewayReconnect, shard_id, &config).unwrap();
+        assert_eq!(event.event_type, "shard.reconnect_requested");
+        assert_eq!(event.shard_id, 5);
+        assert_eq!(event.guild_id, None);
+    }
+
+    #[test]
+    fn denied_guilds_are_dropped() {
+        let config = SerializeConfig {
+            guild_denylist: GuildDenylist::from([123456789012345678]),
+            ..Default::default()
+        };
+        assert!(serialize_event(&member_add(123456789012345678, 987654321098765432, &[]), ShardId::ZERO, &config).is_none());
+
+        let event = serialize_event(&member_add(234567890123456789, 987654321098765432, &[]), ShardId::ZERO, &config)
+            .expect("other guilds pass");
+        assert_eq!(event.guild_id.as_deref(), Some("234567890123456789"));
+        // Guild-less control events aren't subject to the denylist
+        let config = SerializeConfig { forward_reconnects: true, ..config };
+        assert!(serialize_event(&Event::GatewayReconnect, ShardId::ZERO, &config).is_some());
+    }
+
+    #[test]
+    fn denied_channels_are_dropped() {
+        let config = SerializeConfig {
+            channel_denylist: ChannelDenylist::from([333333333333333333]),
+            ..Default::default()
+        };
+        let in_channel = |channel_id: &str| dispatch("MESSAGE_CREATE", message(serde_json::json!({ "channel_id": channel_id })));
+        assert!(serialize_event(&in_channel("333333333333333333"), ShardId::ZERO, &config).is_none());
+
+        let event = serialize_event(&in_channel("444444444444444444"), ShardId::ZERO, &config).expect("other channels pass");
+        assert_eq!(event.channel_id.as_deref(), Some("444444444444444444"));
+        // Guild-level events aren't channel-scoped
+        assert!(serialize_event(&member_add(123456789012345678, 987654321098765432, &[]), ShardId::ZERO, &config).is_some());
+    }
+
+    #[test]
+    fn serialization_failures_are_marked_in_data() {
+        struct Unserializable;
+        impl Serialize for Unserializable {
+            fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+                Err(serde::ser::Error::custom("unsupported guild field"))
+            }
+        }
+
+        let data = serialized_data(&Unserializable, &EventType::GuildJoin, ShardId::ZERO);
+        assert_eq!(data[SERIALIZATION_ERROR_KEY], true, "failure is observable, not null");
+        assert_eq!(data["_error"], "unsupported guild field");
+        assert_eq!(serialized_data(&[1, 2], &EventType::GuildJoin, ShardId::ZERO), serde_json::json!([1, 2]));
+    }
+
+    #[test]
+    fn partition_key_groups_events_by_guild() {
+        let config = SerializeConfig::default();
+        let join = serialize_event(&member_add(123456789012345678, 987654321098765432, &[]), ShardId::ZERO, &config).unwrap();
+        let update = serialize_event(&member_update(123456789012345678, 111111111111111111, &[]), ShardId::ZERO, &config).unwrap();
+        let elsewhere = serialize_event(&member_add(876543210987654321, 987654321098765432, &[]), ShardId::ZERO, &config).unwrap();
+        assert_eq!(join.partition_key, "guild-123456789012345678");
+        assert_eq!(join.partition_key, update.partition_key);
+        assert_ne!(join.partition_key, elsewhere.partition_key);
+
+        let config = SerializeConfig { forward_reconnects: true, ..Default::default() };
+        let reconnect = serialize_event(&Event::GatewayReconnect, ShardId::new(3, 4).unwrap(), &config).unwrap();
+        assert_eq!(reconnect.partition_key, "shard-3", "guild-less events fall back to the shard");
+    }
+
+    #[test]
+    fn deterministic_ids_repeat_for_the_same_event() {
+        let config = SerializeConfig {
+            deterministic_ids: true,
+            ..Default::default()
+        };
+        let join = member_add(123456789012345678, 987654321098765432, &[]);
+        let first = serialize_event(&join, ShardId::ZERO, &config).unwrap();
+        let redelivered = serialize_event(&join, ShardId::ZERO, &config).unwrap();
+        assert_eq!(first.event_id, redelivered.event_id);
+
+        let other = serialize_event(&member_add(123456789012345678, 111111111111111111, &[]), ShardId::ZERO, &config).unwrap();
+        assert_ne!(other.event_id, first.event_id);
+        // Random by default
+        let random = serialize_event(&join, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+        assert_ne!(random.event_id, serialize_event(&join, ShardId::ZERO, &SerializeConfig::default()).unwrap().event_id);
+    }
+
+    #[test]
+    fn filtered_event_types_are_dropped() {
+        let config = SerializeConfig {
+            event_filter: SerializeFilter::all().disable("interaction.create"),
+            ..Default::default()
+        };
+        assert!(serialize_event(&subcommand_group_command(), ShardId::ZERO, &config).is_none());
+
+        let event = serialize_event(&member_add(123456789012345678, 987654321098765432, &[]), ShardId::ZERO, &config)
+            .expect("member.join is still forwarded");
+        assert_eq!(event.event_type, "member.join");
+        assert!(serialize_event(&subcommand_group_command(), ShardId::ZERO, &SerializeConfig::default()).is_some());
+    }
+
+    #[test]
+    fn hashed_guild_id_is_stable_and_not_the_raw_id() {
+        let config = SerializeConfig {
+            guild_id_hashing: Some(Arc::new(GuildIdHasher::new(b"analytics-key"))),
+            ..Default::default()
+        };
+        let first = serialize_event(&member_add(123456789012345678, 1, &[]), ShardId::ZERO, &config).unwrap();
+        let second = serialize_event(&member_add(123456789012345678, 2, &[]), ShardId::ZERO, &config).unwrap();
+
+        let hashed = first.guild_id.clone().unwrap();
+        assert_ne!(hashed, "123456789012345678");
+        assert_eq!(second.guild_id, first.guild_id);
+        let hasher = config.guild_id_hashing.as_ref().unwrap();
+        assert_eq!(hasher.reverse(&hashed).as_deref(), Some("123456789012345678"));
+    }
+
+    #[test]
+    fn hashing_leaves_no_raw_guild_id_in_guild_join() {
+        let mut payload = guild(123456789012345678, Some(444444444444444444));
+        payload["roles"] = serde_json::json!([{
+            "id": "123456789012345678",
+            "name": "@everyone",
+            "color": 0,
+            "colors": { "primary_color": 0, "secondary_color": null, "tertiary_color": null },
+            "hoist": false,
+            "position": 0,
+            "permissions": "0",
+            "managed": false,
+            "mentionable": false,
+            "flags": 0
+        }]);
+        payload["channels"] = serde_json::json!([{
+            "id": "444444444444444444",
+            "type": 0,
+            "guild_id": "123456789012345678",
+            "name": "general",
+            "position": 0,
+            "permission_overwrites": []
+        }]);
+        let config = SerializeConfig {
+            guild_id_hashing: Some(Arc::new(GuildIdHasher::new(b"analytics-key"))),
+            ..Default::default()
+        };
+        let event = serialize_event(&dispatch("GUILD_CREATE", payload), ShardId::ZERO, &config).unwrap();
+        assert_eq!(event.event_type, "guild.join");
+
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(!json.contains("123456789012345678"), "raw guild id leaked: {json}");
+        let hashed = event.guild_id.as_deref().unwrap();
+        assert_eq!(event.data["id"], hashed);
+    }
+
+    #[test]
+    fn schema_fingerprint_is_opt_in() {
+        let config = SerializeConfig { include_schema_fingerprint: true, ..Default::default() };
+        let event = serialize_event(&streaming_voice_state(), ShardId::ZERO, &config).unwrap();
+        assert_eq!(event.schema_fingerprint, Some(fingerprint("voice.state.update")));
+
+        let plain = serialize_event(&streaming_voice_state(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+        assert!(serde_json::to_value(&plain).unwrap().get("schema_fingerprint").is_none());
+    }
+
+    #[test]
+    fn configured_engagement_weights_are_attached() {
+        let config = SerializeConfig {
+            engagement_weights: EngagementWeights::parse("member.join=0.5,message.create=1.0").unwrap(),
+            ..Default::default()
+        };
+        let join = serialize_event(&member_add(123456789012345678, 1, &[]), ShardId::ZERO, &config).unwrap();
+        assert_eq!(join.engagement_weight, Some(0.5));
+        let message = serialize_event(&dispatch("MESSAGE_CREATE", message(serde_json::json!({}))), ShardId::ZERO, &config).unwrap();
+        assert_eq!(message.engagement_weight, Some(1.0));
+        assert_eq!(serde_json::to_value(&message).unwrap()["engagement_weight"], 1.0);
+
+        let voice = serialize_event(&streaming_voice_state(), ShardId::ZERO, &config).unwrap();
+        assert_eq!(voice.engagement_weight, None, "unlisted types carry no weight");
+    }
+
+    #[test]
+    fn intent_is_opt_in() {
+        let config = SerializeConfig { include_intent: true, ..Default::default() };
+        let event = serialize_event(&streaming_voice_state(), ShardId::ZERO, &config).unwrap();
+        assert_eq!(event.intent.as_deref(), Some("GUILD_VOICE_STATES"));
+
+        let plain = serialize_event(&streaming_voice_state(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+        assert!(serde_json::to_value(&plain).unwrap().get("intent").is_none());
+    }
+
+    #[test]
+    fn producer_version_matches_crate_version() {
+        let config = SerializeConfig { include_producer_version: true, ..Default::default() };
+        let event = serialize_event(&streaming_voice_state(), ShardId::ZERO, &config).unwrap();
+        assert_eq!(event.producer_version.as_deref(), Some(env!("CARGO_PKG_VERSION")));
+
+        let plain = serialize_event(&streaming_voice_state(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+        assert!(serde_json::to_value(&plain).unwrap().get("producer_version").is_none());
+    }
+
+    #[test]
+    fn back_to_back_events_have_increasing_timestamp_ns() {
+        let config = SerializeConfig { include_timestamp_ns: true, ..Default::default() };
+        let first = serialize_event(&streaming_voice_state(), ShardId::ZERO, &config).unwrap();
+        let second = serialize_event(&streaming_voice_state(), ShardId::ZERO, &config).unwrap();
+
+        assert!(second.timestamp_ns.unwrap() > first.timestamp_ns.unwrap());
+        let json = serde_json::to_value(&second).unwrap();
+        assert!(json["timestamp_ns"].is_u64());
+
+        let plain = serialize_event(&streaming_voice_state(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+        assert_eq!(plain.timestamp_ns, None);
+    }
+
+    #[test]
+    fn serialization_time_is_recorded_by_event_type() {
+        use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, || {
+            serialize_event(&streaming_voice_state(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            serialize_event(&Event::GatewayHeartbeatAck, ShardId::ZERO, &SerializeConfig::default());
+        });
+
+        let samples: Vec<_> = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .filter(|(key, ..)| key.key().name() == "gateway_serialize_duration_seconds")
+            .collect();
+        assert_eq!(samples.len(), 1, "dropped events record no timing");
+        let (key, _, _, value) = &samples[0];
+        assert!(key.key().labels().any(|l| l.key() == "event_type" && l.value() == "voice.state.update"));
+        assert!(matches!(value, DebugValue::Histogram(values) if values.len() == 1));
+    }
+
+    /// A member.join from a newer producer carrying a field this version
+    /// doesn't know about
+    fn payload_with_unknown_field() -> serde_json::Value {
+        serde_json::json!({
+            "event_id": "00000000-0000-4000-8000-000000000003",
+            "event_type": "member.join",
+            "shard_id": 0,
+            "timestamp": 1700000000000_u64,
+            "guild_id": "123456789012345678",
+            "channel_id": null,
+            "user_id": "987654321098765432",
+            "data": { "username": "testuser", "global_name": "Test User", "discriminator": null },
+            "added_in_a_future_version": true
+        })
+    }
+
+    #[test]
+    #[cfg(not(feature = "strict"))]
+    fn lenient_mode_ignores_unknown_fields() {
+        let event: GatewayEvent = serde_json::from_value(payload_with_unknown_field())
+            .expect("unknown fields must not fail deserialization by default");
+        assert_eq!(event.event_type, "member.join");
+        assert_eq!(event.user_id.as_deref(), Some("987654321098765432"));
+    }
+
+    #[test]
+    #[cfg(feature = "strict")]
+    fn strict_mode_rejects_unknown_fields() {
+        let err = serde_json::from_value::<GatewayEvent>(payload_with_unknown_field()).unwrap_err();
+        assert!(err.to_string().contains("added_in_a_future_version"));
+    }
+
+    fn thread_event(guild_id: Option<&str>) -> GatewayEvent {
+        GatewayEvent {
+            guild_id: guild_id.map(str::to_string),
+            ..gateway_event("thread.create").channel("333333333333333333").build()
+        }
+    }
+
+    #[test]
+    fn shard_info_derives_cluster_from_pool_size() {
+        let info = ShardInfo::for_shard(ShardId::new(57, 60).unwrap(), 60, 25);
+        assert_eq!(info, ShardInfo { shard_id: 57, shard_count: 60, cluster_id: 2, cluster_count: 3 });
+
+        let single = ShardInfo::for_shard(ShardId::ZERO, 1, 25);
+        assert_eq!((single.cluster_id, single.cluster_count), (0, 1));
+    }
+
+    #[test]
+    fn thread_event_without_guild_id_is_rejected() {
+        let err = validate_event(&thread_event(None)).unwrap_err();
+        assert!(matches!(err, GatewayError::MissingGuildId { ref event_type, .. } if event_type == "thread.create"));
+
+        assert!(validate_event(&thread_event(Some("123456789012345678"))).is_ok());
+    }
+
+    #[test]
+    fn guild_less_events_outside_guild_scope_are_valid() {
+        // DM interactions legitimately have no guild
+        let dm_interaction = GatewayEvent {
+            event_type: EventType::InteractionCreate,
+            ..Default::default()
+        };
+        assert!(validate_event(&dm_interaction).is_ok());
+    }
+
+    #[test]
+    fn message_create_is_tagged_with_detected_language() {
+        let config = SerializeConfig { detect_language: true, ..Default::default() };
+        let english = dispatch("MESSAGE_CREATE", message(serde_json::json!({
+            "content": "Does anyone know when the next community call is happening?"
+        })));
+        let french = dispatch("MESSAGE_CREATE", message(serde_json::json!({
+            "content": "Est-ce que quelqu'un sait quand aura lieu le prochain appel communautaire ?"
+        })));
+
+        let english = serialize_event(&english, ShardId::ZERO, &config).unwrap();
+        let french = serialize_event(&french, ShardId::ZERO, &config).unwrap();
+        assert_eq!(english.event_type, "message.create");
+        assert_eq!(english.data["detected_language"]["code"], "eng");
+        assert_eq!(french.data["detected_language"]["code"], "fra");
+
+        // Opt-in: no key without the option
+        let plain = serialize_event(&dispatch("MESSAGE_CREATE", message(serde_json::json!({}))), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+        assert!(plain.data.get("detected_language").is_none());
+    }
+
+    #[test]
+    fn unset_optional_fields_are_omitted() {
+        let json = serde_json::to_value(GatewayEvent::default()).unwrap();
+        let mut keys: Vec<_> = json.as_object().unwrap().keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, [
+            "channel_id", "data", "event_id", "event_type", "guild_id", "partition_key", "schema_version", "shard_id", "timestamp",
+            "user_id",
+        ]);
+
+        let event = GatewayEvent { timestamp_ns: Some(7), gateway_seq: Some(3), ..Default::default() };
+        let json = serde_json::to_value(event).unwrap();
+        assert_eq!(json["timestamp_ns"], 7);
+        assert_eq!(json["gateway_seq"], 3);
+        assert!(json.get("gateway_op").is_none());
+    }
+
+    #[test]
+    fn snowflake_timestamps_match_known_ids() {
+        // Discord's documented example snowflake: 2016-04-30T11:18:25.796Z
+        assert_eq!(snowflake_timestamp_ms(175928847299117063), 1462015105796);
+        // The Discord epoch itself
+        assert_eq!(snowflake_timestamp_ms(0), 1420070400000);
+        assert_eq!(snowflake_timestamp_ms(888888888888888888), 1631998025868);
+    }
+
+    #[test]
+    fn message_and_interaction_timestamps_come_from_their_ids() {
+        let message = dispatch("MESSAGE_CREATE", message(serde_json::json!({})));
+        let event = serialize_event(&message, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+        assert_eq!(event.timestamp, snowflake_timestamp_ms(888888888888888888));
+
+        let event = serialize_event(&user_option_command(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+        assert_eq!(event.timestamp, snowflake_timestamp_ms(444444444444444444));
+        let event = serialize_interaction(&user_option_command(), ShardId::ZERO).unwrap();
+        assert_eq!(event.timestamp, snowflake_timestamp_ms(444444444444444444));
+
+        // A user id dates the account, not the join
+        let join = serialize_event(&member_add(1, 987654321098765432, &[]), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+        assert!(join.timestamp > snowflake_timestamp_ms(987654321098765432));
+    }
+
+    #[test]
+    fn serialize_interaction_extracts_command_routing() {
+        let event = serialize_interaction(&user_option_command(), ShardId::ZERO).unwrap();
+        assert_eq!(event.command_name.as_deref(), Some("profile"));
+        assert_eq!(event.subcommand, None, "plain options are not subcommands");
+
+        let click = serialize_interaction(&button_click(), ShardId::ZERO).unwrap();
+        assert_eq!(click.command_name, None);
+        assert_eq!(click.data["custom_id"], "verify:start");
+
+        assert!(serialize_interaction(&member_add(1, 2, &[]), ShardId::ZERO).is_none());
+    }
+
+    #[test]
+    fn channel_less_interaction_serializes_without_channel_id() {
+        // User-installed command invoked in a DM, with no channel object
+        let event = dispatch("INTERACTION_CREATE", serde_json::json!({
+            "id": "444444444444444444",
+            "application_id": "555555555555555555",
+            "type": 2,
+            "token": "aW50ZXJhY3Rpb25fdG9rZW5fZXhhbXBsZQ",
+            "version": 1,
+            "user": {
+                "id": "987654321098765432",
+                "username": "testuser",
+                "discriminator": "0",
+                "avatar": null
+            },
+            "entitlements": [],
+            "authorizing_integration_owners": { "1": "987654321098765432" },
+            "data": { "id": "666666666666666666", "name": "profile", "type": 1 }
+        }));
+
+        let payload = serialize_event(&event, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+        assert_eq!(payload.event_type, "interaction.create");
+        assert_eq!(payload.channel_id, None);
+        assert_eq!(payload.guild_id, None);
+        assert_eq!(payload.user_id.as_deref(), Some("987654321098765432"));
+        assert_eq!(payload.data["interaction_type"], "ApplicationCommand");
+        assert!(serde_json::to_value(&payload).unwrap()["channel_id"].is_null());
+    }
+
+    #[test]
+    fn interaction_resolved_is_opt_in() {
+        let event = user_option_command();
+
+        let payload = serialize_event(&event, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+        assert!(payload.data.get("resolved").is_none());
+
+        let config = SerializeConfig { include_resolved: true, ..Default::default() };
+        let payload = serialize_event(&event, ShardId::ZERO, &config).unwrap();
+        let target = &payload.data["resolved"]["users"]["777777777777777777"];
+        assert_eq!(target["username"], "targetuser");
+        assert_eq!(target["global_name"], "Target User");
+        assert_eq!(
+            payload.data["resolved"]["members"]["777777777777777777"]["roles"],
+            serde_json::json!(["111111111111111111"])
+        );
+    }
 
     /// Fixture conformance: Rust must be able to round-trip deserialize
     /// every committed JSON fixture. If this fails, the Rust GatewayEvent
@@ -191,6 +2433,76 @@ mod tests {
                 .unwrap_or_else(|e| panic!("Fixture {} failed GatewayEvent deserialization: {}", name, e))
         }
 
+        /// Assert a freshly serialized event matches a fixture on everything
+        /// except the per-call `event_id` and `timestamp`, and carries the
+        /// current schema version (fixtures predate the field).
+        fn assert_matches_fixture(name: &str, actual: &GatewayEvent) {
+            let expected = deserialize_fixture(name);
+            assert_eq!(actual.schema_version, CURRENT_SCHEMA_VERSION, "{name}: schema_version");
+            assert_eq!(actual.event_type, expected.event_type, "{name}: event_type");
+            assert_eq!(actual.guild_id, expected.guild_id, "{name}: guild_id");
+            assert_eq!(actual.channel_id, expected.channel_id, "{name}: channel_id");
+            assert_eq!(actual.user_id, expected.user_id, "{name}: user_id");
+            assert_eq!(actual.data, expected.data, "{name}: data");
+        }
+
+        /// Like [`assert_matches_fixture`], for payloads whose fixture is a
+        /// minimum contract: every fixture `data` field must match, and the
+        /// event may carry more (guild.join's full guild object)
+        fn assert_matches_fixture_subset(name: &str, actual: &GatewayEvent) {
+            let expected = deserialize_fixture(name);
+            assert_eq!(actual.event_type, expected.event_type, "{name}: event_type");
+            assert_eq!(actual.guild_id, expected.guild_id, "{name}: guild_id");
+            for (field, value) in expected.data.as_object().expect("fixture data should be object") {
+                assert_eq!(actual.data.get(field), Some(value), "{name}: data.{field}");
+            }
+        }
+
+        #[test]
+        fn guild_join_large_fixture_matches_serializer() {
+            let presence = |user_id: &str| serde_json::json!({
+                "user": { "id": user_id },
+                "guild_id": "123456789012345678",
+                "status": "online",
+                "activities": [],
+                "client_status": { "desktop": "online" }
+            });
+            let mut guild = guild(123456789012345678, None);
+            guild["large"] = true.into();
+            guild["member_count"] = 250000.into();
+            guild["presences"] = serde_json::json!([presence("987654321098765432"), presence("777777777777777777")]);
+
+            let event = serialize_event(&dispatch("GUILD_CREATE", guild), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture_subset("guild-join-large", &event);
+        }
+
+        #[test]
+        fn guild_join_vanity_fixture_matches_serializer() {
+            let mut showcase = guild(123456789012345678, None);
+            showcase["vanity_url_code"] = "spice".into();
+            showcase["banner"] = "a_0123456789abcdef0123456789abcdef".into();
+
+            let event = serialize_event(&dispatch("GUILD_CREATE", showcase), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture_subset("guild-join-vanity", &event);
+
+            let plain = serialize_event(&dispatch("GUILD_CREATE", guild(123456789012345678, None)), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert!(plain.data["vanity_url_code"].is_null(), "null, not absent, without a vanity URL");
+            assert!(plain.data["banner"].is_null());
+        }
+
+        #[test]
+        fn guild_join_onboarding_fixture_matches_serializer() {
+            let mut community = guild(123456789012345678, Some(333333333333333333));
+            community["rules_channel_id"] = "444444444444444444".into();
+            community["public_updates_channel_id"] = "555555555555555555".into();
+
+            let event = serialize_event(&dispatch("GUILD_CREATE", community), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture_subset("guild-join-onboarding", &event);
+
+            let plain = serialize_event(&dispatch("GUILD_CREATE", guild(123456789012345678, None)), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert!(plain.data["public_updates_channel_id"].is_null(), "null, not absent, when unset");
+        }
+
         #[test]
         fn guild_join_fixture_deserializes() {
             let event = deserialize_fixture("guild-join");
@@ -209,6 +2521,263 @@ mod tests {
             let event = deserialize_fixture("member-join");
             assert_eq!(event.event_type, "member.join");
             assert!(event.user_id.is_some());
+            assert_eq!(event.data["global_name"], "Test User");
+            assert!(event.data["discriminator"].is_null(), "migrated accounts have no discriminator");
+        }
+
+        #[test]
+        fn member_join_flags_fixture_matches_serializer() {
+            let event = serialize_event(&rejoin_member_add(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("member-join-flags", &event);
+            assert_eq!(event.data["flags"]["did_rejoin"], true);
+            assert_eq!(event.data["flags"]["started_onboarding"], false);
+        }
+
+        #[test]
+        fn guild_ban_add_fixture_matches_serializer() {
+            let ban = dispatch("GUILD_BAN_ADD", serde_json::json!({
+                "guild_id": "123456789012345678",
+                "user": user(987654321098765432)
+            }));
+            let event = serialize_event(&ban, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("guild-ban-add", &event);
+        }
+
+        #[test]
+        fn guild_ban_remove_fixture_matches_serializer() {
+            let unban = dispatch("GUILD_BAN_REMOVE", serde_json::json!({
+                "guild_id": "123456789012345678",
+                "user": user(987654321098765432)
+            }));
+            let event = serialize_event(&unban, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("guild-ban-remove", &event);
+            assert_eq!(event.data["username"], "testuser");
+        }
+
+        /// Public thread in channel 333333333333333333
+        fn thread(id: &str, name: &str, archived: bool) -> serde_json::Value {
+            serde_json::json!({
+                "id": id,
+                "guild_id": "123456789012345678",
+                "parent_id": "333333333333333333",
+                "owner_id": "987654321098765432",
+                "name": name,
+                "type": 11,
+                "message_count": 4,
+                "member_count": 2,
+                "thread_metadata": {
+                    "archived": archived,
+                    "auto_archive_duration": 1440,
+                    "archive_timestamp": "2023-01-01T00:00:00.000000+00:00",
+                    "locked": false
+                }
+            })
+        }
+
+        #[test]
+        fn thread_list_sync_fixture_matches_serializer() {
+            let sync = dispatch("THREAD_LIST_SYNC", serde_json::json!({
+                "guild_id": "123456789012345678",
+                "channel_ids": ["333333333333333333"],
+                "threads": [
+                    thread("444444444444444444", "Patch notes", false),
+                    thread("555555555555555555", "Old bug reports", true)
+                ],
+                "members": []
+            }));
+            let event = serialize_event(&sync, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("thread-list-sync", &event);
+            validate_event(&event).unwrap();
+        }
+
+        #[test]
+        fn thread_create_fixture_matches_serializer() {
+            let create = dispatch("THREAD_CREATE", thread("444444444444444444", "Patch notes", false));
+            let event = serialize_event(&create, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("thread-create", &event);
+            assert_eq!(event.channel_id.as_deref(), Some("444444444444444444"), "the thread is the channel");
+            validate_event(&event).unwrap();
+        }
+
+        #[test]
+        fn thread_update_fixture_matches_serializer() {
+            let update = dispatch("THREAD_UPDATE", thread("444444444444444444", "Patch notes", true));
+            let event = serialize_event(&update, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("thread-update", &event);
+            assert_eq!(event.data["archived"], true);
+        }
+
+        #[test]
+        fn thread_delete_fixture_matches_serializer() {
+            let delete = dispatch("THREAD_DELETE", serde_json::json!({
+                "id": "444444444444444444",
+                "guild_id": "123456789012345678",
+                "parent_id": "333333333333333333",
+                "type": 11
+            }));
+            let event = serialize_event(&delete, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("thread-delete", &event);
+            validate_event(&event).unwrap();
+        }
+
+        #[test]
+        fn channel_update_fixture_matches_serializer() {
+            let update = dispatch("CHANNEL_UPDATE", serde_json::json!({
+                "id": "333333333333333333",
+                "guild_id": "123456789012345678",
+                "parent_id": "222222222222222222",
+                "name": "trading-floor",
+                "type": 0,
+                "position": 2,
+                "topic": "Spice prices only",
+                "nsfw": false,
+                "rate_limit_per_user": 30,
+                "permission_overwrites": []
+            }));
+            let event = serialize_event(&update, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("channel-update", &event);
+            validate_event(&event).unwrap();
+        }
+
+        fn member_chunk(chunk_index: u32, user_ids: &[u64]) -> Event {
+            let members: Vec<_> = user_ids
+                .iter()
+                .map(|&id| serde_json::json!({
+                    "user": user(id),
+                    "roles": ["555555555555555555"],
+                    "nick": null,
+                    "joined_at": "2023-01-01T00:00:00.000000+00:00",
+                    "deaf": false,
+                    "mute": false,
+                    "flags": 0
+                }))
+                .collect();
+            dispatch("GUILD_MEMBERS_CHUNK", serde_json::json!({
+                "guild_id": "123456789012345678",
+                "members": members,
+                "chunk_index": chunk_index,
+                "chunk_count": 2,
+                "nonce": "backfill-1",
+                "presences": [{
+                    "user": { "id": "987654321098765432" },
+                    "guild_id": "123456789012345678",
+                    "status": "online",
+                    "activities": [],
+                    "client_status": { "desktop": "online" }
+                }]
+            }))
+        }
+
+        #[test]
+        fn member_chunk_fixture_matches_serializer() {
+            let chunk = member_chunk(0, &[987654321098765432, 777777777777777777]);
+            let event = serialize_event(&chunk, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("member-chunk", &event);
+            assert!(event.data.get("presences").is_none());
+            validate_event(&event).unwrap();
+        }
+
+        #[test]
+        fn member_chunks_reassemble_into_the_full_member_set() {
+            let chunks = [member_chunk(1, &[666666666666666666]), member_chunk(0, &[987654321098765432, 777777777777777777])];
+            let events: Vec<_> = chunks
+                .iter()
+                .map(|chunk| serialize_event(chunk, ShardId::ZERO, &SerializeConfig::default()).unwrap())
+                .collect();
+
+            let chunk_count = events[0].data["chunk_count"].as_u64().unwrap();
+            let mut indexes: Vec<_> = events.iter().map(|e| e.data["chunk_index"].as_u64().unwrap()).collect();
+            indexes.sort_unstable();
+            assert_eq!(indexes, (0..chunk_count).collect::<Vec<_>>(), "every chunk held: complete");
+
+            let members: std::collections::BTreeSet<_> = events
+                .iter()
+                .flat_map(|e| e.data["members"].as_array().unwrap().iter().map(|m| m["id"].as_str().unwrap().to_string()))
+                .collect();
+            assert_eq!(members.len(), 3);
+        }
+
+        #[test]
+        fn user_update_fixture_matches_serializer() {
+            let update = dispatch("USER_UPDATE", serde_json::json!({
+                "id": "111111111111111111",
+                "username": "arrakis",
+                "global_name": "Arrakis",
+                "discriminator": "0",
+                "avatar": "a_1269e74af4df7417b13759eae50c83dc",
+                "banner": "06c16474723fe537c283b8efa61a30c8",
+                "bot": true,
+                "mfa_enabled": false
+            }));
+            let event = serialize_event(&update, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("user-update", &event);
+            validate_event(&event).unwrap();
+        }
+
+        #[test]
+        fn guild_update_fixture_matches_serializer() {
+            let update = guild_update(123456789012345678, "Spice Traders");
+            let event = serialize_event(&update, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("guild-update", &event);
+        }
+
+        #[test]
+        fn reaction_add_burst_fixture_matches_serializer() {
+            let reaction = dispatch("MESSAGE_REACTION_ADD", serde_json::json!({
+                "user_id": "987654321098765432",
+                "channel_id": "333333333333333333",
+                "message_id": "888888888888888888",
+                "guild_id": "123456789012345678",
+                "message_author_id": "555555555555555555",
+                "emoji": { "id": null, "name": "🔥" },
+                "burst": true,
+                "burst_colors": ["#FF6A00", "#FFD400"],
+                "type": 1
+            }));
+            let event = serialize_event(&reaction, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("reaction-add-burst", &event);
+        }
+
+        #[test]
+        fn reaction_add_custom_fixture_matches_serializer() {
+            let reaction = dispatch("MESSAGE_REACTION_ADD", serde_json::json!({
+                "user_id": "987654321098765432",
+                "channel_id": "333333333333333333",
+                "message_id": "888888888888888888",
+                "guild_id": "123456789012345678",
+                "emoji": { "id": "777777777777777777", "name": "sietch", "animated": false },
+                "burst": false,
+                "type": 0
+            }));
+            let event = serialize_event(&reaction, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("reaction-add-custom", &event);
+        }
+
+        #[test]
+        fn reaction_remove_fixture_matches_serializer() {
+            let reaction = dispatch("MESSAGE_REACTION_REMOVE", serde_json::json!({
+                "user_id": "987654321098765432",
+                "channel_id": "333333333333333333",
+                "message_id": "888888888888888888",
+                "guild_id": "123456789012345678",
+                "emoji": { "id": null, "name": "🔥" },
+                "burst": false,
+                "type": 0
+            }));
+            let event = serialize_event(&reaction, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("reaction-remove", &event);
+        }
+
+        #[test]
+        fn typing_start_fixture_matches_serializer() {
+            let typing = dispatch("TYPING_START", serde_json::json!({
+                "channel_id": "333333333333333333",
+                "guild_id": "123456789012345678",
+                "user_id": "987654321098765432",
+                "timestamp": 1700000000
+            }));
+            let event = serialize_event(&typing, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("typing-start", &event);
         }
 
         #[test]
@@ -223,6 +2792,14 @@ mod tests {
             assert_eq!(event.event_type, "member.update");
         }
 
+        #[test]
+        fn member_update_fixture_matches_serializer() {
+            let update = member_update(123456789012345678, 987654321098765432, &[111111111111111111, 222222222222222222]);
+            let event = serialize_event(&update, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("member-update", &event);
+            assert_eq!(event.data["global_name"], "Test User");
+        }
+
         #[test]
         fn interaction_create_fixture_deserializes() {
             let event = deserialize_fixture("interaction-create");
@@ -233,21 +2810,325 @@ mod tests {
             assert!(!data.contains_key("token"), "BB60-20: must NOT have bare 'token' field");
         }
 
+        #[test]
+        fn interaction_create_resolved_fixture_matches_serializer() {
+            let config = SerializeConfig { include_resolved: true, ..Default::default() };
+            let event = serialize_event(&user_option_command(), ShardId::ZERO, &config).unwrap();
+            assert_matches_fixture("interaction-create-resolved", &event);
+
+            let data = event.data.as_object().expect("data should be object");
+            assert!(data.contains_key("interaction_token"), "BB60-20: must use interaction_token");
+            assert!(!data.contains_key("token"), "BB60-20: must NOT have bare 'token' field");
+        }
+
+        #[test]
+        fn interaction_create_component_fixture_matches_serializer() {
+            let event = serialize_event(&button_click(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("interaction-create-component", &event);
+            assert_eq!(event.data["message_id"], "888888888888888888");
+            assert_eq!(event.data["custom_id"], "verify:start");
+        }
+
+        #[test]
+        fn interaction_autocomplete_fixture_matches_serializer() {
+            let event = serialize_event(&role_name_autocomplete(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("interaction-autocomplete", &event);
+            assert_eq!(event.data["focused"], "name");
+            assert_eq!(event.data["value"], "mod");
+        }
+
+        #[test]
+        fn interaction_modal_fixture_matches_serializer() {
+            let event = serialize_event(&application_modal_submit(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("interaction-modal", &event);
+            let fields = event.data["fields"].as_object().expect("fields should be object");
+            assert_eq!(fields.len(), 2, "one field per action row");
+        }
+
+        #[test]
+        fn message_create_webhook_fixture_matches_serializer() {
+            let event = serialize_event(&webhook_message(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("message-create-webhook", &event);
+            assert_eq!(event.user_id, None);
+            assert_eq!(event.data["is_webhook"], true);
+        }
+
+        #[test]
+        fn message_create_fixture_matches_serializer() {
+            let config = SerializeConfig { capture_message_content: true, ..Default::default() };
+            let event = serialize_event(&attachment_message(), ShardId::ZERO, &config).unwrap();
+            assert_matches_fixture("message-create", &event);
+        }
+
+        #[test]
+        fn message_content_is_captured_only_when_enabled() {
+            let event = serialize_event(&attachment_message(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert!(event.data.get("content").is_none());
+            assert_eq!(event.data["attachments"].as_array().unwrap().len(), 1, "metadata is still forwarded");
+
+            let bot = serialize_event(&command_response_message(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_eq!(bot.data["is_bot"], true);
+        }
+
+        #[test]
+        fn message_create_embed_fixture_matches_serializer() {
+            let event = serialize_event(&rich_embed_message(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("message-create-embed", &event);
+        }
+
+        #[test]
+        fn message_create_command_response_fixture_matches_serializer() {
+            let event = serialize_event(&command_response_message(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("message-create-command-response", &event);
+            assert_eq!(event.data["interaction_metadata"]["user_id"], "987654321098765432");
+        }
+
+        #[test]
+        fn message_create_components_fixture_matches_serializer() {
+            let config = SerializeConfig { include_components: true, ..Default::default() };
+            let event = serialize_event(&button_message(), ShardId::ZERO, &config).unwrap();
+            assert_matches_fixture("message-create-components", &event);
+
+            let event = serialize_event(&button_message(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert!(event.data.get("components").is_none(), "opt-in");
+        }
+
+        #[test]
+        fn message_update_fixture_matches_content_edit() {
+            let event = serialize_event(&content_edit(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("message-update", &event);
+        }
+
+        #[test]
+        fn message_update_content_is_captured_only_when_enabled() {
+            let event = serialize_event(&content_edit(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert!(event.data.get("content").is_none());
+
+            let config = SerializeConfig { capture_message_content: true, ..Default::default() };
+            let event = serialize_event(&content_edit(), ShardId::ZERO, &config).unwrap();
+            assert_eq!(event.data["content"], "check out https://example.org");
+        }
+
+        #[test]
+        fn message_delete_fixture_matches_serializer() {
+            let delete = dispatch("MESSAGE_DELETE", serde_json::json!({
+                "id": "888888888888888888",
+                "channel_id": "333333333333333333",
+                "guild_id": "123456789012345678"
+            }));
+            let event = serialize_event(&delete, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("message-delete", &event);
+        }
+
+        #[test]
+        fn message_delete_bulk_fixture_matches_serializer() {
+            let purge = dispatch("MESSAGE_DELETE_BULK", serde_json::json!({
+                "ids": ["888888888888888888", "888888888888888889"],
+                "channel_id": "333333333333333333",
+                "guild_id": "123456789012345678"
+            }));
+            let event = serialize_event(&purge, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("message-delete-bulk", &event);
+        }
+
+        #[test]
+        fn message_embed_update_fixture_matches_link_unfurl() {
+            let event = serialize_event(&link_unfurl(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("message-embed-update", &event);
+            assert_ne!(event.event_type, "message.update", "unfurls are not user edits");
+        }
+
+        #[test]
+        fn automod_rule_fixtures_match_serializer() {
+            for (event_name, fixture, enabled) in [
+                ("AUTO_MODERATION_RULE_CREATE", "automod-rule-create", true),
+                ("AUTO_MODERATION_RULE_UPDATE", "automod-rule-update", false),
+                ("AUTO_MODERATION_RULE_DELETE", "automod-rule-delete", false),
+            ] {
+                let event = serialize_event(&automod_rule(event_name, enabled), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+                assert_matches_fixture(fixture, &event);
+            }
+        }
+
+        #[test]
+        fn guild_anomaly_fixture_matches_detector() {
+            use crate::events::anomaly::{AnomalyConfig, AnomalyDetector};
+            use std::time::{Duration, Instant};
+
+            let mut detector = AnomalyDetector::new(AnomalyConfig {
+                threshold: 2,
+                window: Duration::from_secs(60),
+            });
+            let join = GatewayEvent {
+                event_type: EventType::MemberJoin,
+                guild_id: Some("123456789012345678".to_string()),
+                ..Default::default()
+            };
+            let now = Instant::now();
+            let alert = (0..3).find_map(|_| detector.observe(&join, now)).unwrap();
+            assert_matches_fixture("guild-anomaly", &alert);
+        }
+
+        #[test]
+        fn voice_state_update_fixture_matches_serializer() {
+            let event = serialize_event(&streaming_voice_state(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("voice-state-update", &event);
+            assert_eq!(event.data["self_stream"], true);
+        }
+
+        #[test]
+        fn presence_update_fixture_matches_serializer() {
+            let event = serialize_event(&idle_presence(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("presence-update", &event);
+        }
+
+        #[test]
+        fn presence_update_spotify_fixture_matches_serializer() {
+            let presence = dispatch("PRESENCE_UPDATE", serde_json::json!({
+                "guild_id": "123456789012345678",
+                "user": { "id": "987654321098765432" },
+                "status": "online",
+                "activities": [
+                    { "name": "Custom Status", "type": 4, "state": "reading Dune", "created_at": 1700000000000u64 },
+                    {
+                        "name": "Spotify",
+                        "type": 2,
+                        "details": "Leaving Caladan",
+                        "state": "Hans Zimmer",
+                        "party": { "id": "spotify:987654321098765432" },
+                        "created_at": 1700000000000u64
+                    }
+                ],
+                "client_status": { "desktop": "online" }
+            }));
+            let event = serialize_event(&presence, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("presence-update-spotify", &event);
+        }
+
+        #[test]
+        fn scheduled_event_user_remove_fixture_matches_serializer() {
+            let event = serialize_event(&scheduled_event_unsubscribe(), ShardId::ZERO, &SerializeConfig::default()).unwrap();
+            assert_matches_fixture("scheduled-event-user-remove", &event);
+        }
+
+        #[test]
+        fn shard_reconnect_requested_fixture_matches_serializer() {
+            let config = SerializeConfig { forward_reconnects: true, ..Default::default() };
+            let event = serialize_event(&Event::GatewayReconnect, ShardId::ZERO, &config).unwrap();
+            assert_matches_fixture("shard-reconnect-requested", &event);
+        }
+
+        #[test]
+        fn member_onboarding_complete_fixture_matches_pipeline() {
+            use crate::events::pipeline::EventPipeline;
+            use crate::events::test_support::{dispatch, user};
+
+            let member = |event_name: &str, flags: u64| dispatch(event_name, serde_json::json!({
+                "guild_id": "123456789012345678",
+                "user": user(987654321098765432),
+                "roles": [],
+                "nick": null,
+                "joined_at": "2023-01-01T00:00:00.000000+00:00",
+                "deaf": false,
+                "mute": false,
+                "flags": flags
+            }));
+            let mut pipeline = EventPipeline::new(ShardId::ZERO, SerializeConfig::default());
+            pipeline.process(&member("GUILD_MEMBER_ADD", 0)).unwrap();
+            let payloads = pipeline.process(&member("GUILD_MEMBER_UPDATE", 2)).unwrap();
+
+            assert_eq!(payloads.len(), 2);
+            assert_matches_fixture("member-onboarding-complete", &payloads[1]);
+        }
+
+        #[test]
+        fn member_timeout_expired_fixture_matches_pipeline() {
+            let config = SerializeConfig { timeout_expiry: true, ..Default::default() };
+            let clock = Arc::new(AtomicU64::new(1700000000000));
+            let mut pipeline = crate::events::pipeline::EventPipeline::new(ShardId::ZERO, config).with_clock({
+                let clock = clock.clone();
+                move || clock.load(Ordering::Relaxed)
+            });
+            pipeline.process(&dispatch("GUILD_MEMBER_UPDATE", serde_json::json!({
+                "guild_id": "123456789012345678",
+                "user": user(987654321098765432),
+                "roles": [],
+                "joined_at": "2023-01-01T00:00:00.000000+00:00",
+                "communication_disabled_until": "2023-11-14T22:23:20.000000+00:00"
+            }))).unwrap();
+
+            clock.store(1700000600000, Ordering::Relaxed);
+            let event = pipeline.process(&Event::GatewayHeartbeatAck).unwrap().remove(0);
+            assert_matches_fixture("member-timeout-expired", &event);
+            assert_eq!(event.timestamp, deserialize_fixture("member-timeout-expired").timestamp);
+        }
+
+        #[test]
+        fn message_pin_update_fixture_matches_pipeline() {
+            let mut pipeline = crate::events::pipeline::EventPipeline::new(ShardId::ZERO, SerializeConfig::default());
+            pipeline.process(&dispatch("MESSAGE_CREATE", message(serde_json::json!({})))).unwrap();
+
+            let pin = dispatch("MESSAGE_UPDATE", message(serde_json::json!({ "pinned": true })));
+            let event = pipeline.process(&pin).unwrap().remove(0);
+            assert_matches_fixture("message-pin-update", &event);
+        }
+
+        #[test]
+        fn interaction_event_fixture_matches_serializer() {
+            let expected: InteractionEvent = serde_json::from_value(load_fixture("interaction-event")).unwrap();
+            let event = serialize_interaction(&subcommand_group_command(), ShardId::ZERO).unwrap();
+            assert_eq!(event.interaction_id, expected.interaction_id);
+            assert_eq!(event.interaction_token, expected.interaction_token);
+            assert_eq!(event.guild_id, expected.guild_id);
+            assert_eq!(event.channel_id, expected.channel_id);
+            assert_eq!(event.user_id, expected.user_id);
+            assert_eq!(event.command_name.as_deref(), Some("config"));
+            assert_eq!(event.subcommand.as_deref(), Some("roles add"));
+            assert_eq!(event.command_name, expected.command_name);
+            assert_eq!(event.subcommand, expected.subcommand);
+            assert_eq!(event.data, expected.data);
+            assert_eq!(event.schema_version, CURRENT_SCHEMA_VERSION);
+
+            let json = serde_json::to_value(&event).unwrap();
+            assert!(json.get("interaction_token").is_some(), "BB60-20: must use interaction_token");
+            assert!(json.get("token").is_none(), "BB60-20: must NOT have bare 'token' field");
+        }
+
+        #[test]
+        fn interaction_event_fixture_round_trips_through_serde() {
+            let fixture = load_fixture("interaction-event");
+            let event: InteractionEvent = serde_json::from_value(fixture.clone()).unwrap();
+            assert_eq!(event.schema_version, 0, "legacy fixture");
+
+            let mut json = serde_json::to_value(&event).unwrap();
+            assert_eq!(json.as_object_mut().unwrap().remove("schema_version"), Some(0.into()));
+            assert_eq!(json, fixture);
+        }
+
         #[test]
         fn all_fixtures_round_trip_through_serde() {
             let fixtures = [
-                "guild-join", "guild-leave",
-                "member-join", "member-leave", "member-update",
-                "interaction-create",
+                "guild-join", "guild-join-large", "guild-join-vanity", "guild-join-onboarding", "guild-leave", "guild-anomaly",
+                "member-join", "member-join-flags", "member-leave", "guild-ban-add", "guild-ban-remove", "thread-list-sync", "thread-create", "thread-update", "thread-delete", "channel-update", "user-update", "reaction-add-burst", "reaction-add-custom", "reaction-remove", "typing-start", "guild-update", "member-update", "member-onboarding-complete", "member-timeout-expired", "member-chunk",
+                "interaction-create", "interaction-create-resolved",
+                "interaction-create-component", "interaction-autocomplete", "interaction-modal",
+                "message-create", "message-create-webhook", "message-create-command-response", "message-create-embed", "message-create-components", "message-update", "message-embed-update", "message-pin-update", "message-delete", "message-delete-bulk",
+                "voice-state-update",
+                "automod-rule-create", "automod-rule-update", "automod-rule-delete",
+                "scheduled-event-user-remove", "presence-update", "presence-update-spotify",
+                "shard-reconnect-requested",
             ];
             for name in fixtures {
                 let event = deserialize_fixture(name);
+                // Committed fixtures predate schema_version
+                assert_eq!(event.schema_version, 0, "{name}: legacy fixtures parse as version 0");
                 // Re-serialize and verify it produces valid JSON
                 let json = serde_json::to_string(&event)
                     .unwrap_or_else(|e| panic!("Re-serialization of {} failed: {}", name, e));
                 // Deserialize again to prove round-trip
-                let _: GatewayEvent = serde_json::from_str(&json)
+                let round_tripped: GatewayEvent = serde_json::from_str(&json)
                     .unwrap_or_else(|e| panic!("Round-trip of {} failed: {}", name, e));
+                assert_eq!(round_tripped.schema_version, 0, "{name}: version survives the round trip");
             }
         }
     }
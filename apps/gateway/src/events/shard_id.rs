@@ -0,0 +1,88 @@
+//! Validated shard id
+//!
+//! Shard ids, channel ids and counts are all integers, so a bare `u64`
+//! parameter accepts any of them. [`ShardId`] can only be built from a
+//! value checked against the shard count (or from Twilight's own shard id,
+//! which is checked already), and serializes as a plain number so the wire
+//! format is unchanged.
+
+use crate::error::GatewayError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Id of the shard an event arrived on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ShardId(u64);
+
+impl ShardId {
+    /// Shard 0, valid for any shard count
+    pub const ZERO: Self = Self(0);
+
+    /// Validate `shard_id` against the total `shard_count`
+    pub fn new(shard_id: u64, shard_count: u64) -> Result<Self, GatewayError> {
+        if shard_id >= shard_count {
+            return Err(GatewayError::InvalidShardId { shard_id, shard_count });
+        }
+        Ok(Self(shard_id))
+    }
+
+    /// The raw shard number
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+}
+
+impl From<twilight_model::gateway::ShardId> for ShardId {
+    fn from(shard: twilight_model::gateway::ShardId) -> Self {
+        Self(shard.number().into())
+    }
+}
+
+impl From<ShardId> for u64 {
+    fn from(shard: ShardId) -> Self {
+        shard.0
+    }
+}
+
+impl PartialEq<u64> for ShardId {
+    fn eq(&self, other: &u64) -> bool {
+        self.0 == *other
+    }
+}
+
+impl fmt::Display for ShardId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shard_ids_are_validated_against_shard_count() {
+        assert_eq!(ShardId::new(0, 1).unwrap(), 0);
+        assert_eq!(ShardId::new(24, 25).unwrap().get(), 24);
+
+        let err = ShardId::new(25, 25).unwrap_err();
+        assert!(matches!(err, GatewayError::InvalidShardId { shard_id: 25, shard_count: 25 }));
+        assert!(ShardId::new(0, 0).is_err(), "no shard is valid without shards");
+        // A snowflake is never a plausible shard id
+        assert!(ShardId::new(123456789012345678, 100).is_err());
+    }
+
+    #[test]
+    fn serializes_as_a_plain_number() {
+        let shard = ShardId::new(3, 4).unwrap();
+        assert_eq!(serde_json::to_string(&shard).unwrap(), "3");
+        assert_eq!(serde_json::from_str::<ShardId>("3").unwrap(), shard);
+    }
+
+    #[test]
+    fn converts_from_twilight_shard_id() {
+        let shard = ShardId::from(twilight_model::gateway::ShardId::new(7, 16));
+        assert_eq!(shard.get(), 7);
+    }
+}
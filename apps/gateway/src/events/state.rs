@@ -0,0 +1,383 @@
+//! Shard-local serializer state
+//!
+//! Some payload options need to compare an event against what the shard
+//! saw previously (e.g. whether a member update actually touched roles).
+//! This state lives per shard alongside the event loop and only holds what
+//! those comparisons need, plus the shard's sequence counter and cache
+//! epoch. [`SerializerState::snapshot`] encodes all of it so a restarting
+//! shard can [`restore`](SerializerState::restore) and resume with
+//! consistent sequence numbers and caches.
+
+use crate::error::GatewayError;
+use crate::events::serialize::{now_millis, GatewayEvent};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use twilight_model::gateway::presence::Status;
+
+/// Entries kept in each ban/leave correlation queue; a ban and its leave
+/// arrive within moments of each other, so older entries are stale
+const BAN_CORRELATION_CAPACITY: usize = 1024;
+
+/// Messages whose pin state is tracked; pins mostly hit recent messages,
+/// and updates to older ones stay plain `message.update`
+const MESSAGE_CAPACITY: usize = 10_000;
+
+/// Last-known values for a single guild member
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemberSnapshot {
+    /// Role ids, sorted so comparisons ignore Discord's ordering
+    pub roles: Vec<u64>,
+    /// Joined but hasn't passed membership screening yet; only tracked
+    /// when `join_after_screening` is on
+    pub pending: bool,
+    /// Whether the member has completed onboarding, if a payload with
+    /// member flags has been seen
+    pub onboarded: Option<bool>,
+}
+
+/// Last-known pin and edit state of a message
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageSnapshot {
+    pub pinned: bool,
+    /// Last edit, in microseconds since the Unix epoch
+    pub edited_at: Option<i64>,
+}
+
+/// Last-known channel configuration for a guild
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuildSnapshot {
+    pub system_channel_id: Option<u64>,
+    pub rules_channel_id: Option<u64>,
+}
+
+/// State consulted and updated by [`EventPipeline`](super::pipeline::EventPipeline)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SerializerState {
+    /// Sequence assigned to the last forwarded event
+    sequence: u64,
+    /// Unix ms the sequence started at, set with the first sequence
+    producer_epoch: Option<u64>,
+    /// Fresh sessions seen; bumped on each READY (not on RESUMED, which
+    /// keeps the session's guild cache)
+    cache_epoch: u64,
+    /// guild_id → user_id → last-known member values
+    members: HashMap<u64, HashMap<u64, MemberSnapshot>>,
+    /// guild_id → last-known guild channel configuration
+    guilds: HashMap<u64, GuildSnapshot>,
+    /// guild_id → last emitted `guild.update` summary, for
+    /// `guild_update_deltas`
+    guild_updates: HashMap<u64, serde_json::Map<String, serde_json::Value>>,
+    /// guild_id → user_id → last-known presence status, for
+    /// `compact_presence`; pruned as members and guilds go
+    presences: HashMap<u64, HashMap<u64, Status>>,
+    /// guild_id → user_id → Unix ms a member's timeout lifts, for
+    /// `timeout_expiry`
+    timeouts: HashMap<u64, HashMap<u64, u64>>,
+    /// message_id → last-known pin/edit state
+    messages: HashMap<u64, MessageSnapshot>,
+    /// Tracked message ids, oldest first, for eviction
+    message_order: VecDeque<u64>,
+    /// (guild_id, user_id) of recent real leaves not yet matched to a ban
+    recent_leaves: VecDeque<(u64, u64)>,
+    /// (guild_id, user_id) of bans whose real leave hasn't arrived yet
+    pending_ban_leaves: VecDeque<(u64, u64)>,
+}
+
+impl SerializerState {
+    /// Encode the state for restoring after a restart
+    pub fn snapshot(&self) -> Result<Vec<u8>, GatewayError> {
+        serde_json::to_vec(self).map_err(GatewayError::StateSnapshot)
+    }
+
+    /// Decode a [`snapshot`](Self::snapshot)
+    pub fn restore(bytes: &[u8]) -> Result<Self, GatewayError> {
+        serde_json::from_slice(bytes).map_err(GatewayError::StateSnapshot)
+    }
+
+    /// Sequence assigned to the last forwarded event (0 before the first)
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Advance and return the sequence for the next forwarded event
+    pub fn next_sequence(&mut self) -> u64 {
+        self.producer_epoch.get_or_insert_with(now_millis);
+        self.sequence += 1;
+        self.sequence
+    }
+
+    /// When this state's sequence started, if it has assigned one yet;
+    /// kept across [`restore`](Self::restore)
+    pub fn producer_epoch(&self) -> Option<u64> {
+        self.producer_epoch
+    }
+
+    /// Restart the sequence at 1 under an epoch later than the current one
+    ///
+    /// For state restored from a snapshot that may predate events already
+    /// emitted: continuing its sequence would reuse their positions.
+    pub fn start_new_epoch(&mut self) {
+        let after = self.producer_epoch.map_or(0, |epoch| epoch + 1);
+        self.producer_epoch = Some(now_millis().max(after));
+        self.sequence = 0;
+    }
+
+    /// Number of fresh sessions (READY) seen
+    pub fn cache_epoch(&self) -> u64 {
+        self.cache_epoch
+    }
+
+    /// Record a fresh session, starting a new cache epoch
+    pub fn new_session(&mut self) {
+        self.cache_epoch += 1;
+    }
+
+    /// Get the last-known snapshot for a member, if seen on this shard
+    pub fn member(&self, guild_id: u64, user_id: u64) -> Option<&MemberSnapshot> {
+        self.members.get(&guild_id)?.get(&user_id)
+    }
+
+    /// Get a mutable snapshot for a member, creating an empty one if unseen
+    pub fn member_mut(&mut self, guild_id: u64, user_id: u64) -> &mut MemberSnapshot {
+        self.members.entry(guild_id).or_default().entry(user_id).or_default()
+    }
+
+    /// Forget a member (they left the guild)
+    pub fn remove_member(&mut self, guild_id: u64, user_id: u64) {
+        if let Some(guild) = self.members.get_mut(&guild_id) {
+            guild.remove(&user_id);
+            if guild.is_empty() {
+                self.members.remove(&guild_id);
+            }
+        }
+        if let Some(guild) = self.presences.get_mut(&guild_id) {
+            guild.remove(&user_id);
+            if guild.is_empty() {
+                self.presences.remove(&guild_id);
+            }
+        }
+        self.set_timeout(guild_id, user_id, None);
+    }
+
+    /// Record when a member's timeout lifts (Unix ms), or None if they
+    /// aren't timed out
+    pub fn set_timeout(&mut self, guild_id: u64, user_id: u64, until: Option<u64>) {
+        match until {
+            Some(until) => {
+                self.timeouts.entry(guild_id).or_default().insert(user_id, until);
+            }
+            None => {
+                if let Some(guild) = self.timeouts.get_mut(&guild_id) {
+                    guild.remove(&user_id);
+                    if guild.is_empty() {
+                        self.timeouts.remove(&guild_id);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Forget and return the (guild_id, user_id, until) timeouts that have
+    /// lifted by `now` (Unix ms), earliest first
+    pub fn take_expired_timeouts(&mut self, now: u64) -> Vec<(u64, u64, u64)> {
+        let mut expired = Vec::new();
+        self.timeouts.retain(|&guild_id, users| {
+            users.retain(|&user_id, &mut until| {
+                let lifted = until <= now;
+                if lifted {
+                    expired.push((guild_id, user_id, until));
+                }
+                !lifted
+            });
+            !users.is_empty()
+        });
+        expired.sort_unstable_by_key(|&(_, _, until)| until);
+        expired
+    }
+
+    /// Last-known presence status of a user, if tracked on this shard
+    pub fn presence(&self, guild_id: u64, user_id: u64) -> Option<Status> {
+        self.presences.get(&guild_id)?.get(&user_id).copied()
+    }
+
+    /// Record a user's presence status, returning the previous one
+    pub fn set_presence(&mut self, guild_id: u64, user_id: u64, status: Status) -> Option<Status> {
+        self.presences.entry(guild_id).or_default().insert(user_id, status)
+    }
+
+    /// Remember a real member leave, for a ban that may follow it
+    pub fn record_leave(&mut self, guild_id: u64, user_id: u64) {
+        push_bounded(&mut self.recent_leaves, (guild_id, user_id));
+    }
+
+    /// Returns true (and forgets it) if the member's real leave was seen
+    pub fn take_recent_leave(&mut self, guild_id: u64, user_id: u64) -> bool {
+        take(&mut self.recent_leaves, (guild_id, user_id))
+    }
+
+    /// Remember a ban whose real leave is still to come
+    pub fn expect_ban_leave(&mut self, guild_id: u64, user_id: u64) {
+        push_bounded(&mut self.pending_ban_leaves, (guild_id, user_id));
+    }
+
+    /// Returns true (and forgets it) if a ban already accounted for this leave
+    pub fn take_ban_leave(&mut self, guild_id: u64, user_id: u64) -> bool {
+        take(&mut self.pending_ban_leaves, (guild_id, user_id))
+    }
+
+    /// Record a message's pin/edit state, returning the previous one
+    ///
+    /// Only the most recent messages are kept; the oldest is forgotten when
+    /// the cache is full.
+    pub fn set_message(&mut self, message_id: u64, snapshot: MessageSnapshot) -> Option<MessageSnapshot> {
+        let previous = self.messages.insert(message_id, snapshot);
+        if previous.is_none() {
+            if self.message_order.len() == MESSAGE_CAPACITY {
+                if let Some(oldest) = self.message_order.pop_front() {
+                    self.messages.remove(&oldest);
+                }
+            }
+            self.message_order.push_back(message_id);
+        }
+        previous
+    }
+
+    /// Forget a deleted message
+    ///
+    /// Its id stays in the eviction order until it ages out; ids are never
+    /// reused, so the stale entry is harmless.
+    pub fn remove_message(&mut self, message_id: u64) {
+        self.messages.remove(&message_id);
+    }
+
+    /// Get the last-known snapshot for a guild, if seen on this shard
+    pub fn guild(&self, guild_id: u64) -> Option<&GuildSnapshot> {
+        self.guilds.get(&guild_id)
+    }
+
+    /// Record a guild's channel configuration (GuildCreate/GuildUpdate)
+    pub fn set_guild(&mut self, guild_id: u64, snapshot: GuildSnapshot) {
+        self.guilds.insert(guild_id, snapshot);
+    }
+
+    /// Record the guild's latest `guild.update` summary, returning the
+    /// previous one
+    pub fn set_guild_update(
+        &mut self,
+        guild_id: u64,
+        summary: serde_json::Map<String, serde_json::Value>,
+    ) -> Option<serde_json::Map<String, serde_json::Value>> {
+        self.guild_updates.insert(guild_id, summary)
+    }
+
+    /// Forget the guild's last `guild.update` summary, so the next update
+    /// is emitted in full
+    pub fn clear_guild_update(&mut self, guild_id: u64) {
+        self.guild_updates.remove(&guild_id);
+    }
+
+    /// Forget everything about a guild (the bot left it)
+    pub fn remove_guild(&mut self, guild_id: u64) {
+        self.members.remove(&guild_id);
+        self.guilds.remove(&guild_id);
+        self.guild_updates.remove(&guild_id);
+        self.presences.remove(&guild_id);
+    }
+
+    /// Best-effort channel for channel-partitioned consumers
+    ///
+    /// Policy, in order:
+    /// 1. The event's own `channel_id`, when it has one.
+    /// 2. For guild-level events (`guild.*`, `member.*`) in a guild seen on
+    ///    this shard: the guild's system channel, else its rules channel.
+    /// 3. Otherwise `None`; no channel is invented.
+    ///
+    /// This never changes the wire payload; `channel_id` stays `null` on
+    /// events without channel context. Under `guild_id_hashing` the
+    /// event's `guild_id` is a hash that step 2 can't match; use
+    /// [`EventPipeline::resolve_channel_id`](super::pipeline::EventPipeline::resolve_channel_id),
+    /// which maps it back first.
+    pub fn resolve_channel_id(&self, event: &GatewayEvent) -> Option<String> {
+        self.resolve_channel_id_in(event, event.guild_id.as_deref())
+    }
+
+    /// [`resolve_channel_id`](Self::resolve_channel_id) with the event's raw
+    /// guild id passed separately
+    pub(crate) fn resolve_channel_id_in(&self, event: &GatewayEvent, guild_id: Option<&str>) -> Option<String> {
+        if let Some(ref channel_id) = event.channel_id {
+            return Some(channel_id.clone());
+        }
+
+        let guild_level = ["guild.", "member."]
+            .iter()
+            .any(|prefix| event.event_type.starts_with(prefix));
+        if !guild_level {
+            return None;
+        }
+
+        let guild = self.guild(guild_id?.parse().ok()?)?;
+        guild
+            .system_channel_id
+            .or(guild.rules_channel_id)
+            .map(|id| id.to_string())
+    }
+}
+
+fn push_bounded(queue: &mut VecDeque<(u64, u64)>, entry: (u64, u64)) {
+    if queue.len() == BAN_CORRELATION_CAPACITY {
+        queue.pop_front();
+    }
+    queue.push_back(entry);
+}
+
+fn take(queue: &mut VecDeque<(u64, u64)>, entry: (u64, u64)) -> bool {
+    match queue.iter().position(|e| *e == entry) {
+        Some(index) => {
+            queue.remove(index);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::test_support::gateway_event;
+
+    const GUILD: u64 = 123456789012345678;
+
+    fn event(event_type: &str, channel_id: Option<&str>) -> GatewayEvent {
+        GatewayEvent {
+            channel_id: channel_id.map(str::to_string),
+            ..gateway_event(event_type).guild(&GUILD.to_string()).build()
+        }
+    }
+
+    fn state_with(system: Option<u64>, rules: Option<u64>) -> SerializerState {
+        let mut state = SerializerState::default();
+        state.set_guild(GUILD, GuildSnapshot { system_channel_id: system, rules_channel_id: rules });
+        state
+    }
+
+    #[test]
+    fn guild_level_event_resolves_to_cached_system_channel() {
+        let state = state_with(Some(444), Some(555));
+        assert_eq!(state.resolve_channel_id(&event("member.join", None)).as_deref(), Some("444"));
+
+        let state = state_with(None, Some(555));
+        assert_eq!(state.resolve_channel_id(&event("member.join", None)).as_deref(), Some("555"));
+    }
+
+    #[test]
+    fn own_channel_id_wins_and_unknown_guilds_resolve_to_none() {
+        let state = state_with(Some(444), None);
+        assert_eq!(
+            state.resolve_channel_id(&event("voice.state.update", Some("333"))).as_deref(),
+            Some("333")
+        );
+        assert_eq!(state.resolve_channel_id(&event("voice.state.update", None)), None);
+        assert_eq!(SerializerState::default().resolve_channel_id(&event("member.join", None)), None);
+    }
+}
@@ -0,0 +1,245 @@
+//! Test helpers for building Twilight events from raw Discord payloads,
+//! and hand-made [`GatewayEvent`]s for tests that start after serialization
+
+use crate::events::event_type::EventType;
+use crate::events::serialize::GatewayEvent;
+use crate::events::shard_id::ShardId;
+use serde::de::DeserializeSeed;
+use twilight_model::gateway::event::{DispatchEventWithTypeDeserializer, Event};
+
+/// Build a Twilight event from a raw Discord dispatch payload (`d` field)
+pub fn dispatch(event_name: &str, payload: serde_json::Value) -> Event {
+    let json = payload.to_string();
+    let mut deserializer = serde_json::Deserializer::from_str(&json);
+    DispatchEventWithTypeDeserializer::new(event_name)
+        .deserialize(&mut deserializer)
+        .unwrap_or_else(|e| panic!("Invalid {event_name} payload: {e}"))
+        .into()
+}
+
+/// Discord user object
+pub fn user(user_id: u64) -> serde_json::Value {
+    serde_json::json!({
+        "id": user_id.to_string(),
+        "username": "testuser",
+        "global_name": "Test User",
+        "discriminator": "0",
+        "avatar": null
+    })
+}
+
+fn role_ids(roles: &[u64]) -> Vec<String> {
+    roles.iter().map(|r| r.to_string()).collect()
+}
+
+/// GUILD_MEMBER_ADD with the given roles
+pub fn member_add(guild_id: u64, user_id: u64, roles: &[u64]) -> Event {
+    dispatch("GUILD_MEMBER_ADD", serde_json::json!({
+        "guild_id": guild_id.to_string(),
+        "user": user(user_id),
+        "roles": role_ids(roles),
+        "nick": null,
+        "joined_at": "2023-01-01T00:00:00.000000+00:00",
+        "deaf": false,
+        "mute": false,
+        "flags": 0
+    }))
+}
+
+/// GUILD_MEMBER_REMOVE
+pub fn member_remove(guild_id: u64, user_id: u64) -> Event {
+    dispatch("GUILD_MEMBER_REMOVE", serde_json::json!({
+        "guild_id": guild_id.to_string(),
+        "user": user(user_id)
+    }))
+}
+
+/// GUILD_BAN_ADD
+pub fn ban_add(guild_id: u64, user_id: u64) -> Event {
+    dispatch("GUILD_BAN_ADD", serde_json::json!({
+        "guild_id": guild_id.to_string(),
+        "user": user(user_id)
+    }))
+}
+
+/// GUILD_MEMBER_UPDATE with the given roles
+pub fn member_update(guild_id: u64, user_id: u64, roles: &[u64]) -> Event {
+    dispatch("GUILD_MEMBER_UPDATE", serde_json::json!({
+        "guild_id": guild_id.to_string(),
+        "user": user(user_id),
+        "roles": role_ids(roles),
+        "nick": "testnick",
+        "joined_at": "2023-01-01T00:00:00.000000+00:00"
+    }))
+}
+
+/// Discord message object in a guild text channel, with `overrides`
+/// merged over the top-level fields
+pub fn message(overrides: serde_json::Value) -> serde_json::Value {
+    let mut message = serde_json::json!({
+        "id": "888888888888888888",
+        "channel_id": "333333333333333333",
+        "guild_id": "123456789012345678",
+        "author": user(987654321098765432),
+        "content": "check out https://example.com",
+        "timestamp": "2023-01-01T00:00:00.000000+00:00",
+        "edited_timestamp": null,
+        "tts": false,
+        "mention_everyone": false,
+        "mentions": [],
+        "mention_roles": [],
+        "attachments": [],
+        "embeds": [],
+        "pinned": false,
+        "type": 0,
+        "flags": 0
+    });
+    if let (Some(base), Some(overrides)) = (message.as_object_mut(), overrides.as_object()) {
+        base.extend(overrides.clone());
+    }
+    message
+}
+
+/// READY for a fresh session with no guilds
+pub fn ready() -> Event {
+    dispatch("READY", serde_json::json!({
+        "v": 10,
+        "user": {
+            "id": "111111111111111111",
+            "username": "arrakis",
+            "discriminator": "0",
+            "avatar": null,
+            "bot": true,
+            "mfa_enabled": false
+        },
+        "guilds": [],
+        "session_id": "0f2a7b0e3f5c4d6e8a9b1c2d3e4f5a6b",
+        "resume_gateway_url": "wss://gateway.discord.gg",
+        "application": { "id": "111111111111111111", "flags": 0 }
+    }))
+}
+
+/// Available GUILD_CREATE for a guild with the given system channel
+pub fn guild_create(guild_id: u64, system_channel_id: Option<u64>) -> Event {
+    dispatch("GUILD_CREATE", guild(guild_id, system_channel_id))
+}
+
+/// GUILD_UPDATE renaming a guild, otherwise as [`guild`]
+pub fn guild_update(guild_id: u64, name: &str) -> Event {
+    let mut guild = guild(guild_id, None);
+    guild["name"] = name.into();
+    dispatch("GUILD_UPDATE", guild)
+}
+
+/// Discord guild object (as carried by GUILD_CREATE)
+pub fn guild(guild_id: u64, system_channel_id: Option<u64>) -> serde_json::Value {
+    serde_json::json!({
+        "id": guild_id.to_string(),
+        "name": "Test Guild",
+        "icon": null,
+        "splash": null,
+        "discovery_splash": null,
+        "owner_id": "987654321098765432",
+        "afk_channel_id": null,
+        "afk_timeout": 300,
+        "verification_level": 1,
+        "default_message_notifications": 0,
+        "explicit_content_filter": 0,
+        "roles": [],
+        "emojis": [],
+        "features": [],
+        "mfa_level": 0,
+        "application_id": null,
+        "system_channel_id": system_channel_id.map(|id| id.to_string()),
+        "system_channel_flags": 0,
+        "rules_channel_id": null,
+        "vanity_url_code": null,
+        "description": null,
+        "banner": null,
+        "premium_tier": 0,
+        "preferred_locale": "en-US",
+        "public_updates_channel_id": null,
+        "nsfw_level": 0,
+        "premium_progress_bar_enabled": false,
+        "member_count": 1,
+        "joined_at": "2023-01-01T00:00:00.000000+00:00",
+        "large": false,
+        "unavailable": false,
+        "channels": [],
+        "members": [],
+        "voice_states": [],
+        "presences": [],
+        "threads": [],
+        "stage_instances": [],
+        "stickers": [],
+        "guild_scheduled_events": []
+    })
+}
+
+
+/// A [`GatewayEvent`] of `event_type` with every other field defaulted
+pub fn event(event_type: &str) -> GatewayEvent {
+    gateway_event(event_type).build()
+}
+
+/// Start building a [`GatewayEvent`] of `event_type`
+pub fn gateway_event(event_type: &str) -> GatewayEventBuilder {
+    GatewayEventBuilder(GatewayEvent {
+        event_type: EventType::from(event_type),
+        ..Default::default()
+    })
+}
+
+/// Builder for [`GatewayEvent`]s; unset fields keep their defaults
+pub struct GatewayEventBuilder(GatewayEvent);
+
+impl GatewayEventBuilder {
+    pub fn event_id(mut self, event_id: &str) -> Self {
+        self.0.event_id = event_id.to_string();
+        self
+    }
+
+    /// A random UUIDv4 `event_id`
+    pub fn random_id(self) -> Self {
+        self.event_id(&uuid::Uuid::new_v4().to_string())
+    }
+
+    pub fn shard(mut self, shard_id: u64, shard_count: u64) -> Self {
+        self.0.shard_id = ShardId::new(shard_id, shard_count).unwrap();
+        self
+    }
+
+    pub fn timestamp(mut self, timestamp: u64) -> Self {
+        self.0.timestamp = timestamp;
+        self
+    }
+
+    pub fn guild(mut self, guild_id: &str) -> Self {
+        self.0.guild_id = Some(guild_id.to_string());
+        self
+    }
+
+    pub fn channel(mut self, channel_id: &str) -> Self {
+        self.0.channel_id = Some(channel_id.to_string());
+        self
+    }
+
+    pub fn user(mut self, user_id: &str) -> Self {
+        self.0.user_id = Some(user_id.to_string());
+        self
+    }
+
+    pub fn sequence(mut self, sequence: u64) -> Self {
+        self.0.sequence = Some(sequence);
+        self
+    }
+
+    pub fn data(mut self, data: serde_json::Value) -> Self {
+        self.0.data = data;
+        self
+    }
+
+    pub fn build(self) -> GatewayEvent {
+        self.0
+    }
+}
@@ -0,0 +1,121 @@
+//! Unit of the `timestamp` field
+//!
+//! `timestamp` is UTC Unix epoch milliseconds by default. Consumers that
+//! standardize on microseconds (some time-series databases) can switch to
+//! [`TimestampUnit::Micros`], which scales every emitted `timestamp` by 1000.
+//! Because that changes the value's magnitude, micros events also carry
+//! `timestamp_unit: "micros"`; an event without it is in milliseconds.
+//!
+//! Discord's snowflakes and the gateway's clock are millisecond-resolution,
+//! so the unit changes the scale, not the precision. The conversion is the
+//! last step before an event is emitted: pipeline state, anomaly windows and
+//! timeout scheduling stay in milliseconds. Consumer code comparing
+//! `timestamp` with milliseconds should read it through
+//! [`timestamp_millis`], as `event_age_ms` and `ShardMerger` do.
+
+use crate::error::GatewayError;
+use crate::events::serialize::GatewayEvent;
+
+/// Unit of the `timestamp` field, both UTC Unix epoch
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimestampUnit {
+    #[default]
+    Millis,
+    Micros,
+}
+
+impl TimestampUnit {
+    /// Parse `millis` (or `ms`) and `micros` (or `us`)
+    pub fn parse(value: &str) -> Result<Self, GatewayError> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "millis" | "ms" => Ok(Self::Millis),
+            "micros" | "us" => Ok(Self::Micros),
+            other => Err(GatewayError::Config(format!(
+                "timestamp unit '{other}' must be millis or micros"
+            ))),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Millis => "millis",
+            Self::Micros => "micros",
+        }
+    }
+
+    /// The unit an event's `timestamp` is in, from its `timestamp_unit`
+    /// flag; millis when unflagged or unrecognized
+    pub fn of(event: &GatewayEvent) -> Self {
+        match event.timestamp_unit.as_deref() {
+            Some("micros") => Self::Micros,
+            _ => Self::Millis,
+        }
+    }
+
+    /// `millis` in this unit
+    pub fn from_millis(self, millis: u64) -> u64 {
+        match self {
+            Self::Millis => millis,
+            Self::Micros => millis.saturating_mul(1000),
+        }
+    }
+
+    /// `value` in this unit, as milliseconds
+    pub fn to_millis(self, value: u64) -> u64 {
+        match self {
+            Self::Millis => value,
+            Self::Micros => value / 1000,
+        }
+    }
+
+    /// Convert an emitted event's millisecond `timestamp` to this unit and
+    /// flag the unit when it isn't the default
+    pub(crate) fn apply(self, payload: &mut GatewayEvent) {
+        if self == Self::Millis {
+            return;
+        }
+        payload.timestamp = self.from_millis(payload.timestamp);
+        payload.timestamp_unit = Some(self.as_str().to_string());
+    }
+}
+
+/// An event's `timestamp` in milliseconds, whichever unit it was emitted in
+pub fn timestamp_millis(event: &GatewayEvent) -> u64 {
+    TimestampUnit::of(event).to_millis(event.timestamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::pipeline::EventPipeline;
+    use crate::events::serialize::{serialize_event, SerializeConfig};
+    use crate::events::shard_id::ShardId;
+    use crate::events::test_support::{dispatch, message};
+
+    #[test]
+    fn micros_timestamp_is_1000x_millis_for_the_same_instant() {
+        // message.create is stamped from its snowflake, so both runs see
+        // the same instant
+        let event = dispatch("MESSAGE_CREATE", message(serde_json::json!({})));
+        let micros = SerializeConfig { timestamp_unit: TimestampUnit::Micros, ..Default::default() };
+
+        let ms = serialize_event(&event, ShardId::ZERO, &SerializeConfig::default()).unwrap();
+        let us = serialize_event(&event, ShardId::ZERO, &micros).unwrap();
+        assert_eq!(us.timestamp, ms.timestamp * 1000);
+        assert_eq!(us.timestamp_unit.as_deref(), Some("micros"));
+        assert!(serde_json::to_value(&ms).unwrap().get("timestamp_unit").is_none(), "millis is unflagged");
+
+        let piped = EventPipeline::new(ShardId::ZERO, micros).process(&event).unwrap();
+        assert_eq!(piped[0].timestamp, ms.timestamp * 1000, "converted once through the pipeline too");
+
+        assert_eq!(timestamp_millis(&us), ms.timestamp);
+        assert_eq!(timestamp_millis(&ms), ms.timestamp);
+    }
+
+    #[test]
+    fn parses_unit_names() {
+        assert_eq!(TimestampUnit::parse("micros").unwrap(), TimestampUnit::Micros);
+        assert_eq!(TimestampUnit::parse(" MS ").unwrap(), TimestampUnit::Millis);
+        assert!(TimestampUnit::parse("nanos").is_err());
+    }
+}
@@ -0,0 +1,16 @@
+//! Arrakis Gateway library
+//!
+//! Exposes the gateway's modules so the binary, integration tests, and
+//! benchmarks share a single crate graph. main.rs stays the process
+//! boundary (the only place anyhow is allowed).
+
+pub mod codec;
+pub mod config;
+pub mod consumer;
+pub mod error;
+pub mod events;
+pub mod health;
+pub mod metrics;
+pub mod nats;
+pub mod shard;
+pub mod sink;
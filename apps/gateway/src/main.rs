@@ -12,29 +12,31 @@ use anyhow::Result;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::signal;
-use tracing::{error, info};
-
-mod config;
-pub mod error;
-mod events;
-mod health;
-mod metrics;
-mod nats;
-mod shard;
-
-use config::GatewayConfig;
-use health::AppState;
-use metrics::GatewayMetrics;
-use nats::NatsPublisher;
-use shard::ShardPool;
+use tracing::{error, info, warn};
+
+use arrakis_gateway::config::GatewayConfig;
+use arrakis_gateway::events;
+use arrakis_gateway::health::{self, AppState};
+use arrakis_gateway::metrics::GatewayMetrics;
+use arrakis_gateway::nats::{backpressure, Backpressure, FailedPublishBuffer, NatsPublisher};
+use arrakis_gateway::shard::{ShardPool, StateStore};
+use arrakis_gateway::sink::StdoutNdjsonSink;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Load configuration first to get log level
     let gateway_config = GatewayConfig::from_env()?;
 
-    // Initialize tracing with configured log level
+    // Initialize tracing with configured log level. With STDOUT_EVENTS the
+    // event stream owns stdout, so logs go to stderr to keep it pipeable.
+    let log_writer = if gateway_config.stdout_events {
+        BoxMakeWriter::new(std::io::stderr)
+    } else {
+        BoxMakeWriter::new(std::io::stdout)
+    };
     tracing_subscriber::fmt()
+        .with_writer(log_writer)
         .with_env_filter(
             tracing_subscriber::EnvFilter::from_default_env()
                 .add_directive(format!("arrakis_gateway={}", gateway_config.log_level).parse()?)
@@ -57,10 +59,41 @@ async fn main() -> Result<()> {
 
     // Connect to NATS if configured
     let nats = if let Some(ref url) = gateway_config.nats_url {
-        match NatsPublisher::connect(url).await {
+        let buffer = match gateway_config.publish_buffer_path {
+            Some(ref path) => {
+                let buffer = FailedPublishBuffer::open(path, gateway_config.publish_buffer_capacity)?;
+                info!(
+                    path = %path.display(),
+                    capacity = gateway_config.publish_buffer_capacity,
+                    buffered = buffer.len(),
+                    "Failed-publish buffer enabled"
+                );
+                Some(buffer)
+            }
+            None => None,
+        };
+
+        let backpressure = gateway_config
+            .backpressure
+            .as_ref()
+            .map(|config| Arc::new(Backpressure::new(config.threshold)));
+
+        match NatsPublisher::connect(
+            url,
+            buffer,
+            gateway_config.payload_codec,
+            backpressure.clone(),
+            gateway_config.publish_timeout,
+            gateway_config.split_data_threshold,
+            gateway_config.priority_subjects,
+        ).await {
             Ok(publisher) => {
                 info!(url, "Connected to NATS");
                 metrics.set_nats_connected(true);
+                if let (Some(config), Some(backpressure)) = (gateway_config.backpressure.clone(), backpressure) {
+                    info!(consumer = %config.consumer, threshold = config.threshold, "Consumer backpressure enabled");
+                    tokio::spawn(backpressure::watch_consumer(publisher.jetstream().clone(), config, backpressure));
+                }
                 Some(publisher)
             }
             Err(e) => {
@@ -74,8 +107,13 @@ async fn main() -> Result<()> {
         None
     };
 
+    // Run each serializer arm once so the first real events don't pay
+    // one-time setup costs
+    let warmed = events::prewarm(&gateway_config.serialize)?;
+    info!(samples = warmed, "Serializer prewarmed");
+
     // Get Discord intents
-    let intents = GatewayConfig::intents();
+    let intents = gateway_config.enabled_intents();
     info!(?intents, "Using Discord intents");
 
     // Create shard pool
@@ -86,9 +124,25 @@ async fn main() -> Result<()> {
         intents,
         nats.clone(),
         Arc::clone(&metrics),
+        gateway_config.serialize.clone(),
     )
     .await?;
 
+    let pool = if gateway_config.stdout_events {
+        info!("Writing events to stdout as NDJSON");
+        pool.with_stdout_sink(Arc::new(StdoutNdjsonSink::stdout()))
+    } else {
+        pool
+    };
+
+    let pool = match gateway_config.state_dir {
+        Some(ref dir) => {
+            info!(dir = %dir.display(), "Persisting shard serializer state");
+            pool.with_state_store(Arc::new(StateStore::open(dir, gateway_config.state_snapshot_interval)?))
+        }
+        None => pool,
+    };
+
     let pool_state = pool.state();
     info!(
         pool_id = gateway_config.pool_id,
@@ -134,6 +188,12 @@ async fn main() -> Result<()> {
     info!("Shutting down gateway...");
 
     if let Some(ref nats) = nats {
+        let buffered = nats.buffered_events();
+        match nats.flush().await {
+            Ok(()) if buffered > 0 => info!(flushed = buffered, "Flushed buffered events"),
+            Ok(()) => {}
+            Err(e) => warn!(error = %e, "Shutdown flush incomplete; remaining events kept for the next start"),
+        }
         nats.close().await;
     }
 
@@ -52,6 +52,26 @@ impl GatewayMetrics {
             Unit::Count,
             "Total gateway errors"
         );
+        describe_counter!(
+            "gateway_events_shed_total",
+            Unit::Count,
+            "Low-priority events dropped under consumer backpressure"
+        );
+        describe_counter!(
+            "gateway_events_deduplicated_total",
+            Unit::Count,
+            "Events dropped as repeats of the previous event within the dedup window"
+        );
+        describe_counter!(
+            "gateway_events_dead_lettered_total",
+            Unit::Count,
+            "Events moved to the failed-publish buffer, by reason"
+        );
+        describe_counter!(
+            "gateway_serialization_failures_total",
+            Unit::Count,
+            "Events whose data failed to serialize, forwarded with a _serialization_error marker"
+        );
 
         // Latency histogram
         describe_histogram!(
@@ -59,6 +79,11 @@ impl GatewayMetrics {
             Unit::Seconds,
             "Time to route event to NATS"
         );
+        describe_histogram!(
+            "gateway_serialize_duration_seconds",
+            Unit::Seconds,
+            "Time to serialize a forwarded event"
+        );
 
         // Gauges
         describe_gauge!(
@@ -76,6 +101,16 @@ impl GatewayMetrics {
             Unit::Count,
             "NATS connection status (1=connected, 0=disconnected)"
         );
+        describe_gauge!(
+            "gateway_publish_buffered_events",
+            Unit::Count,
+            "Events waiting in the failed-publish buffer for replay"
+        );
+        describe_gauge!(
+            "gateway_consumer_pending",
+            Unit::Count,
+            "Pending count of the consumer watched for backpressure"
+        );
     }
 
     /// Record an event received
@@ -174,6 +209,11 @@ impl GatewayMetrics {
         gauge!("gateway_nats_connected").set(if connected { 1.0 } else { 0.0 });
     }
 
+    /// Set number of events waiting in the failed-publish buffer
+    pub fn set_publish_buffered(&self, count: usize) {
+        gauge!("gateway_publish_buffered_events").set(count as f64);
+    }
+
     /// Render metrics in Prometheus format
     pub fn render(&self) -> String {
         self.handle.render()
@@ -0,0 +1,128 @@
+//! Consumer backpressure
+//!
+//! When workers fall behind, publishing at full rate only grows the
+//! JetStream backlog. The gateway polls the pending count of a durable
+//! consumer into a shared [`Backpressure`] signal; while it is over the
+//! threshold the publisher sheds low-priority events (high-volume state
+//! churn that a later event supersedes) and keeps everything else.
+
+use crate::events::priority::{event_priority, EventPriority};
+use crate::events::serialize::GatewayEvent;
+use async_nats::jetstream::Context as JsContext;
+use metrics::gauge;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+/// Default pending count above which low-priority events are shed
+pub const DEFAULT_THRESHOLD: u64 = 10_000;
+
+/// Default interval between consumer pending-count polls
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Which consumer to watch and when to shed
+#[derive(Debug, Clone)]
+pub struct BackpressureConfig {
+    /// Stream the consumer reads from
+    pub stream: String,
+    /// Durable consumer whose pending count is the lag signal
+    pub consumer: String,
+    /// Pending count above which low-priority events are shed
+    pub threshold: u64,
+    /// How often to poll the consumer
+    pub poll_interval: Duration,
+}
+
+/// Consumer lag shared between the watcher and the publisher
+#[derive(Debug)]
+pub struct Backpressure {
+    pending: AtomicU64,
+    threshold: u64,
+}
+
+impl Backpressure {
+    pub fn new(threshold: u64) -> Self {
+        Self {
+            pending: AtomicU64::new(0),
+            threshold,
+        }
+    }
+
+    /// Record the consumer's latest pending count
+    pub fn set_pending(&self, pending: u64) {
+        self.pending.store(pending, Ordering::Relaxed);
+        gauge!("gateway_consumer_pending").set(pending as f64);
+    }
+
+    pub fn pending(&self) -> u64 {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    /// Returns true while the consumer is over the threshold
+    pub fn under_pressure(&self) -> bool {
+        self.pending() > self.threshold
+    }
+
+    /// Returns true if `event` should be dropped rather than published
+    pub fn should_shed(&self, event: &GatewayEvent) -> bool {
+        self.under_pressure() && is_low_priority(&event.event_type)
+    }
+}
+
+/// Returns true for event types that may be shed under backpressure: the
+/// p3 tier (see [`event_priority`])
+///
+/// Each is superseded by the next event of the same type for the same
+/// user or message, or is stale within seconds (typing), so dropping some
+/// loses detail but not state.
+pub fn is_low_priority(event_type: &str) -> bool {
+    event_priority(event_type) == EventPriority::P3
+}
+
+/// Poll the consumer's pending count into `backpressure` until dropped
+///
+/// Poll failures are logged and keep the last known count, so a flaky
+/// JetStream API doesn't flip shedding on and off.
+pub async fn watch_consumer(jetstream: JsContext, config: BackpressureConfig, backpressure: Arc<Backpressure>) {
+    let mut interval = tokio::time::interval(config.poll_interval);
+    loop {
+        interval.tick().await;
+        let info = match jetstream.get_stream_no_info(&config.stream).await {
+            Ok(stream) => stream.consumer_info(&config.consumer).await.map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        match info {
+            Ok(info) => backpressure.set_pending(info.num_pending),
+            Err(error) => warn!(
+                stream = %config.stream,
+                consumer = %config.consumer,
+                error,
+                "Failed to poll consumer pending count"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::test_support::event;
+
+    #[test]
+    fn high_pending_sheds_low_priority_events() {
+        let backpressure = Backpressure::new(1_000);
+        assert!(!backpressure.should_shed(&event("presence.update")));
+
+        backpressure.set_pending(50_000);
+        assert!(backpressure.should_shed(&event("presence.update")));
+        assert!(backpressure.should_shed(&event("voice.state.update")));
+        assert!(backpressure.should_shed(&event("typing.start")));
+        assert!(!backpressure.should_shed(&event("member.join")));
+        assert!(!backpressure.should_shed(&event("interaction.create")));
+
+        // Caught up again
+        backpressure.set_pending(1_000);
+        assert!(!backpressure.should_shed(&event("presence.update")));
+    }
+}
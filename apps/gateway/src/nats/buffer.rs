@@ -0,0 +1,385 @@
+//! Durable buffer for events that failed to publish
+//!
+//! When a NATS publish fails (connection drop, broker restart), the event
+//! would otherwise be lost and only logged. The buffer captures it in a
+//! bounded, file-backed queue (one JSON event per line) so it survives a
+//! process restart, and replays it once the broker is reachable again.
+
+use crate::error::GatewayError;
+use crate::events::serialize::GatewayEvent;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{debug, warn};
+
+/// Default maximum number of buffered events
+pub const DEFAULT_CAPACITY: usize = 10_000;
+
+/// Bounded, file-backed queue of events awaiting republish
+///
+/// When full, the oldest event is evicted to make room for the newest.
+/// Replays are serialized: a shard that finds another replay in progress
+/// skips rather than racing it, which keeps buffered events in order.
+pub struct FailedPublishBuffer {
+    path: PathBuf,
+    capacity: usize,
+    queue: Mutex<VecDeque<GatewayEvent>>,
+    replay_lock: tokio::sync::Mutex<()>,
+}
+
+impl FailedPublishBuffer {
+    /// Open (or create) a buffer backed by `path`
+    ///
+    /// Events left over from a previous run are loaded so they are retried
+    /// on the next successful connection. Unparseable lines are skipped.
+    pub fn open(path: impl Into<PathBuf>, capacity: usize) -> Result<Self, GatewayError> {
+        let path = path.into();
+        let mut queue = VecDeque::new();
+
+        if path.exists() {
+            let file = std::fs::File::open(&path).map_err(|e| buffer_error(&path, e))?;
+            for line in BufReader::new(file).lines() {
+                let line = line.map_err(|e| buffer_error(&path, e))?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<GatewayEvent>(&line) {
+                    Ok(event) => queue.push_back(event),
+                    Err(e) => warn!(path = %path.display(), error = %e, "Skipping corrupt buffered event"),
+                }
+            }
+            while queue.len() > capacity {
+                queue.pop_front();
+            }
+        }
+
+        let buffer = Self {
+            path,
+            capacity,
+            queue: Mutex::new(queue),
+            replay_lock: tokio::sync::Mutex::new(()),
+        };
+        buffer.persist(&buffer.queue.lock().unwrap())?;
+        Ok(buffer)
+    }
+
+    /// Number of events currently buffered
+    pub fn len(&self) -> usize {
+        self.queue.lock().unwrap().len()
+    }
+
+    /// Returns true if no events are buffered
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Buffer an event whose publish failed
+    pub fn push(&self, event: GatewayEvent) -> Result<(), GatewayError> {
+        let mut queue = self.queue.lock().unwrap();
+        queue.push_back(event);
+        if queue.len() > self.capacity {
+            let evicted = queue.pop_front();
+            warn!(
+                capacity = self.capacity,
+                event_id = evicted.as_ref().map(|e| e.event_id.as_str()),
+                "Publish buffer full, evicting oldest event"
+            );
+            self.persist(&queue)
+        } else {
+            self.append(queue.back().expect("just pushed"))
+        }
+    }
+
+    /// Replay buffered events through `publish`, oldest first
+    ///
+    /// Stops at the first failure, leaving that event and everything after
+    /// it buffered for the next attempt. Returns the number of events
+    /// successfully replayed.
+    pub async fn replay<F, Fut>(&self, publish: F) -> usize
+    where
+        F: FnMut(GatewayEvent) -> Fut,
+        Fut: Future<Output = Result<(), GatewayError>>,
+    {
+        let Ok(_guard) = self.replay_lock.try_lock() else {
+            return 0;
+        };
+
+        let (replayed, failure) = self.drain(publish).await;
+        if let Some(e) = failure {
+            debug!(error = %e, remaining = self.len(), "Buffered event replay failed");
+        }
+        replayed
+    }
+
+    /// Publish every buffered event through `publish`, oldest first, for
+    /// shutdown
+    ///
+    /// Unlike [`replay`](Self::replay), waits out a replay in progress
+    /// rather than skipping. Stops at the first failure with
+    /// `FlushIncomplete`; the unpublished events stay in the backing file
+    /// and are retried on the next start.
+    pub async fn flush<F, Fut>(&self, publish: F) -> Result<(), GatewayError>
+    where
+        F: FnMut(GatewayEvent) -> Fut,
+        Fut: Future<Output = Result<(), GatewayError>>,
+    {
+        let _guard = self.replay_lock.lock().await;
+
+        match self.drain(publish).await {
+            (_, None) => Ok(()),
+            (_, Some(e)) => Err(GatewayError::FlushIncomplete {
+                remaining: self.len(),
+                source: Box::new(e),
+            }),
+        }
+    }
+
+    /// Publish from the front of the queue until it's empty or `publish`
+    /// fails; the caller holds `replay_lock`
+    ///
+    /// Returns the number published and the failure that stopped it.
+    async fn drain<F, Fut>(&self, mut publish: F) -> (usize, Option<GatewayError>)
+    where
+        F: FnMut(GatewayEvent) -> Fut,
+        Fut: Future<Output = Result<(), GatewayError>>,
+    {
+        let mut published = 0;
+        let mut failure = None;
+        loop {
+            let Some(event) = self.queue.lock().unwrap().front().cloned() else {
+                break;
+            };
+            let event_id = event.event_id.clone();
+            if let Err(e) = publish(event).await {
+                failure = Some(e);
+                break;
+            }
+            // A push into a full buffer may have evicted it mid-publish;
+            // then the front is an event that hasn't gone out yet
+            let mut queue = self.queue.lock().unwrap();
+            if queue.front().is_some_and(|front| front.event_id == event_id) {
+                queue.pop_front();
+            }
+            published += 1;
+        }
+
+        if published > 0 {
+            if let Err(e) = self.persist(&self.queue.lock().unwrap()) {
+                warn!(error = %e, "Failed to persist publish buffer after replay");
+            }
+        }
+        (published, failure)
+    }
+
+    /// Append a single event to the backing file
+    fn append(&self, event: &GatewayEvent) -> Result<(), GatewayError> {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| buffer_error(&self.path, e))?;
+        write_line(&mut file, event).map_err(|e| buffer_error(&self.path, e))
+    }
+
+    /// Rewrite the backing file to match the in-memory queue
+    fn persist(&self, queue: &VecDeque<GatewayEvent>) -> Result<(), GatewayError> {
+        let mut file = std::fs::File::create(&self.path).map_err(|e| buffer_error(&self.path, e))?;
+        for event in queue {
+            write_line(&mut file, event).map_err(|e| buffer_error(&self.path, e))?;
+        }
+        Ok(())
+    }
+}
+
+fn write_line(file: &mut std::fs::File, event: &GatewayEvent) -> std::io::Result<()> {
+    let mut line = serde_json::to_vec(event)?;
+    line.push(b'\n');
+    file.write_all(&line)
+}
+
+fn buffer_error(path: &Path, source: std::io::Error) -> GatewayError {
+    GatewayError::PublishBuffer {
+        path: path.display().to_string(),
+        source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::test_support::gateway_event;
+
+    fn test_event(event_id: &str) -> GatewayEvent {
+        gateway_event("member.join")
+            .event_id(event_id)
+            .timestamp(1700000000000)
+            .guild("123456789012345678")
+            .user("987654321098765432")
+            .build()
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "arrakis-publish-buffer-{}-{}.ndjson",
+            name,
+            uuid::Uuid::new_v4()
+        ));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn broker_down() -> GatewayError {
+        GatewayError::NatsPublishFailed {
+            subject: "events.member.join".to_string(),
+            source: Box::new(std::io::Error::other("connection reset")),
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_publish_is_retried_after_recovery() {
+        let path = temp_path("retry");
+        let buffer = FailedPublishBuffer::open(&path, DEFAULT_CAPACITY).unwrap();
+
+        // Publish fails: the event is captured instead of lost
+        buffer.push(test_event("00000000-0000-4000-8000-000000000001")).unwrap();
+        assert_eq!(buffer.len(), 1);
+
+        // Broker still down: replay fails and the event stays buffered
+        let replayed = buffer.replay(|_| async { Err(broker_down()) }).await;
+        assert_eq!(replayed, 0);
+        assert_eq!(buffer.len(), 1);
+
+        // Broker back: the buffered event is republished
+        let mut published = Vec::new();
+        let replayed = buffer
+            .replay(|event| {
+                published.push(event.event_id);
+                async { Ok(()) }
+            })
+            .await;
+        assert_eq!(replayed, 1);
+        assert!(buffer.is_empty());
+        assert_eq!(published, vec!["00000000-0000-4000-8000-000000000001"]);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn flush_publishes_every_buffered_event() {
+        let path = temp_path("flush");
+        let buffer = FailedPublishBuffer::open(&path, DEFAULT_CAPACITY).unwrap();
+        for n in 1..=3 {
+            buffer.push(test_event(&format!("00000000-0000-4000-8000-00000000000{n}"))).unwrap();
+        }
+
+        let mut published = Vec::new();
+        buffer
+            .flush(|event| {
+                published.push(event.event_id);
+                async { Ok(()) }
+            })
+            .await
+            .unwrap();
+        assert!(buffer.is_empty());
+        assert_eq!(
+            published,
+            vec![
+                "00000000-0000-4000-8000-000000000001",
+                "00000000-0000-4000-8000-000000000002",
+                "00000000-0000-4000-8000-000000000003",
+            ]
+        );
+        assert!(FailedPublishBuffer::open(&path, DEFAULT_CAPACITY).unwrap().is_empty(), "file emptied too");
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn incomplete_flush_keeps_the_rest_for_the_next_start() {
+        let path = temp_path("flush-incomplete");
+        let buffer = FailedPublishBuffer::open(&path, DEFAULT_CAPACITY).unwrap();
+        buffer.push(test_event("00000000-0000-4000-8000-000000000001")).unwrap();
+        buffer.push(test_event("00000000-0000-4000-8000-000000000002")).unwrap();
+
+        let mut attempts = 0;
+        let result = buffer
+            .flush(|_| {
+                attempts += 1;
+                let result = if attempts == 1 { Ok(()) } else { Err(broker_down()) };
+                async move { result }
+            })
+            .await;
+        assert!(matches!(result, Err(GatewayError::FlushIncomplete { remaining: 1, .. })));
+
+        let reopened = FailedPublishBuffer::open(&path, DEFAULT_CAPACITY).unwrap();
+        assert_eq!(reopened.len(), 1);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn eviction_during_replay_does_not_drop_the_next_event() {
+        let path = temp_path("evict-during-replay");
+        let buffer = FailedPublishBuffer::open(&path, 2).unwrap();
+        buffer.push(test_event("00000000-0000-4000-8000-000000000001")).unwrap();
+        buffer.push(test_event("00000000-0000-4000-8000-000000000002")).unwrap();
+
+        // Another shard's failed publish lands while the first event is in
+        // flight, evicting it from the full buffer
+        let mut published = Vec::new();
+        buffer
+            .flush(|event| {
+                if published.is_empty() {
+                    buffer.push(test_event("00000000-0000-4000-8000-000000000003")).unwrap();
+                }
+                published.push(event.event_id);
+                async { Ok(()) }
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            published,
+            vec![
+                "00000000-0000-4000-8000-000000000001",
+                "00000000-0000-4000-8000-000000000002",
+                "00000000-0000-4000-8000-000000000003",
+            ]
+        );
+        assert!(buffer.is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn buffered_events_survive_reopen() {
+        let path = temp_path("reopen");
+        {
+            let buffer = FailedPublishBuffer::open(&path, DEFAULT_CAPACITY).unwrap();
+            buffer.push(test_event("00000000-0000-4000-8000-000000000001")).unwrap();
+            buffer.push(test_event("00000000-0000-4000-8000-000000000002")).unwrap();
+        }
+
+        let reopened = FailedPublishBuffer::open(&path, DEFAULT_CAPACITY).unwrap();
+        assert_eq!(reopened.len(), 2);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn full_buffer_evicts_oldest() {
+        let path = temp_path("bounded");
+        let buffer = FailedPublishBuffer::open(&path, 2).unwrap();
+        buffer.push(test_event("00000000-0000-4000-8000-000000000001")).unwrap();
+        buffer.push(test_event("00000000-0000-4000-8000-000000000002")).unwrap();
+        buffer.push(test_event("00000000-0000-4000-8000-000000000003")).unwrap();
+
+        assert_eq!(buffer.len(), 2);
+        let queue = buffer.queue.lock().unwrap();
+        assert_eq!(queue.front().unwrap().event_id, "00000000-0000-4000-8000-000000000002");
+        drop(queue);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -3,6 +3,12 @@
 //! Sprint S-4: Twilight Gateway Core
 //! Publishes gateway events to NATS streams per SDD §7.1
 
+pub mod backpressure;
+mod buffer;
 mod publisher;
+pub mod split;
 
-pub use publisher::NatsPublisher;
+pub use backpressure::{Backpressure, BackpressureConfig};
+pub use buffer::{FailedPublishBuffer, DEFAULT_CAPACITY as DEFAULT_BUFFER_CAPACITY};
+pub use publisher::{ensure_streams, priority_subject, streams, subjects, NatsPublisher};
+pub use split::{detach_data, payload_subject, DetachedData};
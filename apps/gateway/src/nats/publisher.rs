@@ -2,12 +2,22 @@
 //!
 //! Sprint S-4: Publishes serialized events to NATS JetStream
 
+use crate::codec::{Codec, Identity, CODEC_HEADER};
 use crate::error::GatewayError;
+use crate::events::priority::{event_category, event_priority, EventPriority};
 use crate::events::serialize::GatewayEvent;
+use crate::nats::backpressure::Backpressure;
+use crate::nats::buffer::FailedPublishBuffer;
+use crate::nats::split::detach_data;
+use async_nats::connection::State;
 use async_nats::jetstream::{self, Context as JsContext};
 use async_nats::Client;
+use metrics::counter;
+use std::borrow::Cow;
+use std::future::Future;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{debug, error, info, warn};
 
 /// Stream names per SDD §7.1.1
@@ -18,6 +28,10 @@ pub mod streams {
     pub const EVENTS: &str = "EVENTS";
     /// Eligibility stream for token checks
     pub const ELIGIBILITY: &str = "ELIGIBILITY";
+    /// Payloads stream for event data split off oversized events
+    pub const PAYLOADS: &str = "PAYLOADS";
+    /// Prioritized stream for priority-encoded subjects
+    pub const PRIORITIZED: &str = "PRIORITIZED";
 }
 
 /// Subject prefixes for routing
@@ -28,8 +42,31 @@ pub mod subjects {
     pub const GUILD_EVENTS: &str = "events.guild";
     /// Member events: events.member.{event_type}
     pub const MEMBER_EVENTS: &str = "events.member";
+    /// Message events: events.message.{event_type}
+    pub const MESSAGE_EVENTS: &str = "events.message";
+    /// Usage events: inference.usage.{event_type} (published by loa-finn, not the gateway)
+    pub const USAGE: &str = "inference.usage";
     /// Interactions: commands.interaction
     pub const INTERACTION: &str = "commands.interaction";
+    /// Detached event data: discord.payload.{event_id}
+    pub const PAYLOADS: &str = "discord.payload";
+    /// Priority-encoded events: discord.{priority}.{category}.{event_type}
+    pub const PRIORITIZED: &str = "discord";
+}
+
+/// Priority tiers of the PRIORITIZED stream, in subject form
+const PRIORITY_TIERS: [EventPriority; 4] = [EventPriority::P0, EventPriority::P1, EventPriority::P2, EventPriority::P3];
+
+/// Subject of `event_type` with its priority and category encoded
+/// (`discord.p0.command.interaction.create`), for subscribing per tier
+/// with `discord.p0.>`
+pub fn priority_subject(event_type: &str) -> String {
+    format!(
+        "{}.{}.{}.{event_type}",
+        subjects::PRIORITIZED,
+        event_priority(event_type),
+        event_category(event_type)
+    )
 }
 
 /// NATS publisher for gateway events
@@ -39,13 +76,43 @@ pub struct NatsPublisher {
     connected: AtomicBool,
     messages_published: AtomicU64,
     publish_failures: AtomicU64,
+    /// Durable buffer for failed publishes (None = failures are only logged)
+    buffer: Option<FailedPublishBuffer>,
+    /// Compression for payloads; non-identity codecs stamp CODEC_HEADER
+    codec: &'static dyn Codec,
+    /// Consumer lag signal (None = never shed)
+    backpressure: Option<Arc<Backpressure>>,
+    /// Longest wait for a publish ack (None = wait indefinitely)
+    publish_timeout: Option<Duration>,
+    /// `data` larger than this many bytes is published separately
+    /// (None = always inline)
+    split_data_threshold: Option<usize>,
+    /// Publish on [`priority_subject`]s instead of the flat ones
+    priority_subjects: bool,
 }
 
 impl NatsPublisher {
     /// Connect to NATS server.
     /// SEC-4.4: When the URL uses `tls://`, configures TLS with the CA
     /// certificate from `NATS_TLS_CA` for self-signed cert verification.
-    pub async fn connect(servers: &str) -> Result<Arc<Self>, GatewayError> {
+    ///
+    /// When `buffer` is set, events whose publish fails are captured there
+    /// and replayed once the connection is re-established. Payloads are
+    /// compressed with `codec`. While `backpressure` reports the watched
+    /// consumer over its threshold, low-priority events are shed. A publish
+    /// not acked within `publish_timeout` fails with `PublishTimeout`. Events
+    /// whose `data` exceeds `split_data_threshold` bytes have it published
+    /// on its own subject (see [`split`](super::split)). With
+    /// `priority_subjects`, events go to [`priority_subject`]s.
+    pub async fn connect(
+        servers: &str,
+        buffer: Option<FailedPublishBuffer>,
+        codec: &'static dyn Codec,
+        backpressure: Option<Arc<Backpressure>>,
+        publish_timeout: Option<Duration>,
+        split_data_threshold: Option<usize>,
+        priority_subjects: bool,
+    ) -> Result<Arc<Self>, GatewayError> {
         info!(servers, "Connecting to NATS");
 
         let needs_tls = servers.contains("tls://");
@@ -84,6 +151,12 @@ impl NatsPublisher {
             connected: AtomicBool::new(true),
             messages_published: AtomicU64::new(0),
             publish_failures: AtomicU64::new(0),
+            buffer,
+            codec,
+            backpressure,
+            publish_timeout,
+            split_data_threshold,
+            priority_subjects,
         }))
     }
 
@@ -102,12 +175,77 @@ impl NatsPublisher {
         self.publish_failures.load(Ordering::Relaxed)
     }
 
+    /// JetStream context, for watchers sharing the connection
+    pub fn jetstream(&self) -> &JsContext {
+        &self.jetstream
+    }
+
+    /// Get number of events waiting in the failed-publish buffer
+    pub fn buffered_events(&self) -> usize {
+        self.buffer.as_ref().map_or(0, |b| b.len())
+    }
+
     /// Publish a gateway event to the appropriate stream
+    ///
+    /// Buffered events from earlier failures are replayed first (once the
+    /// client reports a live connection) so ordering is preserved as far as
+    /// possible. If this publish fails or times out, the event is
+    /// dead-lettered into the buffer. Low-priority events shed under
+    /// backpressure are dropped and count as published.
     pub async fn publish_event(&self, event: &GatewayEvent) -> Result<(), GatewayError> {
-        let subject = self.route_event(event);
-        let payload = serde_json::to_vec(event).map_err(|e| GatewayError::SerializationFailed {
-            event_type: event.event_type.clone(),
-            shard_id: event.shard_id,
+        if self.backpressure.as_ref().is_some_and(|b| b.should_shed(event)) {
+            counter!("gateway_events_shed_total", "event_type" => event.event_type.to_string()).increment(1);
+            return Ok(());
+        }
+
+        if let Some(ref buffer) = self.buffer {
+            if !buffer.is_empty() && self.client.connection_state() == State::Connected {
+                let replayed = buffer
+                    .replay(|buffered| async move { self.send_event_bounded(&buffered).await })
+                    .await;
+                if replayed > 0 {
+                    info!(replayed, remaining = buffer.len(), "Replayed buffered events");
+                }
+            }
+        }
+
+        let result = self.send_event_bounded(event).await;
+        dead_letter(&result, event, self.buffer.as_ref());
+        result
+    }
+
+    /// [`send_event`](Self::send_event) under the configured publish timeout
+    async fn send_event_bounded(&self, event: &GatewayEvent) -> Result<(), GatewayError> {
+        let result = with_timeout(self.send_event(event), self.publish_timeout, || self.route_event(event)).await;
+        if let Err(GatewayError::PublishTimeout { subject, timeout_ms }) = &result {
+            self.publish_failures.fetch_add(1, Ordering::Relaxed);
+            warn!(subject, timeout_ms, event_id = %event.event_id, "Publish timed out");
+        }
+        result
+    }
+
+    /// Serialize and publish a single event, awaiting the JetStream ack
+    ///
+    /// Data split off an oversized event is published first, so it is in
+    /// the PAYLOADS stream by the time a consumer sees the `data_ref`.
+    async fn send_event(&self, event: &GatewayEvent) -> Result<(), GatewayError> {
+        let split = match self.split_data_threshold {
+            Some(threshold) => detach_data(event, threshold)?,
+            None => None,
+        };
+        let event = match split {
+            Some((header, detached)) => {
+                debug!(event_id = %event.event_id, bytes = detached.payload.len(), "Publishing detached data");
+                self.publish_bytes(detached.subject, &detached.payload).await?;
+                Cow::Owned(header)
+            }
+            None => Cow::Borrowed(event),
+        };
+
+        let subject = self.route_event(&event);
+        let payload = serde_json::to_vec(event.as_ref()).map_err(|e| GatewayError::SerializationFailed {
+            event_type: event.event_type.to_string(),
+            shard_id: event.shard_id.get(),
             source: e,
         })?;
 
@@ -115,10 +253,27 @@ impl NatsPublisher {
             event_type = %event.event_type,
             subject,
             event_id = %event.event_id,
+            codec = self.codec.name(),
             "Publishing event"
         );
+        self.publish_bytes(subject, &payload).await
+    }
 
-        match self.jetstream.publish(subject.clone(), payload.into()).await {
+    /// Compress and publish one message, awaiting the JetStream ack
+    async fn publish_bytes(&self, subject: String, payload: &[u8]) -> Result<(), GatewayError> {
+        let payload = self.codec.compress(payload)?;
+
+        // Identity payloads carry no header, so consumers that predate
+        // codecs keep working
+        let publish = if self.codec.id() == Identity.id() {
+            self.jetstream.publish(subject.clone(), payload.into()).await
+        } else {
+            let mut headers = async_nats::HeaderMap::new();
+            headers.insert(CODEC_HEADER, self.codec.id().to_string().as_str());
+            self.jetstream.publish_with_headers(subject.clone(), headers, payload.into()).await
+        };
+
+        match publish {
             Ok(ack_future) => {
                 // In async-nats 0.46, publish returns a PublishAckFuture
                 // that must be awaited to get the actual acknowledgment
@@ -156,25 +311,60 @@ impl NatsPublisher {
 
     /// Route event to appropriate subject based on event type
     fn route_event(&self, event: &GatewayEvent) -> String {
+        if self.priority_subjects {
+            return priority_subject(event.event_type.as_str());
+        }
         match event.event_type.as_str() {
             // Interactions go to COMMANDS stream
             "interaction.create" => format!("{}.interaction", subjects::COMMANDS),
+            "interaction.autocomplete" => format!("{}.autocomplete", subjects::COMMANDS),
+            "interaction.modal" => format!("{}.modal", subjects::COMMANDS),
 
             // Guild events go to EVENTS stream
             "guild.join" => format!("{}.join", subjects::GUILD_EVENTS),
             "guild.leave" => format!("{}.leave", subjects::GUILD_EVENTS),
             "guild.update" => format!("{}.update", subjects::GUILD_EVENTS),
+            "guild.anomaly" => format!("{}.anomaly", subjects::GUILD_EVENTS),
+            "guild.ban.add" => format!("{}.ban.add", subjects::GUILD_EVENTS),
+            "guild.ban.remove" => format!("{}.ban.remove", subjects::GUILD_EVENTS),
 
             // Member events go to EVENTS stream
             "member.join" => format!("{}.join", subjects::MEMBER_EVENTS),
             "member.leave" => format!("{}.leave", subjects::MEMBER_EVENTS),
             "member.update" => format!("{}.update", subjects::MEMBER_EVENTS),
+            "member.onboarding.complete" => format!("{}.onboarding.complete", subjects::MEMBER_EVENTS),
+            "member.timeout.expired" => format!("{}.timeout.expired", subjects::MEMBER_EVENTS),
+            "member.chunk" => format!("{}.chunk", subjects::MEMBER_EVENTS),
+
+            // Message events go to EVENTS stream
+            "message.create" => format!("{}.create", subjects::MESSAGE_EVENTS),
+            "message.update" => format!("{}.update", subjects::MESSAGE_EVENTS),
+            "message.embed.update" => format!("{}.embed.update", subjects::MESSAGE_EVENTS),
+            "message.pin.update" => format!("{}.pin.update", subjects::MESSAGE_EVENTS),
+            "message.delete" => format!("{}.delete", subjects::MESSAGE_EVENTS),
+            "message.delete.bulk" => format!("{}.delete.bulk", subjects::MESSAGE_EVENTS),
+            "reaction.add" => format!("{}.reaction.add", subjects::MESSAGE_EVENTS),
+            "reaction.remove" => format!("{}.reaction.remove", subjects::MESSAGE_EVENTS),
 
             // Default: generic event
             other => format!("events.{}", other.replace('.', "_")),
         }
     }
 
+    /// Publish every event left in the failed-publish buffer, for shutdown
+    ///
+    /// Fails with `FlushIncomplete` if the broker rejects one; what's left
+    /// stays in the buffer file for the next start. A no-op without a
+    /// buffer.
+    pub async fn flush(&self) -> Result<(), GatewayError> {
+        let Some(ref buffer) = self.buffer else {
+            return Ok(());
+        };
+        buffer
+            .flush(|buffered| async move { self.send_event_bounded(&buffered).await })
+            .await
+    }
+
     /// Graceful shutdown
     pub async fn close(&self) {
         info!("Closing NATS connection");
@@ -183,6 +373,46 @@ impl NatsPublisher {
     }
 }
 
+/// Await `publish`, failing with `PublishTimeout` once `timeout` elapses
+///
+/// A stalled broker can leave a JetStream ack pending indefinitely, with the
+/// shard's event loop waiting behind it. A timed-out publish may still land,
+/// so its replay can duplicate; consumers dedupe by `event_id`.
+async fn with_timeout<Fut>(
+    publish: Fut,
+    timeout: Option<Duration>,
+    subject: impl FnOnce() -> String,
+) -> Result<(), GatewayError>
+where
+    Fut: Future<Output = Result<(), GatewayError>>,
+{
+    let Some(timeout) = timeout else {
+        return publish.await;
+    };
+    tokio::time::timeout(timeout, publish).await.unwrap_or_else(|_| {
+        Err(GatewayError::PublishTimeout {
+            subject: subject(),
+            timeout_ms: u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX),
+        })
+    })
+}
+
+/// Move an event that failed to publish into the failed-publish buffer
+///
+/// Only broker-side failures qualify; an event that can't be serialized
+/// would fail the same way on replay.
+fn dead_letter(result: &Result<(), GatewayError>, event: &GatewayEvent, buffer: Option<&FailedPublishBuffer>) {
+    let (Err(reason @ (GatewayError::NatsPublishFailed { .. } | GatewayError::PublishTimeout { .. })), Some(buffer)) =
+        (result, buffer)
+    else {
+        return;
+    };
+    counter!("gateway_events_dead_lettered_total", "reason" => reason.error_type_label()).increment(1);
+    if let Err(e) = buffer.push(event.clone()) {
+        error!(event_id = %event.event_id, error = %e, "Failed to buffer unpublished event");
+    }
+}
+
 /// Ensure streams exist with correct configuration
 ///
 /// This is typically run during startup or by a separate setup job.
@@ -231,6 +461,53 @@ pub async fn ensure_streams(js: &JsContext) -> Result<(), GatewayError> {
         }
     }
 
+    // PAYLOADS stream - data split off oversized events; outlives the
+    // EVENTS retention so a consumer can still fetch it for any event there
+    let payloads_config = Config {
+        name: streams::PAYLOADS.to_string(),
+        subjects: vec![format!("{}.>", subjects::PAYLOADS)],
+        retention: RetentionPolicy::Limits,
+        max_age: std::time::Duration::from_secs(600),
+        storage: StorageType::Memory,
+        ..Default::default()
+    };
+
+    match js.create_stream(payloads_config).await {
+        Ok(_) => info!("Created PAYLOADS stream"),
+        Err(e) if e.to_string().contains("already in use") => {
+            debug!("PAYLOADS stream already exists");
+        }
+        Err(e) => {
+            error!(error = %e, "Failed to create PAYLOADS stream");
+            return Err(GatewayError::Config(format!("Failed to create PAYLOADS stream: {e}")));
+        }
+    }
+
+    // PRIORITIZED stream - priority-encoded subjects, for gateways with
+    // PUBLISH_PRIORITY_SUBJECTS; same retention as EVENTS
+    let prioritized_config = Config {
+        name: streams::PRIORITIZED.to_string(),
+        subjects: PRIORITY_TIERS
+            .iter()
+            .map(|tier| format!("{}.{tier}.>", subjects::PRIORITIZED))
+            .collect(),
+        retention: RetentionPolicy::Limits,
+        max_age: std::time::Duration::from_secs(300),
+        storage: StorageType::Memory,
+        ..Default::default()
+    };
+
+    match js.create_stream(prioritized_config).await {
+        Ok(_) => info!("Created PRIORITIZED stream"),
+        Err(e) if e.to_string().contains("already in use") => {
+            debug!("PRIORITIZED stream already exists");
+        }
+        Err(e) => {
+            error!(error = %e, "Failed to create PRIORITIZED stream");
+            return Err(GatewayError::Config(format!("Failed to create PRIORITIZED stream: {e}")));
+        }
+    }
+
     info!("NATS streams configured");
     Ok(())
 }
@@ -238,18 +515,21 @@ pub async fn ensure_streams(js: &JsContext) -> Result<(), GatewayError> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::events::event_type::EventType;
+    use crate::events::shard_id::ShardId;
 
     #[test]
     fn test_route_interaction() {
         let event = GatewayEvent {
             event_id: "test".to_string(),
-            event_type: "interaction.create".to_string(),
-            shard_id: 0,
+            event_type: EventType::InteractionCreate,
+            shard_id: ShardId::ZERO,
             timestamp: 0,
             guild_id: None,
             channel_id: None,
             user_id: None,
             data: serde_json::Value::Null,
+            ..Default::default()
         };
 
         // Create a mock publisher would require more setup
@@ -257,6 +537,14 @@ mod tests {
         assert_eq!(event.event_type, "interaction.create");
     }
 
+    #[test]
+    fn priority_subjects_encode_tier_and_category() {
+        assert_eq!(priority_subject("interaction.create"), "discord.p0.command.interaction.create");
+        assert_eq!(priority_subject("shard.reconnect_requested"), "discord.p0.control.shard.reconnect_requested");
+        assert_eq!(priority_subject("typing.start"), "discord.p3.data.typing.start");
+        assert!(!priority_subject("typing.start").starts_with(subjects::PAYLOADS), "outside the PAYLOADS stream");
+    }
+
     #[test]
     fn test_stream_constants() {
         assert_eq!(streams::COMMANDS, "COMMANDS");
@@ -264,6 +552,30 @@ mod tests {
         assert_eq!(streams::ELIGIBILITY, "ELIGIBILITY");
     }
 
+    #[tokio::test]
+    async fn stalled_publish_is_dead_lettered_after_timeout() {
+        let path = std::env::temp_dir().join(format!("arrakis-publish-timeout-{}.ndjson", uuid::Uuid::new_v4()));
+        let buffer = FailedPublishBuffer::open(&path, 10).unwrap();
+        let event = GatewayEvent {
+            event_id: "00000000-0000-4000-8000-000000000001".to_string(),
+            event_type: EventType::MemberJoin,
+            ..Default::default()
+        };
+
+        // A broker that never acks
+        let stalled = std::future::pending::<Result<(), GatewayError>>();
+        let result = with_timeout(stalled, Some(Duration::from_millis(10)), || "events.member.join".to_string()).await;
+        assert!(
+            matches!(&result, Err(GatewayError::PublishTimeout { subject, timeout_ms: 10 }) if subject == "events.member.join"),
+            "got {result:?}"
+        );
+
+        dead_letter(&result, &event, Some(&buffer));
+        assert_eq!(buffer.len(), 1);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
     /// Validates that Rust hardcoded constants match the language-neutral
     /// nats-routing.json. If this fails, Rust routing has drifted from
     /// the shared contract consumed by TypeScript workers.
@@ -300,6 +612,21 @@ mod tests {
                 json_streams["ELIGIBILITY"]["name"].as_str().unwrap(),
                 "ELIGIBILITY stream name mismatch"
             );
+            assert_eq!(
+                streams::PAYLOADS,
+                json_streams["PAYLOADS"]["name"].as_str().unwrap(),
+                "PAYLOADS stream name mismatch"
+            );
+            assert_eq!(
+                streams::PRIORITIZED,
+                json_streams["PRIORITIZED"]["name"].as_str().unwrap(),
+                "PRIORITIZED stream name mismatch"
+            );
+            let json_tiers: Vec<_> = json_streams["PRIORITIZED"]["subjects"].as_array().unwrap().iter()
+                .map(|s| s.as_str().unwrap().to_string())
+                .collect();
+            let tiers: Vec<_> = PRIORITY_TIERS.iter().map(|tier| format!("{}.{tier}.>", subjects::PRIORITIZED)).collect();
+            assert_eq!(tiers, json_tiers, "PRIORITIZED stream subjects mismatch");
         }
 
         #[test]
@@ -332,6 +659,26 @@ mod tests {
                 json_subjects["commands"]["interaction"].as_str().unwrap(),
                 "interaction subject mismatch"
             );
+            assert_eq!(
+                subjects::MESSAGE_EVENTS,
+                json_subjects["message_events"]["prefix"].as_str().unwrap(),
+                "message_events prefix mismatch"
+            );
+            assert_eq!(
+                subjects::USAGE,
+                json_subjects["usage"]["prefix"].as_str().unwrap(),
+                "usage prefix mismatch"
+            );
+            assert_eq!(
+                subjects::PAYLOADS,
+                json_subjects["payloads"]["prefix"].as_str().unwrap(),
+                "payloads prefix mismatch"
+            );
+            assert_eq!(
+                subjects::PRIORITIZED,
+                json_subjects["prioritized"]["prefix"].as_str().unwrap(),
+                "prioritized prefix mismatch"
+            );
         }
 
         #[test]
@@ -352,7 +699,9 @@ mod tests {
                 // Verify the expected subject starts with a known prefix
                 let valid = expected.starts_with(subjects::COMMANDS)
                     || expected.starts_with(subjects::GUILD_EVENTS)
-                    || expected.starts_with(subjects::MEMBER_EVENTS);
+                    || expected.starts_with(subjects::MEMBER_EVENTS)
+                    || expected.starts_with(subjects::MESSAGE_EVENTS)
+                    || expected.starts_with(subjects::USAGE);
                 assert!(
                     valid,
                     "event_type '{}' maps to subject '{}' which doesn't match any Rust prefix",
@@ -0,0 +1,86 @@
+//! Oversized event data on its own subject
+//!
+//! A full-guild `guild.join` can run to hundreds of kilobytes, which every
+//! subscriber to the event's subject has to take whether it reads `data`
+//! or not. With a split threshold set, the publisher moves `data` above it
+//! into a message of its own on `discord.payload.<event_id>` (the PAYLOADS
+//! stream) and publishes the event itself with `data: null` and
+//! `data_ref` set to the event_id. Consumers that need the data fetch it
+//! from [`payload_subject`] on demand.
+
+use crate::error::GatewayError;
+use crate::events::serialize::GatewayEvent;
+use crate::nats::publisher::subjects;
+
+/// Subject carrying the detached data of event `event_id`
+pub fn payload_subject(event_id: &str) -> String {
+    format!("{}.{event_id}", subjects::PAYLOADS)
+}
+
+/// An event's `data`, moved to its own message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetachedData {
+    pub subject: String,
+    /// The data's JSON
+    pub payload: Vec<u8>,
+}
+
+/// Split `event` if its `data` serializes to more than `threshold` bytes
+///
+/// Returns the header event to publish in its place (`data` null,
+/// `data_ref` set) and the detached data, or None when `data` is small
+/// enough to stay inline.
+pub fn detach_data(event: &GatewayEvent, threshold: usize) -> Result<Option<(GatewayEvent, DetachedData)>, GatewayError> {
+    let payload = serde_json::to_vec(&event.data).map_err(|e| GatewayError::SerializationFailed {
+        event_type: event.event_type.to_string(),
+        shard_id: event.shard_id.get(),
+        source: e,
+    })?;
+    if payload.len() <= threshold {
+        return Ok(None);
+    }
+    let header = GatewayEvent {
+        data: serde_json::Value::Null,
+        data_ref: Some(event.event_id.clone()),
+        ..event.clone()
+    };
+    let detached = DetachedData {
+        subject: payload_subject(&event.event_id),
+        payload,
+    };
+    Ok(Some((header, detached)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::test_support::gateway_event;
+
+    fn guild_join(channels: usize) -> GatewayEvent {
+        gateway_event("guild.join")
+            .event_id("00000000-0000-4000-8000-000000000001")
+            .guild("123456789012345678")
+            .data(serde_json::json!({ "channels": vec!["333333333333333333"; channels] }))
+            .build()
+    }
+
+    #[test]
+    fn oversized_data_is_split_onto_the_payload_subject() {
+        let event = guild_join(1000);
+        let (header, detached) = detach_data(&event, 4096).unwrap().expect("over the threshold");
+
+        assert_eq!(header.data, serde_json::Value::Null);
+        assert_eq!(header.data_ref.as_deref(), Some("00000000-0000-4000-8000-000000000001"));
+        assert_eq!(header.guild_id, event.guild_id, "envelope stays on the header");
+        assert_eq!(detached.subject, "discord.payload.00000000-0000-4000-8000-000000000001");
+        assert_eq!(serde_json::from_slice::<serde_json::Value>(&detached.payload).unwrap(), event.data);
+
+        let json = serde_json::to_value(&header).unwrap();
+        assert_eq!(json["data_ref"], "00000000-0000-4000-8000-000000000001");
+    }
+
+    #[test]
+    fn small_data_stays_inline() {
+        assert!(detach_data(&guild_join(2), 4096).unwrap().is_none());
+    }
+}
@@ -4,7 +4,9 @@
 //! Implements shard pools per SDD §5.1.3
 
 mod pool;
+mod snapshot;
 mod state;
 
 pub use pool::ShardPool;
+pub use snapshot::{StateStore, DEFAULT_SNAPSHOT_INTERVAL};
 pub use state::{ShardHealth, ShardState};
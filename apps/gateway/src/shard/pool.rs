@@ -4,16 +4,18 @@
 //! Manages multiple Discord shards per process per SDD §5.1.3
 
 use crate::error::GatewayError;
-use crate::events::serialize::serialize_event;
+use crate::events::{EventPipeline, SerializeConfig, ShardInfo};
 use crate::metrics::GatewayMetrics;
 use crate::nats::NatsPublisher;
+use crate::shard::snapshot::{StateStore, DEFAULT_SNAPSHOT_INTERVAL};
 use crate::shard::state::{ShardHealth, ShardState};
+use crate::sink::StdoutNdjsonSink;
 
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::broadcast;
 use tracing::{debug, error, info, warn};
-use twilight_gateway::{Config, EventTypeFlags, Intents, Shard, StreamExt as _};
+use twilight_gateway::{Config, EventTypeFlags, Intents, Session, Shard, StreamExt as _};
 use twilight_model::gateway::{ShardId, event::Event};
 
 /// Number of shards per gateway process (pool)
@@ -26,6 +28,9 @@ pub struct ShardPool {
     nats: Option<Arc<NatsPublisher>>,
     state: ShardState,
     metrics: Arc<GatewayMetrics>,
+    serialize_config: SerializeConfig,
+    stdout_sink: Option<Arc<StdoutNdjsonSink>>,
+    state_store: Option<Arc<StateStore>>,
     shutdown_tx: broadcast::Sender<()>,
 }
 
@@ -39,6 +44,7 @@ impl ShardPool {
     /// * `intents` - Discord gateway intents
     /// * `nats` - Optional NATS publisher (None for local testing)
     /// * `metrics` - Prometheus metrics
+    /// * `serialize_config` - Event payload options
     pub async fn new(
         pool_id: u64,
         total_shards: u64,
@@ -46,6 +52,7 @@ impl ShardPool {
         intents: Intents,
         nats: Option<Arc<NatsPublisher>>,
         metrics: Arc<GatewayMetrics>,
+        serialize_config: SerializeConfig,
     ) -> Result<Self, GatewayError> {
         let start_shard = pool_id * SHARDS_PER_POOL;
         let end_shard = ((pool_id + 1) * SHARDS_PER_POOL).min(total_shards);
@@ -86,10 +93,26 @@ impl ShardPool {
             nats,
             state,
             metrics,
+            serialize_config,
+            stdout_sink: None,
+            state_store: None,
             shutdown_tx,
         })
     }
 
+    /// Also write every forwarded event to an NDJSON stdout sink
+    pub fn with_stdout_sink(mut self, sink: Arc<StdoutNdjsonSink>) -> Self {
+        self.stdout_sink = Some(sink);
+        self
+    }
+
+    /// Restore each shard's serializer state from `store` on start, and
+    /// snapshot it there periodically and on shutdown
+    pub fn with_state_store(mut self, store: Arc<StateStore>) -> Self {
+        self.state_store = Some(store);
+        self
+    }
+
     /// Get the pool ID
     pub fn pool_id(&self) -> u64 {
         self.pool_id
@@ -111,18 +134,25 @@ impl ShardPool {
             let nats = self.nats.clone();
             let state = self.state.clone();
             let metrics = Arc::clone(&self.metrics);
-            let mut shutdown_rx = self.shutdown_tx.subscribe();
+            let serialize_config = self.serialize_config.clone();
+            let stdout_sink = self.stdout_sink.clone();
+            let state_store = self.state_store.clone();
+            let shutdown_rx = self.shutdown_tx.subscribe();
 
             let handle = tokio::spawn(async move {
-                tokio::select! {
-                    result = run_shard(shard, nats, state, metrics) => {
-                        if let Err(e) = result {
-                            error!(shard_id, error = %e, "Shard task failed");
-                        }
-                    }
-                    _ = shutdown_rx.recv() => {
-                        info!(shard_id, "Shard received shutdown signal");
-                    }
+                let result = run_shard(
+                    shard,
+                    nats,
+                    state,
+                    metrics,
+                    serialize_config,
+                    stdout_sink,
+                    state_store,
+                    shutdown_rx,
+                )
+                .await;
+                if let Err(e) = result {
+                    error!(shard_id, error = %e, "Shard task failed");
                 }
             });
 
@@ -144,12 +174,21 @@ impl ShardPool {
     }
 }
 
-/// Run a single shard's event loop
+/// Run a single shard's event loop until it ends, fails or `shutdown`
+/// fires
+///
+/// With a `state_store`, the pipeline's state is restored before the first
+/// event and saved every snapshot interval and on the way out.
+#[allow(clippy::too_many_arguments)]
 async fn run_shard(
     mut shard: Shard,
     nats: Option<Arc<NatsPublisher>>,
     state: ShardState,
     metrics: Arc<GatewayMetrics>,
+    serialize_config: SerializeConfig,
+    stdout_sink: Option<Arc<StdoutNdjsonSink>>,
+    state_store: Option<Arc<StateStore>>,
+    mut shutdown: broadcast::Receiver<()>,
 ) -> Result<(), GatewayError> {
     let shard_id: u64 = shard.id().number().into();
     let pool_id = state.pool_id();
@@ -158,11 +197,58 @@ async fn run_shard(
 
     info!(shard_id, pool_id, "Shard starting");
 
+    let shard_info = ShardInfo::for_shard(shard.id().into(), state.total_shards(), SHARDS_PER_POOL);
+    let mut pipeline = EventPipeline::new(shard.id().into(), serialize_config).with_shard_info(shard_info);
+    let shard_count = state.total_shards();
+    if let Some(ref store) = state_store {
+        match store.load(shard_id, shard_count) {
+            Ok(Some(restored)) => {
+                info!(shard_id, sequence = restored.sequence(), "Restored serializer state");
+                pipeline = pipeline.with_state(restored);
+            }
+            Ok(None) => {}
+            // A corrupt or unreadable snapshot shouldn't keep the shard down
+            Err(e) => warn!(shard_id, error = %e, "Failed to restore serializer state, starting fresh"),
+        }
+    }
+    // `last` once the shard stops processing events, so the next start can
+    // resume the exact sequence
+    let save_state = |pipeline: &EventPipeline, last: bool| {
+        if let Some(ref store) = state_store {
+            let saved = if last {
+                store.save_final(shard_id, shard_count, pipeline.state())
+            } else {
+                store.save(shard_id, shard_count, pipeline.state())
+            };
+            if let Err(e) = saved {
+                metrics.record_error(shard_id, e.error_type_label());
+                warn!(shard_id, error = %e, "Failed to save serializer state");
+            }
+        }
+    };
+    let snapshot_period = state_store.as_ref().map_or(DEFAULT_SNAPSHOT_INTERVAL, |store| store.interval());
+    let mut snapshot_tick = tokio::time::interval_at(tokio::time::Instant::now() + snapshot_period, snapshot_period);
+
     // Circuit breaker: mark shard dead after N consecutive errors without success
     const MAX_CONSECUTIVE_ERRORS: u32 = 10;
     let mut consecutive_errors: u32 = 0;
 
-    while let Some(item) = shard.next_event(EventTypeFlags::all()).await {
+    loop {
+        let item = tokio::select! {
+            item = shard.next_event(EventTypeFlags::all()) => match item {
+                Some(item) => item,
+                None => break,
+            },
+            _ = shutdown.recv() => {
+                info!(shard_id, "Shard received shutdown signal");
+                save_state(&pipeline, true);
+                return Ok(());
+            }
+            _ = snapshot_tick.tick(), if state_store.is_some() => {
+                save_state(&pipeline, false);
+                continue;
+            }
+        };
         let event = match item {
             Ok(event) => {
                 consecutive_errors = 0;
@@ -180,6 +266,7 @@ async fn run_shard(
                     };
                     metrics.record_error(shard_id, err.error_type_label());
                     state.set_health(shard_id, ShardHealth::Dead);
+                    save_state(&pipeline, true);
                     error!(shard_id, "Fatal gateway error (reconnect failed)");
                     return Err(err);
                 }
@@ -193,6 +280,7 @@ async fn run_shard(
                     };
                     metrics.record_error(shard_id, err.error_type_label());
                     state.set_health(shard_id, ShardHealth::Dead);
+                    save_state(&pipeline, true);
                     error!(shard_id, consecutive = consecutive_errors, "Shard dead: consecutive error threshold exceeded");
                     return Err(err);
                 }
@@ -250,12 +338,31 @@ async fn run_shard(
             _ => {}
         }
 
-        // Route event to NATS if available
-        if let Some(ref nats) = nats {
-            let start = Instant::now();
+        // Serialize through the shard pipeline even without NATS so
+        // shard-local state stays current
+        let gateway_seq = shard.session().map(Session::sequence);
+        let payloads = match pipeline.process_frame(&event, gateway_seq) {
+            Ok(payloads) => payloads,
+            Err(e) => {
+                metrics.record_error(shard_id, e.error_type_label());
+                warn!(shard_id, error = %e, "Rejected malformed event");
+                continue;
+            }
+        };
+
+        for payload in &payloads {
+            if let Some(ref sink) = stdout_sink {
+                if let Err(e) = sink.write_event(payload) {
+                    metrics.record_error(shard_id, e.error_type_label());
+                    warn!(shard_id, error = %e, "Failed to write event to stdout sink");
+                }
+            }
+
+            // Route event to NATS if available
+            if let Some(ref nats) = nats {
+                let start = Instant::now();
 
-            if let Some(payload) = serialize_event(&event, shard_id) {
-                match nats.publish_event(&payload).await {
+                match nats.publish_event(payload).await {
                     Ok(()) => {
                         state.record_route(shard_id);
                         metrics.record_route_success(shard_id, start.elapsed());
@@ -266,12 +373,14 @@ async fn run_shard(
                         warn!(shard_id, error = %e, "Failed to publish event to NATS");
                     }
                 }
+                metrics.set_publish_buffered(nats.buffered_events());
             }
         }
     }
 
     // Stream ended — shard closed
     info!(shard_id, "Shard event stream ended");
+    save_state(&pipeline, true);
     Ok(())
 }
 
@@ -0,0 +1,194 @@
+//! Per-shard serializer state on disk
+//!
+//! A shard's [`SerializerState`] holds its sequence counter and the caches
+//! payload options diff against. Without persistence every restart begins
+//! again at sequence 1 with empty caches. With a [`StateStore`] configured
+//! (`STATE_DIR`), each shard restores its state before processing any
+//! event, snapshots it every `interval` and again when it stops.
+//!
+//! Files are named by shard id and shard count, so after resharding no
+//! shard picks up another topology's state.
+//!
+//! Only a snapshot saved as the shard stopped holds its exact sequence. A
+//! periodic one may predate events already emitted, so after a crash the
+//! restored state keeps its caches but starts a new `producer_epoch`;
+//! continuing its sequence would reuse positions consumers have committed.
+//! Loading a snapshot marks it periodic, so a crash after a clean restart
+//! can't resume it twice.
+
+use crate::error::GatewayError;
+use crate::events::state::SerializerState;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Default interval between periodic snapshots
+pub const DEFAULT_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Directory of per-shard state snapshots
+#[derive(Debug)]
+pub struct StateStore {
+    dir: PathBuf,
+    interval: Duration,
+}
+
+/// On-disk form of a snapshot (`state` is borrowed when saving)
+#[derive(Serialize, Deserialize)]
+struct Snapshot<S> {
+    /// Saved as the shard stopped, after its last event
+    clean: bool,
+    state: S,
+}
+
+impl StateStore {
+    /// Store snapshots in `dir` (created if missing), every `interval`
+    pub fn open(dir: impl Into<PathBuf>, interval: Duration) -> Result<Self, GatewayError> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).map_err(|e| state_file_error(&dir, e))?;
+        Ok(Self { dir, interval })
+    }
+
+    /// How often a running shard snapshots its state
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Snapshot file for one shard of a `shard_count`-shard topology
+    pub fn path(&self, shard_id: u64, shard_count: u64) -> PathBuf {
+        self.dir.join(format!("shard-{shard_id}-of-{shard_count}.json"))
+    }
+
+    /// The shard's last saved state, or None if it has none yet
+    ///
+    /// Unless the snapshot was saved by [`save_final`](Self::save_final),
+    /// the state comes back under a new producer epoch.
+    pub fn load(&self, shard_id: u64, shard_count: u64) -> Result<Option<SerializerState>, GatewayError> {
+        let path = self.path(shard_id, shard_count);
+        let bytes = match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(state_file_error(&path, e)),
+        };
+        let Snapshot::<SerializerState> { clean, mut state } =
+            serde_json::from_slice(&bytes).map_err(GatewayError::StateSnapshot)?;
+        if clean {
+            self.save(shard_id, shard_count, &state)?;
+        } else {
+            state.start_new_epoch();
+        }
+        Ok(Some(state))
+    }
+
+    /// Save a periodic snapshot of the running shard's state
+    pub fn save(&self, shard_id: u64, shard_count: u64, state: &SerializerState) -> Result<(), GatewayError> {
+        self.write(shard_id, shard_count, state, false)
+    }
+
+    /// Save the state of a shard that has stopped processing events, which
+    /// the next start resumes exactly
+    pub fn save_final(&self, shard_id: u64, shard_count: u64, state: &SerializerState) -> Result<(), GatewayError> {
+        self.write(shard_id, shard_count, state, true)
+    }
+
+    /// Replace the previous snapshot atomically
+    fn write(&self, shard_id: u64, shard_count: u64, state: &SerializerState, clean: bool) -> Result<(), GatewayError> {
+        let path = self.path(shard_id, shard_count);
+        let partial = path.with_extension("json.tmp");
+        let bytes = serde_json::to_vec(&Snapshot { clean, state }).map_err(GatewayError::StateSnapshot)?;
+        std::fs::write(&partial, bytes).map_err(|e| state_file_error(&partial, e))?;
+        std::fs::rename(&partial, &path).map_err(|e| state_file_error(&path, e))
+    }
+}
+
+fn state_file_error(path: &Path, source: std::io::Error) -> GatewayError {
+    GatewayError::StateFile {
+        path: path.display().to_string(),
+        source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consumer::{MemoryCursor, ReplayFilter};
+    use crate::events::pipeline::EventPipeline;
+    use crate::events::serialize::{GatewayEvent, SerializeConfig};
+    use crate::events::shard_id::ShardId;
+    use crate::events::test_support::member_add;
+
+    const GUILD: u64 = 123456789012345678;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("arrakis-state-{}-{}", name, uuid::Uuid::new_v4()))
+    }
+
+    fn pipeline(state: Option<SerializerState>) -> EventPipeline {
+        let pipeline = EventPipeline::new(ShardId::new(3, 4).unwrap(), SerializeConfig::default());
+        match state {
+            Some(state) => pipeline.with_state(state),
+            None => pipeline,
+        }
+    }
+
+    fn emit(pipeline: &mut EventPipeline, users: std::ops::RangeInclusive<u64>) -> Vec<GatewayEvent> {
+        users
+            .map(|user_id| pipeline.process(&member_add(GUILD, user_id, &[])).unwrap().remove(0))
+            .collect()
+    }
+
+    /// Events the filter passes, committing each
+    fn passed(filter: &mut ReplayFilter<MemoryCursor>, events: &[GatewayEvent]) -> usize {
+        events
+            .iter()
+            .filter(|event| {
+                let pass = filter.should_process(event).unwrap();
+                if pass {
+                    filter.commit(event).unwrap();
+                }
+                pass
+            })
+            .count()
+    }
+
+    #[test]
+    fn restart_after_clean_stop_continues_the_sequence() {
+        let dir = temp_dir("restart");
+        let store = StateStore::open(&dir, DEFAULT_SNAPSHOT_INTERVAL).unwrap();
+        assert!(store.load(3, 4).unwrap().is_none(), "first start");
+
+        let mut first = pipeline(None);
+        emit(&mut first, 1..=3);
+        store.save_final(3, 4, first.state()).unwrap();
+
+        let mut restarted = pipeline(store.load(3, 4).unwrap());
+        let next = emit(&mut restarted, 4..=4).remove(0);
+        assert_eq!(next.sequence, Some(4));
+        assert_eq!(next.producer_epoch, first.state().producer_epoch());
+
+        // Crashing now must not resume the same snapshot a second time
+        let again = store.load(3, 4).unwrap().unwrap();
+        assert_ne!(again.producer_epoch(), first.state().producer_epoch());
+
+        assert!(store.load(3, 8).unwrap().is_none(), "a resharded topology starts fresh");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn restart_from_a_periodic_snapshot_is_not_a_replay() {
+        let dir = temp_dir("crash");
+        let store = StateStore::open(&dir, DEFAULT_SNAPSHOT_INTERVAL).unwrap();
+        let mut filter = ReplayFilter::new(MemoryCursor::default());
+
+        let mut first = pipeline(None);
+        assert_eq!(passed(&mut filter, &emit(&mut first, 1..=2)), 2);
+        store.save(3, 4, first.state()).unwrap();
+        // Emitted after the last periodic snapshot, then the shard crashes
+        assert_eq!(passed(&mut filter, &emit(&mut first, 3..=5)), 3);
+
+        let mut restarted = pipeline(store.load(3, 4).unwrap());
+        let resumed = emit(&mut restarted, 6..=8);
+        assert!(resumed[0].producer_epoch > first.state().producer_epoch());
+        assert_eq!(passed(&mut filter, &resumed), 3, "new events pass the replay filter");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,95 @@
+//! NDJSON debugging sink
+//!
+//! Writes each serialized event as one JSON line, flushed per line, so a
+//! local gateway can be piped straight into `jq`:
+//!
+//! ```text
+//! STDOUT_EVENTS=true arrakis-gateway | jq 'select(.event_type=="member.join")'
+//! ```
+//!
+//! This is a debugging aid alongside (not instead of) the NATS path. When
+//! enabled, logs move to stderr so stdout carries events only.
+
+use crate::error::GatewayError;
+use crate::events::GatewayEvent;
+use std::io::Write;
+use std::sync::Mutex;
+
+/// Line-delimited JSON event writer (stdout by default)
+///
+/// Shared across shard tasks; each event is written and flushed under a
+/// lock so lines from different shards never interleave.
+pub struct StdoutNdjsonSink<W: Write + Send = std::io::Stdout> {
+    writer: Mutex<W>,
+}
+
+impl StdoutNdjsonSink {
+    /// Sink writing to the process's stdout
+    pub fn stdout() -> Self {
+        Self::new(std::io::stdout())
+    }
+}
+
+impl<W: Write + Send> StdoutNdjsonSink<W> {
+    /// Sink writing to an arbitrary writer
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+        }
+    }
+
+    /// Write one event as a single JSON line and flush
+    pub fn write_event(&self, event: &GatewayEvent) -> Result<(), GatewayError> {
+        let mut line = serde_json::to_vec(event).map_err(|source| GatewayError::SerializationFailed {
+            event_type: event.event_type.to_string(),
+            shard_id: event.shard_id.get(),
+            source,
+        })?;
+        line.push(b'\n');
+
+        let mut writer = self.writer.lock().unwrap();
+        writer
+            .write_all(&line)
+            .and_then(|()| writer.flush())
+            .map_err(GatewayError::SinkWrite)
+    }
+
+    /// Consume the sink, returning the underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::test_support::gateway_event;
+
+    fn test_event(event_type: &str, user_id: &str) -> GatewayEvent {
+        gateway_event(event_type)
+            .event_id("00000000-0000-4000-8000-000000000001")
+            .timestamp(1700000000000)
+            .guild("123456789012345678")
+            .user(user_id)
+            .build()
+    }
+
+    #[test]
+    fn writes_one_json_object_per_line() {
+        let sink = StdoutNdjsonSink::new(Vec::new());
+        sink.write_event(&test_event("member.join", "987654321098765432")).unwrap();
+        sink.write_event(&test_event("member.leave", "987654321098765433")).unwrap();
+
+        let output = String::from_utf8(sink.into_inner()).unwrap();
+        assert!(output.ends_with('\n'));
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+
+        let first: GatewayEvent = serde_json::from_str(lines[0]).unwrap();
+        let second: GatewayEvent = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(first.event_type, "member.join");
+        assert_eq!(second.event_type, "member.leave");
+        assert_eq!(second.user_id.as_deref(), Some("987654321098765433"));
+    }
+}
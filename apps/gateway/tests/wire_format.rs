@@ -106,10 +106,53 @@ const DETERMINISTIC_FIXTURES: &[&str] = &[
 const ALL_FIXTURES: &[&str] = &[
     "guild-join",
     "guild-leave",
+    "guild-anomaly",
     "member-join",
     "member-leave",
     "member-update",
+    "member-timeout-expired",
     "interaction-create",
+    "interaction-create-resolved",
+    "interaction-create-component",
+    "message-update",
+    "message-embed-update",
+    "message-delete",
+    "message-delete-bulk",
+    "voice-state-update",
+    "scheduled-event-user-remove",
+    "presence-update",
+    "presence-update-spotify",
+    "shard-reconnect-requested",
+    "interaction-autocomplete",
+    "interaction-modal",
+    "message-create-webhook",
+    "member-onboarding-complete",
+    "message-create-command-response",
+    "message-create-embed",
+    "message-create-components",
+    "message-create",
+    "message-pin-update",
+    "guild-join-large",
+    "guild-join-vanity",
+    "guild-join-onboarding",
+    "member-join-flags",
+    "guild-ban-add",
+    "guild-ban-remove",
+    "thread-list-sync",
+    "thread-create",
+    "thread-update",
+    "thread-delete",
+    "channel-update",
+    "user-update",
+    "member-chunk",
+    "reaction-add-burst",
+    "reaction-add-custom",
+    "reaction-remove",
+    "typing-start",
+    "guild-update",
+    "automod-rule-create",
+    "automod-rule-update",
+    "automod-rule-delete",
 ];
 
 /// Required envelope fields for every GatewayEvent.
@@ -188,3 +231,14 @@ fn bb60_20_interaction_token_field_name() {
         "BB60-20: interaction fixture must NOT have bare 'token' field"
     );
 }
+
+/// BB60-20 regression guard for the dedicated InteractionEvent fixture,
+/// which carries the token at the top level.
+#[test]
+fn bb60_20_interaction_event_token_field_name() {
+    let fixture = load_fixture("interaction-event");
+    let obj = fixture.as_object().expect("fixture should be object");
+    assert!(obj.contains_key("interaction_token"), "BB60-20: InteractionEvent must have 'interaction_token'");
+    assert!(!obj.contains_key("token"), "BB60-20: InteractionEvent must NOT have bare 'token' field");
+    uuid::Uuid::parse_str(obj["event_id"].as_str().unwrap()).expect("event_id should be a UUID");
+}
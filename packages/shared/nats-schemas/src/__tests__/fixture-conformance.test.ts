@@ -16,7 +16,7 @@ import { join, dirname } from 'node:path';
 import { fileURLToPath } from 'node:url';
 
 import { GatewayEventSchema } from '../schemas/gateway-event.js';
-import { InteractionPayloadSchema } from '../schemas/interaction-payload.js';
+import { InteractionEventSchema, InteractionPayloadSchema } from '../schemas/interaction-payload.js';
 import {
   GuildJoinDataSchema,
   GuildLeaveDataSchema,
@@ -24,6 +24,34 @@ import {
   MemberLeaveDataSchema,
   MemberUpdateDataSchema,
   InteractionCreateDataSchema,
+  VoiceStateUpdateDataSchema,
+  MessageUpdateDataSchema,
+  MessageEmbedUpdateDataSchema,
+  AutomodRuleDataSchema,
+  GuildAnomalyDataSchema,
+  ScheduledEventUserRemoveDataSchema,
+  PresenceUpdateDataSchema,
+  ShardReconnectRequestedDataSchema,
+  InteractionAutocompleteDataSchema,
+  MessageCreateDataSchema,
+  MemberOnboardingCompleteDataSchema,
+  GuildBanAddDataSchema,
+  ThreadListSyncDataSchema,
+  ReactionAddDataSchema,
+  GuildUpdateDataSchema,
+  MessagePinUpdateDataSchema,
+  GuildBanRemoveDataSchema,
+  MemberTimeoutExpiredDataSchema,
+  InteractionModalDataSchema,
+  ThreadDataSchema,
+  ThreadDeleteDataSchema,
+  MessageDeleteDataSchema,
+  MessageDeleteBulkDataSchema,
+  ChannelUpdateDataSchema,
+  UserUpdateDataSchema,
+  MemberChunkDataSchema,
+  ReactionRemoveDataSchema,
+  TypingStartDataSchema,
 } from '../schemas/event-data.js';
 
 const __dirname = dirname(fileURLToPath(import.meta.url));
@@ -42,6 +70,49 @@ describe('Fixture conformance: GatewayEventSchema', () => {
     'member-leave',
     'member-update',
     'interaction-create',
+    'interaction-create-resolved',
+    'voice-state-update',
+    'interaction-create-component',
+    'message-update',
+    'message-embed-update',
+    'automod-rule-create',
+    'automod-rule-update',
+    'automod-rule-delete',
+    'guild-anomaly',
+    'scheduled-event-user-remove',
+    'presence-update',
+    'shard-reconnect-requested',
+    'interaction-autocomplete',
+    'message-create-webhook',
+    'member-onboarding-complete',
+    'message-create-command-response',
+    'message-create-embed',
+    'member-join-flags',
+    'guild-ban-add',
+    'thread-list-sync',
+    'reaction-add-burst',
+    'guild-update',
+    'message-create',
+    'message-pin-update',
+    'guild-join-large',
+    'guild-ban-remove',
+    'member-timeout-expired',
+    'interaction-modal',
+    'thread-create',
+    'thread-update',
+    'thread-delete',
+    'guild-join-vanity',
+    'message-delete',
+    'message-delete-bulk',
+    'channel-update',
+    'user-update',
+    'member-chunk',
+    'reaction-add-custom',
+    'reaction-remove',
+    'presence-update-spotify',
+    'typing-start',
+    'guild-join-onboarding',
+    'message-create-components',
   ];
 
   for (const name of fixtures) {
@@ -89,6 +160,267 @@ describe('Fixture conformance: event-specific data schemas', () => {
     const result = InteractionCreateDataSchema.safeParse(fixture.data);
     expect(result.success).toBe(true);
   });
+
+  it('interaction-create-resolved data carries the resolved target user', () => {
+    const fixture = loadFixture('interaction-create-resolved') as { data: unknown };
+    const result = InteractionCreateDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+    expect(result.data?.resolved?.users['777777777777777777']?.username).toBe('targetuser');
+  });
+
+  it('interaction-create-component data carries the originating message and custom_id', () => {
+    const fixture = loadFixture('interaction-create-component') as { data: unknown };
+    const result = InteractionCreateDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+    expect(result.data?.message_id).toBe('888888888888888888');
+    expect(result.data?.custom_id).toBe('verify:start');
+  });
+
+  it('voice-state-update data validates against VoiceStateUpdateDataSchema', () => {
+    const fixture = loadFixture('voice-state-update') as { data: unknown };
+    const result = VoiceStateUpdateDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('message-update data validates against MessageUpdateDataSchema', () => {
+    const fixture = loadFixture('message-update') as { data: unknown };
+    const result = MessageUpdateDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('message-embed-update data validates against MessageEmbedUpdateDataSchema', () => {
+    const fixture = loadFixture('message-embed-update') as { data: unknown };
+    const result = MessageEmbedUpdateDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('automod-rule-create data validates against AutomodRuleDataSchema', () => {
+    const fixture = loadFixture('automod-rule-create') as { data: unknown };
+    const result = AutomodRuleDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('automod-rule-update data validates against AutomodRuleDataSchema', () => {
+    const fixture = loadFixture('automod-rule-update') as { data: unknown };
+    const result = AutomodRuleDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('automod-rule-delete data validates against AutomodRuleDataSchema', () => {
+    const fixture = loadFixture('automod-rule-delete') as { data: unknown };
+    const result = AutomodRuleDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('guild-anomaly data validates against GuildAnomalyDataSchema', () => {
+    const fixture = loadFixture('guild-anomaly') as { data: unknown };
+    const result = GuildAnomalyDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('scheduled-event-user-remove data validates against ScheduledEventUserRemoveDataSchema', () => {
+    const fixture = loadFixture('scheduled-event-user-remove') as { data: unknown };
+    const result = ScheduledEventUserRemoveDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('presence-update data validates against PresenceUpdateDataSchema', () => {
+    const fixture = loadFixture('presence-update') as { data: unknown };
+    const result = PresenceUpdateDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('shard-reconnect-requested data validates against ShardReconnectRequestedDataSchema', () => {
+    const fixture = loadFixture('shard-reconnect-requested') as { data: unknown };
+    const result = ShardReconnectRequestedDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('interaction-autocomplete data validates against InteractionAutocompleteDataSchema', () => {
+    const fixture = loadFixture('interaction-autocomplete') as { data: unknown };
+    const result = InteractionAutocompleteDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('message-create-webhook data validates against MessageCreateDataSchema', () => {
+    const fixture = loadFixture('message-create-webhook') as { data: unknown };
+    const result = MessageCreateDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('member-onboarding-complete data validates against MemberOnboardingCompleteDataSchema', () => {
+    const fixture = loadFixture('member-onboarding-complete') as { data: unknown };
+    const result = MemberOnboardingCompleteDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('message-create-command-response data validates against MessageCreateDataSchema', () => {
+    const fixture = loadFixture('message-create-command-response') as { data: unknown };
+    const result = MessageCreateDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('message-create-embed data validates against MessageCreateDataSchema', () => {
+    const fixture = loadFixture('message-create-embed') as { data: unknown };
+    const result = MessageCreateDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('member-join-flags data validates against MemberJoinDataSchema', () => {
+    const fixture = loadFixture('member-join-flags') as { data: unknown };
+    const result = MemberJoinDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('guild-ban-add data validates against GuildBanAddDataSchema', () => {
+    const fixture = loadFixture('guild-ban-add') as { data: unknown };
+    const result = GuildBanAddDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('thread-list-sync data validates against ThreadListSyncDataSchema', () => {
+    const fixture = loadFixture('thread-list-sync') as { data: unknown };
+    const result = ThreadListSyncDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('reaction-add-burst data validates against ReactionAddDataSchema', () => {
+    const fixture = loadFixture('reaction-add-burst') as { data: unknown };
+    const result = ReactionAddDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('guild-update data validates against GuildUpdateDataSchema', () => {
+    const fixture = loadFixture('guild-update') as { data: unknown };
+    const result = GuildUpdateDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('message-create data validates against MessageCreateDataSchema', () => {
+    const fixture = loadFixture('message-create') as { data: unknown };
+    const result = MessageCreateDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('message-pin-update data validates against MessagePinUpdateDataSchema', () => {
+    const fixture = loadFixture('message-pin-update') as { data: unknown };
+    const result = MessagePinUpdateDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('guild-join-large data validates against GuildJoinDataSchema', () => {
+    const fixture = loadFixture('guild-join-large') as { data: unknown };
+    const result = GuildJoinDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('guild-ban-remove data validates against GuildBanRemoveDataSchema', () => {
+    const fixture = loadFixture('guild-ban-remove') as { data: unknown };
+    const result = GuildBanRemoveDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('member-timeout-expired data validates against MemberTimeoutExpiredDataSchema', () => {
+    const fixture = loadFixture('member-timeout-expired') as { data: unknown };
+    const result = MemberTimeoutExpiredDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('interaction-modal data validates against InteractionModalDataSchema', () => {
+    const fixture = loadFixture('interaction-modal') as { data: unknown };
+    const result = InteractionModalDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('thread-create data validates against ThreadDataSchema', () => {
+    const fixture = loadFixture('thread-create') as { data: unknown };
+    const result = ThreadDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('thread-update data validates against ThreadDataSchema', () => {
+    const fixture = loadFixture('thread-update') as { data: unknown };
+    const result = ThreadDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('thread-delete data validates against ThreadDeleteDataSchema', () => {
+    const fixture = loadFixture('thread-delete') as { data: unknown };
+    const result = ThreadDeleteDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('guild-join-vanity data validates against GuildJoinDataSchema', () => {
+    const fixture = loadFixture('guild-join-vanity') as { data: unknown };
+    const result = GuildJoinDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('message-delete data validates against MessageDeleteDataSchema', () => {
+    const fixture = loadFixture('message-delete') as { data: unknown };
+    const result = MessageDeleteDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('message-delete-bulk data validates against MessageDeleteBulkDataSchema', () => {
+    const fixture = loadFixture('message-delete-bulk') as { data: unknown };
+    const result = MessageDeleteBulkDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('channel-update data validates against ChannelUpdateDataSchema', () => {
+    const fixture = loadFixture('channel-update') as { data: unknown };
+    const result = ChannelUpdateDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('user-update data validates against UserUpdateDataSchema', () => {
+    const fixture = loadFixture('user-update') as { data: unknown };
+    const result = UserUpdateDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('member-chunk data validates against MemberChunkDataSchema', () => {
+    const fixture = loadFixture('member-chunk') as { data: unknown };
+    const result = MemberChunkDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('reaction-add-custom data validates against ReactionAddDataSchema', () => {
+    const fixture = loadFixture('reaction-add-custom') as { data: unknown };
+    const result = ReactionAddDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('reaction-remove data validates against ReactionRemoveDataSchema', () => {
+    const fixture = loadFixture('reaction-remove') as { data: unknown };
+    const result = ReactionRemoveDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('presence-update-spotify data validates against PresenceUpdateDataSchema', () => {
+    const fixture = loadFixture('presence-update-spotify') as { data: unknown };
+    const result = PresenceUpdateDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('typing-start data validates against TypingStartDataSchema', () => {
+    const fixture = loadFixture('typing-start') as { data: unknown };
+    const result = TypingStartDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('guild-join-onboarding data validates against GuildJoinDataSchema', () => {
+    const fixture = loadFixture('guild-join-onboarding') as { data: unknown };
+    const result = GuildJoinDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
+
+  it('message-create-components data validates against MessageCreateDataSchema', () => {
+    const fixture = loadFixture('message-create-components') as { data: unknown };
+    const result = MessageCreateDataSchema.safeParse(fixture.data);
+    expect(result.success).toBe(true);
+  });
 });
 
 describe('Fixture conformance: InteractionPayloadSchema', () => {
@@ -99,6 +431,20 @@ describe('Fixture conformance: InteractionPayloadSchema', () => {
   });
 });
 
+describe('Fixture conformance: InteractionEventSchema', () => {
+  it('interaction-event.json validates against InteractionEventSchema', () => {
+    const result = InteractionEventSchema.safeParse(loadFixture('interaction-event'));
+    expect(result.success).toBe(true);
+  });
+
+  it('rejects a bare "token" field (BB60-20)', () => {
+    const fixture = loadFixture('interaction-event') as Record<string, unknown>;
+    const { interaction_token, ...rest } = fixture;
+    const result = InteractionEventSchema.safeParse({ ...rest, token: interaction_token });
+    expect(result.success).toBe(false);
+  });
+});
+
 describe('BB60-20 regression guard', () => {
   it('interaction fixture uses interaction_token (NOT token)', () => {
     const fixture = loadFixture('interaction-create') as {
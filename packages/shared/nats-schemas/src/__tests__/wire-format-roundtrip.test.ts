@@ -46,6 +46,49 @@ const ALL_FIXTURES = [
   'member-leave',
   'member-update',
   'interaction-create',
+  'interaction-create-resolved',
+  'voice-state-update',
+  'interaction-create-component',
+  'message-update',
+  'message-embed-update',
+  'automod-rule-create',
+  'automod-rule-update',
+  'automod-rule-delete',
+  'guild-anomaly',
+  'scheduled-event-user-remove',
+  'presence-update',
+  'shard-reconnect-requested',
+  'interaction-autocomplete',
+  'message-create-webhook',
+  'member-onboarding-complete',
+  'message-create-command-response',
+  'message-create-embed',
+  'member-join-flags',
+  'guild-ban-add',
+  'thread-list-sync',
+  'reaction-add-burst',
+  'guild-update',
+  'message-create',
+  'message-pin-update',
+  'guild-join-large',
+  'guild-ban-remove',
+  'member-timeout-expired',
+  'interaction-modal',
+  'thread-create',
+  'thread-update',
+  'thread-delete',
+  'guild-join-vanity',
+  'message-delete',
+  'message-delete-bulk',
+  'channel-update',
+  'user-update',
+  'member-chunk',
+  'reaction-add-custom',
+  'reaction-remove',
+  'presence-update-spotify',
+  'typing-start',
+  'guild-join-onboarding',
+  'message-create-components',
 ];
 
 describe('Wire format round-trip (TypeScript side)', () => {
@@ -124,7 +167,16 @@ describe('Wire format round-trip (TypeScript side)', () => {
     });
 
     it('KNOWN_EVENT_TYPES has expected length', () => {
-      expect(KNOWN_EVENT_TYPES.length).toBe(7);
+      expect(KNOWN_EVENT_TYPES.length).toBe(37);
+    });
+  });
+
+  describe('schema_version', () => {
+    it('legacy fixtures without the field parse as version 0', () => {
+      for (const name of ALL_FIXTURES) {
+        const parsed = GatewayEventSchema.parse(loadFixture(name));
+        expect(parsed.schema_version).toBe(0);
+      }
     });
   });
 
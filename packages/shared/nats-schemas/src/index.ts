@@ -7,6 +7,7 @@
  */
 
 export {
+  CURRENT_SCHEMA_VERSION,
   GatewayEventSchema,
   KNOWN_EVENT_TYPES,
   isKnownEventType,
@@ -19,8 +20,10 @@ export {
   InteractionTransportPayloadSchema,
   InteractionTransportDataSchema,
   EnrichedInteractionDataSchema,
+  InteractionEventSchema,
   type InteractionPayload,
   type InteractionTransportPayload,
+  type InteractionEvent,
 } from './schemas/interaction-payload.js';
 export {
   GuildJoinDataSchema,
@@ -29,12 +32,74 @@ export {
   MemberLeaveDataSchema,
   MemberUpdateDataSchema,
   InteractionCreateDataSchema,
+  InteractionResolvedSchema,
+  VoiceStateUpdateDataSchema,
+  MessageUpdateDataSchema,
+  MessageEmbedUpdateDataSchema,
+  AutomodRuleDataSchema,
+  GuildAnomalyDataSchema,
+  ScheduledEventUserRemoveDataSchema,
+  PresenceUpdateDataSchema,
+  PresenceActivitySchema,
+  ShardReconnectRequestedDataSchema,
+  InteractionAutocompleteDataSchema,
+  MessageCreateDataSchema,
+  MemberOnboardingCompleteDataSchema,
+  GuildBanAddDataSchema,
+  GuildBanRemoveDataSchema,
+  ThreadListSyncDataSchema,
+  ReactionAddDataSchema,
+  ReactionRemoveDataSchema,
+  GuildUpdateDataSchema,
+  MessagePinUpdateDataSchema,
+  MemberTimeoutExpiredDataSchema,
+  SerializationErrorDataSchema,
+  InteractionModalDataSchema,
+  ThreadDataSchema,
+  ThreadDeleteDataSchema,
+  MessageDeleteDataSchema,
+  MessageDeleteBulkDataSchema,
+  ChannelUpdateDataSchema,
+  UserUpdateDataSchema,
+  MemberChunkDataSchema,
+  TypingStartDataSchema,
   type GuildJoinData,
   type GuildLeaveData,
   type MemberJoinData,
   type MemberLeaveData,
   type MemberUpdateData,
   type InteractionCreateData,
+  type InteractionResolved,
+  type VoiceStateUpdateData,
+  type MessageUpdateData,
+  type MessageEmbedUpdateData,
+  type AutomodRuleData,
+  type GuildAnomalyData,
+  type ScheduledEventUserRemoveData,
+  type PresenceUpdateData,
+  type PresenceActivity,
+  type ShardReconnectRequestedData,
+  type InteractionAutocompleteData,
+  type MessageCreateData,
+  type MemberOnboardingCompleteData,
+  type GuildBanAddData,
+  type GuildBanRemoveData,
+  type ThreadListSyncData,
+  type ReactionAddData,
+  type ReactionRemoveData,
+  type GuildUpdateData,
+  type MessagePinUpdateData,
+  type MemberTimeoutExpiredData,
+  type SerializationErrorData,
+  type InteractionModalData,
+  type ThreadData,
+  type ThreadDeleteData,
+  type MessageDeleteData,
+  type MessageDeleteBulkData,
+  type ChannelUpdateData,
+  type UserUpdateData,
+  type MemberChunkData,
+  type TypingStartData,
 } from './schemas/event-data.js';
 export {
   UsageFinalizedSchema,
@@ -22,12 +22,27 @@ import { z } from 'zod';
  *
  * This mirrors Stripe's API team pattern where the public SDK validates a subset
  * of the wire payload and forwards the rest untouched. (BB60-S5-2)
+ *
+ * `large`, `member_count` and `presence_count` are chunking hints: a large
+ * guild arrives without its offline members, so request member chunks.
+ * `system_channel_id` (join messages), `rules_channel_id` and
+ * `public_updates_channel_id` route welcome and onboarding messages; each
+ * is null when the guild hasn't set one.
  */
 export const GuildJoinDataSchema = z
   .object({
     id: z.string(),
     name: z.string().optional(),
-    member_count: z.number().int().optional(),
+    member_count: z.number().int().nullable().optional(),
+    large: z.boolean().optional(),
+    presence_count: z.number().int().nonnegative().optional(),
+    /** Vanity invite code (discord.gg/<code>); null without one */
+    vanity_url_code: z.string().nullable().optional(),
+    /** Banner image hash; null without one */
+    banner: z.string().nullable().optional(),
+    system_channel_id: z.string().nullable().optional(),
+    rules_channel_id: z.string().nullable().optional(),
+    public_updates_channel_id: z.string().nullable().optional(),
   })
   .passthrough();
 
@@ -42,52 +57,652 @@ export const GuildLeaveDataSchema = z.object({
 
 export type GuildLeaveData = z.infer<typeof GuildLeaveDataSchema>;
 
+/**
+ * data payload for event_type = "guild.anomaly"
+ *
+ * Control event from the gateway's rate detector: `count` events of
+ * `event_type` in one `window_secs` window exceeded `threshold`.
+ */
+export const GuildAnomalyDataSchema = z.object({
+  event_type: z.string(),
+  count: z.number().int(),
+  threshold: z.number().int(),
+  window_secs: z.number(),
+  rate_per_sec: z.number(),
+});
+
+export type GuildAnomalyData = z.infer<typeof GuildAnomalyDataSchema>;
+
+/**
+ * data payload for event_type = "guild.ban.add"
+ *
+ * The banned user is the envelope's user_id. Older gateways send null.
+ */
+export const GuildBanAddDataSchema = z.union([
+  z.null(),
+  z.object({
+    username: z.string(),
+  }),
+]);
+
+export type GuildBanAddData = z.infer<typeof GuildBanAddDataSchema>;
+
+/**
+ * data payload for event_type = "guild.ban.remove"
+ *
+ * The unbanned user is the envelope's user_id.
+ */
+export const GuildBanRemoveDataSchema = z.object({
+  username: z.string(),
+});
+
+export type GuildBanRemoveData = z.infer<typeof GuildBanRemoveDataSchema>;
+
+/**
+ * data payload for event_type = "guild.update"
+ *
+ * Compact guild settings. With SERIALIZE_GUILD_UPDATE_DELTAS only the
+ * fields changed since the guild's last update are present (a JSON merge
+ * patch: cleared fields are null), so every field is optional.
+ */
+export const GuildUpdateDataSchema = z.object({
+  name: z.string().optional(),
+  description: z.string().nullable().optional(),
+  icon: z.string().nullable().optional(),
+  banner: z.string().nullable().optional(),
+  vanity_url_code: z.string().nullable().optional(),
+  owner_id: z.string().optional(),
+  features: z.array(z.string()).optional(),
+  preferred_locale: z.string().optional(),
+  premium_tier: z.number().int().optional(),
+  verification_level: z.number().int().optional(),
+  system_channel_id: z.string().nullable().optional(),
+  rules_channel_id: z.string().nullable().optional(),
+  public_updates_channel_id: z.string().nullable().optional(),
+});
+
+export type GuildUpdateData = z.infer<typeof GuildUpdateDataSchema>;
+
 // ---------------------------------------------------------------------------
 // Member events
 // ---------------------------------------------------------------------------
 
 /**
  * data payload for event_type = "member.join"
+ *
+ * `flags` decodes Discord's member flags; `did_rejoin` and
+ * `bypassed_verification` are the anti-raid signals.
  */
 export const MemberJoinDataSchema = z.object({
   username: z.string(),
+  /** Display name; null when the user hasn't set one */
+  global_name: z.string().nullable(),
+  /** Legacy discriminator; null for accounts migrated to unique usernames */
   discriminator: z.number().int().nullable(),
+  flags: z.object({
+    did_rejoin: z.boolean(),
+    completed_onboarding: z.boolean(),
+    started_onboarding: z.boolean(),
+    bypassed_verification: z.boolean(),
+  }),
 });
 
 export type MemberJoinData = z.infer<typeof MemberJoinDataSchema>;
 
 /**
  * data payload for event_type = "member.leave"
- * Rust sends Value::Null — an empty object or null.
+ * Rust sends Value::Null — an empty object or null. With
+ * SERIALIZE_LEAVE_ON_BAN, the leave the gateway emits for a ban carries
+ * `reason: "banned"` and the `guild.ban.add` event_id.
  */
-export const MemberLeaveDataSchema = z.union([z.null(), z.object({})]);
+export const MemberLeaveDataSchema = z.union([
+  z.null(),
+  z.object({
+    reason: z.literal('banned').optional(),
+    ban_event_id: z.string().optional(),
+  }),
+]);
 
 export type MemberLeaveData = z.infer<typeof MemberLeaveDataSchema>;
 
 /**
  * data payload for event_type = "member.update"
+ *
+ * `roles` is absent when the gateway runs with SERIALIZE_OMIT_UNCHANGED_ROLES
+ * and the update didn't touch roles; `[]` always means "all roles removed".
+ * With SERIALIZE_ROLE_DIFFS, `roles_added`/`roles_removed` hold the change
+ * from the member's last-known roles (absent when those are unknown).
  */
 export const MemberUpdateDataSchema = z.object({
-  roles: z.array(z.string()),
+  roles: z.array(z.string()).optional(),
+  roles_added: z.array(z.string()).optional(),
+  roles_removed: z.array(z.string()).optional(),
   nick: z.string().nullable(),
+  global_name: z.string().nullable(),
+  /** ISO 8601 end of the member's timeout; null when not timed out */
+  communication_disabled_until: z.string().nullable().optional(),
 });
 
 export type MemberUpdateData = z.infer<typeof MemberUpdateDataSchema>;
 
+/**
+ * data payload for event_type = "member.onboarding.complete"
+ *
+ * Derived by the gateway from a member.update that sets the
+ * COMPLETED_ONBOARDING member flag; the member is the envelope's user_id.
+ */
+export const MemberOnboardingCompleteDataSchema = z.null();
+
+export type MemberOnboardingCompleteData = z.infer<typeof MemberOnboardingCompleteDataSchema>;
+
+/**
+ * data payload for event_type = "member.timeout.expired"
+ *
+ * Derived by the gateway (SERIALIZE_TIMEOUT_EXPIRY) when a timeout seen in
+ * a member.update lifts; Discord sends nothing then. The member is the
+ * envelope's user_id and `timestamp` is when the timeout lifted.
+ */
+export const MemberTimeoutExpiredDataSchema = z.null();
+
+export type MemberTimeoutExpiredData = z.infer<typeof MemberTimeoutExpiredDataSchema>;
+
+/**
+ * data payload for event_type = "member.chunk"
+ *
+ * One batch of a guild member request. Chunks of one request share a
+ * `nonce` (null when the request set none); the set is complete once all
+ * `chunk_count` chunks (indexes 0..chunk_count-1) have arrived.
+ */
+export const MemberChunkDataSchema = z.object({
+  members: z.array(
+    z.object({
+      id: z.string(),
+      username: z.string(),
+      global_name: z.string().nullable(),
+      roles: z.array(z.string()),
+    }),
+  ),
+  chunk_index: z.number().int().nonnegative(),
+  chunk_count: z.number().int().positive(),
+  nonce: z.string().nullable(),
+});
+
+export type MemberChunkData = z.infer<typeof MemberChunkDataSchema>;
+
 // ---------------------------------------------------------------------------
 // Interaction events
 // ---------------------------------------------------------------------------
 
+/**
+ * Compact `resolved` map for slash command options (opt-in via
+ * SERIALIZE_INCLUDE_RESOLVED). Keyed by Discord snowflake.
+ */
+export const InteractionResolvedSchema = z.object({
+  users: z.record(
+    z.object({
+      username: z.string(),
+      global_name: z.string().nullable(),
+      bot: z.boolean(),
+    }),
+  ),
+  members: z.record(
+    z.object({
+      nick: z.string().nullable(),
+      roles: z.array(z.string()),
+    }),
+  ),
+  channels: z.record(
+    z.object({
+      name: z.string(),
+      type: z.number().int(),
+    }),
+  ),
+  roles: z.record(z.object({ name: z.string() })),
+});
+
+export type InteractionResolved = z.infer<typeof InteractionResolvedSchema>;
+
 /**
  * data payload for event_type = "interaction.create"
  *
- * Maps directly to the serde_json::json! block in serialize.rs lines 133-137.
+ * Maps directly to the serde_json::json! block in serialize.rs.
  * Note: field is "interaction_token" (NOT "token") per BB60-20 fix.
  */
 export const InteractionCreateDataSchema = z.object({
   interaction_id: z.string(),
   interaction_type: z.string(),
   interaction_token: z.string(),
+  resolved: InteractionResolvedSchema.optional(),
+  /** Component interactions only: the component's custom_id */
+  custom_id: z.string().optional(),
+  /** Component interactions only: the message the component is attached to */
+  message_id: z.string().optional(),
 });
 
 export type InteractionCreateData = z.infer<typeof InteractionCreateDataSchema>;
+
+/**
+ * interaction.autocomplete: the user is typing into a command option.
+ * `focused` is that option's name (nested subcommand options included) and
+ * `value` its partial input; respond with suggestions via interaction_token.
+ */
+export const InteractionAutocompleteDataSchema = z.object({
+  interaction_id: z.string(),
+  interaction_token: z.string(),
+  command_name: z.string().nullable(),
+  focused: z.string().nullable(),
+  value: z.string().nullable(),
+});
+
+export type InteractionAutocompleteData = z.infer<typeof InteractionAutocompleteDataSchema>;
+
+/**
+ * data payload for event_type = "interaction.modal"
+ *
+ * A submitted modal. `fields` maps each input's custom_id to its value:
+ * a string for text inputs, an array of the chosen values (or ids) for
+ * selects and file uploads, across every row of the modal.
+ */
+export const InteractionModalDataSchema = z.object({
+  interaction_id: z.string(),
+  interaction_token: z.string(),
+  custom_id: z.string().nullable(),
+  fields: z.record(z.union([z.string(), z.array(z.string())])),
+});
+
+export type InteractionModalData = z.infer<typeof InteractionModalDataSchema>;
+
+// ---------------------------------------------------------------------------
+// Voice events
+// ---------------------------------------------------------------------------
+
+/**
+ * data payload for event_type = "voice.state.update"
+ *
+ * The envelope `channel_id` is null when the user left voice.
+ * `suppress` is true for stage audience members, false for speakers.
+ */
+export const VoiceStateUpdateDataSchema = z.object({
+  mute: z.boolean(),
+  deaf: z.boolean(),
+  self_mute: z.boolean(),
+  self_deaf: z.boolean(),
+  self_stream: z.boolean(),
+  self_video: z.boolean(),
+  suppress: z.boolean(),
+});
+
+export type VoiceStateUpdateData = z.infer<typeof VoiceStateUpdateDataSchema>;
+
+// ---------------------------------------------------------------------------
+// Message events
+// ---------------------------------------------------------------------------
+
+/**
+ * data payload for event_type = "message.update" (a user edit)
+ *
+ * `content` is the edited text, present only when the gateway captures
+ * message content.
+ */
+export const MessageUpdateDataSchema = z.object({
+  message_id: z.string(),
+  edited_timestamp: z.string().nullable(),
+  content: z.string().optional(),
+});
+
+export type MessageUpdateData = z.infer<typeof MessageUpdateDataSchema>;
+
+/**
+ * data payload for event_type = "message.embed.update"
+ *
+ * Discord unfurled a link into an embed; the author didn't edit anything.
+ */
+export const MessageEmbedUpdateDataSchema = z.object({
+  message_id: z.string(),
+  embeds: z.array(
+    z.object({
+      type: z.string(),
+      url: z.string().nullable(),
+      title: z.string().nullable(),
+    }),
+  ),
+});
+
+export type MessageEmbedUpdateData = z.infer<typeof MessageEmbedUpdateDataSchema>;
+
+/**
+ * data payload for event_type = "message.create"
+ *
+ * Webhook messages set `is_webhook` and `webhook_id`; their envelope
+ * user_id is null because the author isn't a real member. Responses to an
+ * interaction carry `interaction_metadata` (`type` is Discord's numeric
+ * interaction type, 2 = application command; `user_id` ran it).
+ * `embeds` is a flat summary of each embed (empty when there are none);
+ * `author` is the embed author's name.
+ */
+export const MessageCreateDataSchema = z.object({
+  message_id: z.string(),
+  is_webhook: z.boolean(),
+  /** Author is a bot (webhooks included); cheap filter for workers */
+  is_bot: z.boolean(),
+  /** Attachment URLs */
+  attachments: z.array(z.string()),
+  /** Opt-in (SERIALIZE_CAPTURE_MESSAGE_CONTENT); absent otherwise */
+  content: z.string().optional(),
+  embeds: z.array(
+    z.object({
+      type: z.string(),
+      title: z.string().nullable(),
+      url: z.string().nullable(),
+      description: z.string().nullable(),
+      author: z.string().nullable(),
+      image_url: z.string().nullable(),
+    }),
+  ),
+  webhook_id: z.string().optional(),
+  /**
+   * Opt-in (SERIALIZE_INCLUDE_COMPONENTS): buttons and select menus in the
+   * message's action rows. `type` is Discord's component type; `custom_id`
+   * is null for link buttons, `label` for selects and icon-only buttons.
+   */
+  components: z
+    .array(
+      z.object({
+        type: z.number().int(),
+        custom_id: z.string().nullable(),
+        label: z.string().nullable(),
+      }),
+    )
+    .optional(),
+  interaction_metadata: z
+    .object({
+      id: z.string(),
+      type: z.number().int(),
+      user_id: z.string(),
+    })
+    .optional(),
+  /** Opt-in (SERIALIZE_DETECT_LANGUAGE); null for short content */
+  detected_language: z
+    .object({ code: z.string(), confidence: z.number() })
+    .nullable()
+    .optional(),
+});
+
+export type MessageCreateData = z.infer<typeof MessageCreateDataSchema>;
+
+/**
+ * data payload for event_type = "reaction.add"
+ *
+ * `emoji` is the unicode emoji or custom emoji name (null if the custom
+ * emoji was deleted); `emoji_id` is set for custom emoji. `burst` marks a
+ * super reaction, which carries its `burst_colors` ("#RRGGBB").
+ */
+export const ReactionAddDataSchema = z.object({
+  message_id: z.string(),
+  emoji: z.string().nullable(),
+  emoji_id: z.string().nullable(),
+  burst: z.boolean(),
+  burst_colors: z.array(z.string()).optional(),
+});
+
+export type ReactionAddData = z.infer<typeof ReactionAddDataSchema>;
+
+/**
+ * data payload for event_type = "reaction.remove"
+ *
+ * Same emoji shape as reaction.add: match custom emoji on `emoji_id`,
+ * unicode emoji (`emoji_id` null) on `emoji`.
+ */
+export const ReactionRemoveDataSchema = z.object({
+  message_id: z.string(),
+  emoji: z.string().nullable(),
+  emoji_id: z.string().nullable(),
+  burst: z.boolean(),
+});
+
+export type ReactionRemoveData = z.infer<typeof ReactionRemoveDataSchema>;
+
+/**
+ * data payload for event_type = "message.pin.update"
+ *
+ * A message was pinned or unpinned without being edited. Envelope
+ * user_id is null: Discord doesn't say who pinned it.
+ */
+export const MessagePinUpdateDataSchema = z.object({
+  message_id: z.string(),
+  pinned: z.boolean(),
+});
+
+export type MessagePinUpdateData = z.infer<typeof MessagePinUpdateDataSchema>;
+
+/**
+ * data payload for event_type = "message.delete"
+ */
+export const MessageDeleteDataSchema = z.object({
+  message_id: z.string(),
+});
+
+export type MessageDeleteData = z.infer<typeof MessageDeleteDataSchema>;
+
+/**
+ * data payload for event_type = "message.delete.bulk"
+ *
+ * One event per purge, carrying every deleted message id.
+ */
+export const MessageDeleteBulkDataSchema = z.object({
+  message_ids: z.array(z.string()),
+});
+
+export type MessageDeleteBulkData = z.infer<typeof MessageDeleteBulkDataSchema>;
+
+// ---------------------------------------------------------------------------
+// AutoMod events
+// ---------------------------------------------------------------------------
+
+/**
+ * data payload for event_type = "automod.rule.create" | "automod.rule.update"
+ * | "automod.rule.delete"
+ *
+ * `trigger_type` is Discord's numeric trigger type (1 = keyword, 3 = spam,
+ * 4 = keyword preset, 5 = mention spam, 6 = member profile).
+ */
+export const AutomodRuleDataSchema = z.object({
+  id: z.string(),
+  name: z.string(),
+  trigger_type: z.number().int(),
+  enabled: z.boolean(),
+});
+
+export type AutomodRuleData = z.infer<typeof AutomodRuleDataSchema>;
+
+// ---------------------------------------------------------------------------
+// Scheduled events
+// ---------------------------------------------------------------------------
+
+/**
+ * scheduled_event.user_remove: a user withdrew their RSVP. The user is the
+ * envelope's user_id; consumers decrement the event's interested count.
+ */
+export const ScheduledEventUserRemoveDataSchema = z.object({
+  scheduled_event_id: z.string(),
+});
+
+export type ScheduledEventUserRemoveData = z.infer<typeof ScheduledEventUserRemoveDataSchema>;
+
+// ---------------------------------------------------------------------------
+// Presence events
+// ---------------------------------------------------------------------------
+
+/**
+ * Primary (first non-custom-status) activity of a presence. `type` is
+ * Discord's activity type (0 playing, 1 streaming, 2 listening, 3 watching,
+ * 5 competing); for Spotify, `details` is the track and `state` the artist.
+ * `party_size` is [current, max].
+ */
+export const PresenceActivitySchema = z.object({
+  name: z.string(),
+  type: z.number().int().nonnegative(),
+  details: z.string().nullable(),
+  state: z.string().nullable(),
+  application_id: z.string().nullable(),
+  party_size: z.tuple([z.number().int().nonnegative(), z.number().int().nonnegative()]).nullable(),
+});
+
+export type PresenceActivity = z.infer<typeof PresenceActivitySchema>;
+
+/**
+ * presence.update: a member's status or activity changed. `activity` is
+ * absent when the member isn't doing anything. With presence compaction on,
+ * the gateway drops updates whose status matches the last one it saw, even
+ * if the activity changed.
+ */
+export const PresenceUpdateDataSchema = z.object({
+  status: z.enum(['online', 'idle', 'dnd', 'invisible', 'offline']),
+  activity: PresenceActivitySchema.optional(),
+});
+
+export type PresenceUpdateData = z.infer<typeof PresenceUpdateDataSchema>;
+
+// ---------------------------------------------------------------------------
+// Shard control events
+// ---------------------------------------------------------------------------
+
+/**
+ * shard.reconnect_requested: Discord asked the envelope's shard to reconnect
+ * (OP 7). Opt-in; carries no data.
+ */
+export const ShardReconnectRequestedDataSchema = z.null();
+
+export type ShardReconnectRequestedData = z.infer<typeof ShardReconnectRequestedDataSchema>;
+
+// ---------------------------------------------------------------------------
+// Thread events
+// ---------------------------------------------------------------------------
+
+/**
+ * data payload for event_type = "thread.list.sync"
+ *
+ * Active threads sent when a shard connects. `channel_ids` lists the parent
+ * channels synced (empty means the whole guild); `type` is Discord's
+ * numeric channel type (11 = public thread, 12 = private thread).
+ */
+export const ThreadListSyncDataSchema = z.object({
+  channel_ids: z.array(z.string()),
+  threads: z.array(
+    z.object({
+      id: z.string(),
+      parent_id: z.string().nullable(),
+      name: z.string().nullable(),
+      type: z.number().int(),
+      owner_id: z.string().nullable(),
+      archived: z.boolean(),
+      locked: z.boolean(),
+    }),
+  ),
+});
+
+export type ThreadListSyncData = z.infer<typeof ThreadListSyncDataSchema>;
+
+/**
+ * data payload for event_type = "thread.create" and "thread.update"
+ *
+ * The thread (also the envelope's channel_id), shaped like a
+ * thread.list.sync entry.
+ */
+export const ThreadDataSchema = z.object({
+  id: z.string(),
+  parent_id: z.string().nullable(),
+  name: z.string().nullable(),
+  type: z.number().int(),
+  owner_id: z.string().nullable(),
+  archived: z.boolean(),
+  locked: z.boolean(),
+});
+
+export type ThreadData = z.infer<typeof ThreadDataSchema>;
+
+/**
+ * data payload for event_type = "thread.delete"
+ *
+ * Discord sends only the ids and type of a deleted thread.
+ */
+export const ThreadDeleteDataSchema = z.object({
+  id: z.string(),
+  parent_id: z.string(),
+  type: z.number().int(),
+});
+
+export type ThreadDeleteData = z.infer<typeof ThreadDeleteDataSchema>;
+
+// ---------------------------------------------------------------------------
+// Serialization failures
+// ---------------------------------------------------------------------------
+
+/**
+ * data the gateway sends in place of a payload it failed to serialize, for
+ * any event_type. Check for it before the event's own schema and route the
+ * event to a dead-letter subject; `_error` is serde's message.
+ */
+export const SerializationErrorDataSchema = z.object({
+  _serialization_error: z.literal(true),
+  _error: z.string(),
+});
+
+export type SerializationErrorData = z.infer<typeof SerializationErrorDataSchema>;
+
+// ---------------------------------------------------------------------------
+// Channel events
+// ---------------------------------------------------------------------------
+
+/**
+ * data payload for event_type = "channel.update"
+ *
+ * `rate_limit_per_user` is the slowmode interval in seconds (0 = off);
+ * `type` is Discord's numeric channel type.
+ */
+export const ChannelUpdateDataSchema = z.object({
+  id: z.string(),
+  parent_id: z.string().nullable(),
+  name: z.string().nullable(),
+  type: z.number().int(),
+  topic: z.string().nullable(),
+  nsfw: z.boolean(),
+  rate_limit_per_user: z.number().int().nonnegative(),
+});
+
+export type ChannelUpdateData = z.infer<typeof ChannelUpdateDataSchema>;
+
+// ---------------------------------------------------------------------------
+// User events
+// ---------------------------------------------------------------------------
+
+/**
+ * data payload for event_type = "user.update"
+ *
+ * Discord sends USER_UPDATE only for the bot's own user. `avatar` and
+ * `banner` are image hashes (an `a_` prefix marks an animated image).
+ */
+export const UserUpdateDataSchema = z.object({
+  username: z.string(),
+  global_name: z.string().nullable(),
+  avatar: z.string().nullable(),
+  banner: z.string().nullable(),
+});
+
+export type UserUpdateData = z.infer<typeof UserUpdateDataSchema>;
+
+// ---------------------------------------------------------------------------
+// Typing events
+// ---------------------------------------------------------------------------
+
+/**
+ * data payload for event_type = "typing.start" (opt-in via TYPING_EVENTS)
+ *
+ * `started_at` is when Discord says the member started typing (Unix ms);
+ * the channel and member are in the envelope.
+ */
+export const TypingStartDataSchema = z.object({
+  started_at: z.number().int().nonnegative(),
+});
+
+export type TypingStartData = z.infer<typeof TypingStartDataSchema>;
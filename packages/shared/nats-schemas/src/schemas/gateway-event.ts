@@ -7,21 +7,53 @@
 
 import { z } from 'zod';
 
+/**
+ * Wire contract version these schemas describe (Rust CURRENT_SCHEMA_VERSION).
+ * Workers can reject payloads with a newer schema_version than they know.
+ */
+export const CURRENT_SCHEMA_VERSION = 1;
+
 /**
  * Base gateway event envelope.
  * Every message on the NATS wire matches this shape.
  *
  * Field-level contract (maps 1:1 to Rust GatewayEvent):
+ *   schema_version — wire contract version (CURRENT_SCHEMA_VERSION); 0 when
+ *                    absent (producers that predate the field)
  *   event_id       — UUIDv4 string
  *   event_type     — dot-separated event classifier (e.g. "guild.join")
  *   shard_id       — Discord shard that produced the event
- *   timestamp      — Unix epoch milliseconds (u64 in Rust → number in JS)
- *   guild_id       — nullable Discord snowflake
+ *   timestamp      — UTC Unix epoch milliseconds (u64 in Rust → number in
+ *                    JS), or microseconds when timestamp_unit is "micros";
+ *                    for message.create and interaction.* the message's or
+ *                    interaction's creation time from its snowflake
+ *   guild_id       — nullable Discord snowflake (a 32-char hex HMAC when the
+ *                    gateway hashes guild ids)
  *   channel_id     — nullable Discord snowflake
  *   user_id        — nullable Discord snowflake
  *   data           — event-specific payload (opaque at this level)
+ *   partition_key  — ordering key ("guild-<guild_id>", or "shard-<shard_id>"
+ *                    for guild-less events); absent from older producers
+ *   sequence       — optional per-shard monotonic sequence (replay cursor)
+ *   producer_epoch — optional Unix ms the shard's sequence started at; a
+ *                    later epoch means the sequence restarted from 1
+ *   shard_info     — optional sharding topology of the producing gateway
+ *   cache_epoch    — optional shard cache generation (guild.* events)
+ *   timestamp_ns   — optional monotonic nanoseconds (same-process ordering)
+ *   timestamp_unit — "micros" when the gateway scales timestamp to
+ *                    microseconds; absent means milliseconds
+ *   logical_clock  — optional cross-shard logical clock (total ordering)
+ *   internal_user_id — optional linked internal account id for user_id
+ *   producer_version — optional version of the producing gateway
+ *   schema_fingerprint — optional hash of the event type's data shape
+ *   intent         — optional gateway intent that enabled the event type
+ *   engagement_weight — optional configured ranking weight of the event type
+ *   gateway_op / gateway_seq — optional raw frame opcode and Discord sequence
+ *   data_ref       — optional event_id whose data was split off onto
+ *                    discord.payload.<data_ref> (data is then null)
  */
 export const GatewayEventSchema = z.object({
+  schema_version: z.number().int().nonnegative().default(0),
   event_id: z.string().uuid(),
   event_type: z.string().min(1),
   shard_id: z.number().int().nonnegative(),
@@ -42,6 +74,79 @@ export const GatewayEventSchema = z.object({
    * forward compatibility. (BB60-S5-4)
    */
   data: z.unknown(),
+  /**
+   * Ordering key: events sharing it must be processed in order. Every
+   * event of a guild shares one. Absent on events from older producers.
+   */
+  partition_key: z.string().optional(),
+  /** Per-shard monotonic sequence; absent on events from older gateways. */
+  sequence: z.number().int().nonnegative().optional(),
+  /**
+   * Unix ms the producing shard's sequence started at. Compare
+   * (producer_epoch, sequence): a later epoch restarts the sequence at 1.
+   */
+  producer_epoch: z.number().int().nonnegative().optional(),
+  /**
+   * Producing shard's position in the sharding topology (opt-in via
+   * SERIALIZE_INCLUDE_SHARD_INFO). A cluster is one gateway process.
+   */
+  shard_info: z
+    .object({
+      shard_id: z.number().int().nonnegative(),
+      shard_count: z.number().int().positive(),
+      cluster_id: z.number().int().nonnegative(),
+      cluster_count: z.number().int().positive(),
+    })
+    .optional(),
+  /**
+   * Fresh sessions the producing shard has seen (opt-in via
+   * SERIALIZE_INCLUDE_CACHE_EPOCH, guild.* events only). The shard rebuilds
+   * its guild cache on each new session; invalidate cached guild state when
+   * this changes.
+   */
+  cache_epoch: z.number().int().nonnegative().optional(),
+  /**
+   * Nanoseconds on the producing process's monotonic clock (opt-in via
+   * SERIALIZE_INCLUDE_TIMESTAMP_NS). Strictly increasing within one gateway
+   * process and reset on restart; use it to order events sharing a
+   * millisecond `timestamp`, not as wall-clock time.
+   */
+  timestamp_ns: z.number().int().nonnegative().optional(),
+  /**
+   * Unit of `timestamp` when not milliseconds (SERIALIZE_TIMESTAMP_UNIT);
+   * "micros" means `timestamp` is 1000x the millisecond value
+   */
+  timestamp_unit: z.string().optional(),
+  /**
+   * Logical clock shared across shards (opt-in via SERIALIZE_LOGICAL_CLOCK);
+   * strictly increasing across every shard sharing the clock
+   */
+  logical_clock: z.number().int().nonnegative().optional(),
+  /**
+   * Internal account id linked to user_id, in deployments that configure
+   * an id mapper. Absent (or null) when the user isn't linked.
+   */
+  internal_user_id: z.string().nullable().optional(),
+  /** Gateway crate version (opt-in via SERIALIZE_INCLUDE_PRODUCER_VERSION) */
+  producer_version: z.string().optional(),
+  /**
+   * Hash of this event type's declared data shape (opt-in via
+   * SERIALIZE_INCLUDE_SCHEMA_FINGERPRINT); changes when the type's fields do
+   */
+  schema_fingerprint: z.string().optional(),
+  /** Gateway intent behind the event type (opt-in via SERIALIZE_INCLUDE_INTENT) */
+  intent: z.string().optional(),
+  /** Ranking weight from the gateway's ENGAGEMENT_WEIGHTS table */
+  engagement_weight: z.number().nonnegative().optional(),
+  /** Raw gateway frame opcode (opt-in via SERIALIZE_PROTOCOL_DEBUG) */
+  gateway_op: z.number().int().nonnegative().optional(),
+  /** Discord dispatch sequence `s` (opt-in via SERIALIZE_PROTOCOL_DEBUG) */
+  gateway_seq: z.number().int().nonnegative().optional(),
+  /**
+   * Set when the gateway published an oversized `data` separately
+   * (PUBLISH_SPLIT_DATA_BYTES); fetch it from discord.payload.<data_ref>
+   */
+  data_ref: z.string().uuid().optional(),
 });
 
 /** Inferred TypeScript type from the Zod schema */
@@ -69,6 +174,35 @@ export const KNOWN_EVENT_TYPES = [
   'member.update',
   'interaction.create',
   'message.create',
+  'voice.state.update',
+  'message.update',
+  'message.embed.update',
+  'automod.rule.create',
+  'automod.rule.update',
+  'automod.rule.delete',
+  'guild.anomaly',
+  'scheduled_event.user_remove',
+  'presence.update',
+  'shard.reconnect_requested',
+  'interaction.autocomplete',
+  'member.onboarding.complete',
+  'guild.ban.add',
+  'thread.list.sync',
+  'reaction.add',
+  'message.pin.update',
+  'guild.ban.remove',
+  'member.timeout.expired',
+  'interaction.modal',
+  'thread.create',
+  'thread.update',
+  'thread.delete',
+  'message.delete',
+  'message.delete.bulk',
+  'channel.update',
+  'user.update',
+  'member.chunk',
+  'reaction.remove',
+  'typing.start',
 ] as const;
 
 export type KnownEventType = (typeof KNOWN_EVENT_TYPES)[number];
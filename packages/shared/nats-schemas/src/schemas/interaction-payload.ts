@@ -82,3 +82,38 @@ export const InteractionPayloadSchema = z.object({
 });
 
 export type InteractionPayload = z.infer<typeof InteractionPayloadSchema>;
+
+// ---------------------------------------------------------------------------
+// Dedicated interaction event
+// ---------------------------------------------------------------------------
+
+/**
+ * InteractionEvent — the gateway's dedicated interaction payload (Rust
+ * `serialize_interaction`), with command routing fields extracted at the
+ * source rather than nested in `data`. Not a GatewayEvent: there is no
+ * `event_type`, and the response token is top-level `interaction_token`
+ * (never `token`, per BB60-20).
+ */
+export const InteractionEventSchema = z.object({
+  /** As GatewayEvent.schema_version; 0 when absent */
+  schema_version: z.number().int().nonnegative().default(0),
+  event_id: z.string().uuid(),
+  shard_id: z.number().int().nonnegative(),
+  timestamp: z.number().int().nonnegative(),
+  interaction_id: z.string(),
+  interaction_token: z.string(),
+  guild_id: z.string().nullable(),
+  channel_id: z.string().nullable(),
+  user_id: z.string(),
+  /** Application command name; null for other interaction types */
+  command_name: z.string().nullable(),
+  /** Subcommand, prefixed by its group if any ("roles add") */
+  subcommand: z.string().nullable(),
+  data: z.object({
+    interaction_type: z.string(),
+    custom_id: z.string().optional(),
+    message_id: z.string().optional(),
+  }),
+});
+
+export type InteractionEvent = z.infer<typeof InteractionEventSchema>;